@@ -4,10 +4,12 @@ use async_trait::async_trait;
 #[cfg(feature = "record")]
 use bytes::Bytes;
 
+#[cfg(feature = "https")]
+use crate::server::ca_certificate_pem;
 use crate::{
     api::adapter::{
         MockServerAdapter, ServerAdapterError,
-        ServerAdapterError::{MockNotFound, UpstreamError},
+        ServerAdapterError::{InvalidMockDefinitionError, MockNotFound, UpstreamError},
     },
     common::data::{
         ActiveForwardingRule, ActiveMock, ActiveProxyRule, ActiveRecording, ClosestMatch, ForwardingRuleConfig,
@@ -30,6 +32,19 @@ impl LocalMockServerAdapter {
     }
 }
 
+/// Maps errors that are caused by an invalid mock or rule definition to
+/// [InvalidMockDefinitionError], like the remote adapter does for definitions that the server
+/// rejects. All other errors are mapped to [UpstreamError].
+fn to_adapter_error(err: state::Error) -> ServerAdapterError {
+    match err {
+        state::Error::ValidationError(_)
+        | state::Error::UnregisteredFunction(_, _)
+        | state::Error::ScriptError(_)
+        | state::Error::BodyMethodInvalid => InvalidMockDefinitionError(err.to_string()),
+        err => UpstreamError(err.to_string()),
+    }
+}
+
 #[async_trait]
 impl MockServerAdapter for LocalMockServerAdapter {
     fn host(&self) -> String {
@@ -50,10 +65,7 @@ impl MockServerAdapter for LocalMockServerAdapter {
     }
 
     async fn create_mock(&self, mock: &MockDefinition) -> Result<ActiveMock, ServerAdapterError> {
-        let active_mock = self
-            .state
-            .add_mock(mock.clone(), false)
-            .map_err(|e| UpstreamError(e.to_string()))?;
+        let active_mock = self.state.add_mock(mock.clone(), false).map_err(to_adapter_error)?;
         Ok(active_mock)
     }
 
@@ -61,11 +73,15 @@ impl MockServerAdapter for LocalMockServerAdapter {
         let mock = self
             .state
             .read_mock(mock_id)
-            .map_err(|e| UpstreamError(e.to_string()))?
+            .map_err(to_adapter_error)?
             .ok_or(MockNotFound(mock_id))?;
         Ok(mock)
     }
 
+    async fn fetch_mocks(&self) -> Result<Vec<ActiveMock>, ServerAdapterError> {
+        Ok(self.state.read_mocks())
+    }
+
     async fn delete_mock(&self, mock_id: usize) -> Result<(), ServerAdapterError> {
         self.state
             .delete_mock(mock_id)
@@ -85,7 +101,7 @@ impl MockServerAdapter for LocalMockServerAdapter {
         &self,
         config: ForwardingRuleConfig,
     ) -> Result<ActiveForwardingRule, ServerAdapterError> {
-        Ok(self.state.create_forwarding_rule(config))
+        self.state.create_forwarding_rule(config).map_err(to_adapter_error)
     }
 
     async fn delete_forwarding_rule(&self, id: usize) -> Result<(), ServerAdapterError> {
@@ -94,7 +110,7 @@ impl MockServerAdapter for LocalMockServerAdapter {
     }
 
     async fn create_proxy_rule(&self, config: ProxyRuleConfig) -> Result<ActiveProxyRule, ServerAdapterError> {
-        Ok(self.state.create_proxy_rule(config))
+        self.state.create_proxy_rule(config).map_err(to_adapter_error)
     }
 
     async fn delete_proxy_rule(&self, id: usize) -> Result<(), ServerAdapterError> {
@@ -103,7 +119,7 @@ impl MockServerAdapter for LocalMockServerAdapter {
     }
 
     async fn create_recording(&self, config: RecordingRuleConfig) -> Result<ActiveRecording, ServerAdapterError> {
-        Ok(self.state.create_recording(config))
+        self.state.create_recording(config).map_err(to_adapter_error)
     }
 
     async fn delete_recording(&self, id: usize) -> Result<(), ServerAdapterError> {
@@ -111,6 +127,14 @@ impl MockServerAdapter for LocalMockServerAdapter {
         Ok(())
     }
 
+    #[cfg(feature = "https")]
+    async fn ca_certificate(&self) -> Result<Option<String>, ServerAdapterError> {
+        // Local servers are always started without a custom CA, so they use the CA of this process.
+        ca_certificate_pem()
+            .map(|cert| Some(cert.to_string()))
+            .map_err(|err| UpstreamError(err.to_string()))
+    }
+
     #[cfg(feature = "record")]
     async fn export_recording(&self, id: usize) -> Result<Option<Bytes>, ServerAdapterError> {
         Ok(self
@@ -127,6 +151,6 @@ impl MockServerAdapter for LocalMockServerAdapter {
         Ok(self
             .state
             .load_mocks_from_recording(recording_file_content)
-            .map_err(|err| UpstreamError(err.to_string()))?)
+            .map_err(to_adapter_error)?)
     }
 }
@@ -42,6 +42,14 @@ pub trait MockServerAdapter {
 
     async fn create_mock(&self, mock: &MockDefinition) -> Result<ActiveMock, ServerAdapterError>;
     async fn fetch_mock(&self, mock_id: usize) -> Result<ActiveMock, ServerAdapterError>;
+
+    /// Fetches all mocks of the server. Adapters that do not support this return an error.
+    async fn fetch_mocks(&self) -> Result<Vec<ActiveMock>, ServerAdapterError> {
+        Err(ServerAdapterError::UpstreamError(
+            "fetching all mocks is not supported by this adapter".to_string(),
+        ))
+    }
+
     async fn delete_mock(&self, mock_id: usize) -> Result<(), ServerAdapterError>;
 
     async fn verify(&self, rr: &RequestRequirements) -> Result<Option<ClosestMatch>, ServerAdapterError>;
@@ -58,6 +66,15 @@ pub trait MockServerAdapter {
     async fn create_recording(&self, mock: RecordingRuleConfig) -> Result<ActiveRecording, ServerAdapterError>;
     async fn delete_recording(&self, id: usize) -> Result<(), ServerAdapterError>;
 
+    /// Fetches the PEM encoded CA certificate the server signs its TLS certificates with.
+    /// Adapters that do not support this return an error.
+    #[cfg(feature = "https")]
+    async fn ca_certificate(&self) -> Result<Option<String>, ServerAdapterError> {
+        Err(ServerAdapterError::UpstreamError(
+            "fetching the CA certificate is not supported by this adapter".to_string(),
+        ))
+    }
+
     #[cfg(feature = "record")]
     async fn export_recording(&self, id: usize) -> Result<Option<Bytes>, ServerAdapterError>;
 
@@ -19,6 +19,7 @@ use crate::{
         data::{
             ActiveForwardingRule, ActiveMock, ActiveProxyRule, ActiveRecording, ClosestMatch, ForwardingRuleConfig,
             MockDefinition, MockServerHttpResponse, ProxyRuleConfig, RecordingRuleConfig, RequestRequirements,
+            ResponseTransform,
         },
         http::HttpClient,
     },
@@ -35,21 +36,43 @@ impl RemoteMockServerAdapter {
     }
 
     fn validate_request_requirements(&self, requirements: &RequestRequirements) -> Result<(), ServerAdapterError> {
-        match requirements.is_true {
-            Some(_) => Err(InvalidMockDefinitionError(
-                "Anonymous function request matchers are not supported when using a remote mock server".to_string(),
-            )),
-            None => Ok(()),
+        if requirements.is_true.is_some() || requirements.is_false.is_some() {
+            return Err(InvalidMockDefinitionError(
+                "Anonymous function request matchers are not supported when using a remote mock server. \
+                 Register a named predicate on the server and use When::predicate instead"
+                    .to_string(),
+            ));
         }
+
+        Ok(())
     }
 
     fn validate_response(&self, response: &MockServerHttpResponse) -> Result<(), ServerAdapterError> {
-        match response.respond_with {
-            Some(_) => Err(InvalidMockDefinitionError(
-                "Dynamic responders are not supported by remote/standalone servers".to_string(),
-            )),
-            None => Ok(()),
+        if response.respond_with.is_some() {
+            return Err(InvalidMockDefinitionError(
+                "Dynamic responders are not supported by remote/standalone servers. \
+                 Register a named responder on the server and use Then::responder instead"
+                    .to_string(),
+            ));
+        }
+
+        if response.wait_for.is_some() {
+            return Err(InvalidMockDefinitionError(
+                "Response gates are not supported by remote/standalone servers".to_string(),
+            ));
         }
+
+        Ok(())
+    }
+
+    fn validate_response_transform(&self, transform: &ResponseTransform) -> Result<(), ServerAdapterError> {
+        if transform.map_response.is_some() {
+            return Err(InvalidMockDefinitionError(
+                "Response mapping closures are not supported by remote/standalone servers".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 
     /// Builds a request against the `__httpmock__` API. When `json_body` is
@@ -187,6 +210,11 @@ impl MockServerAdapter for RemoteMockServerAdapter {
         .await
     }
 
+    async fn fetch_mocks(&self) -> Result<Vec<ActiveMock>, ServerAdapterError> {
+        self.request_json("GET", "mocks", None, StatusCode::OK, "fetch mocks from the mock server")
+            .await
+    }
+
     async fn delete_mock(&self, mock_id: usize) -> Result<(), ServerAdapterError> {
         self.request_empty(
             "DELETE",
@@ -225,6 +253,13 @@ impl MockServerAdapter for RemoteMockServerAdapter {
         config: ForwardingRuleConfig,
     ) -> Result<ActiveForwardingRule, ServerAdapterError> {
         self.validate_request_requirements(&config.request_requirements)?;
+        self.validate_response_transform(&config.response_transform)?;
+
+        if config.request_transform.transform_json_body.is_some() {
+            return Err(InvalidMockDefinitionError(
+                "Request body transform closures are not supported by remote/standalone servers".to_string(),
+            ));
+        }
 
         let json = serde_json::to_string(&config).map_err(JsonSerializationError)?;
 
@@ -251,6 +286,7 @@ impl MockServerAdapter for RemoteMockServerAdapter {
 
     async fn create_proxy_rule(&self, config: ProxyRuleConfig) -> Result<ActiveProxyRule, ServerAdapterError> {
         self.validate_request_requirements(&config.request_requirements)?;
+        self.validate_response_transform(&config.response_transform)?;
 
         let json = serde_json::to_string(&config).map_err(JsonSerializationError)?;
 
@@ -301,6 +337,25 @@ impl MockServerAdapter for RemoteMockServerAdapter {
         .await
     }
 
+    #[cfg(feature = "https")]
+    async fn ca_certificate(&self) -> Result<Option<String>, ServerAdapterError> {
+        let request = self.build_request("GET", "ca_certificate", None)?;
+
+        let (status, body) = self.do_request_raw(request).await?;
+
+        // Servers that generate no certificates (or don't support HTTPS) respond with 404.
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        } else if status != StatusCode::OK.as_u16() {
+            return Err(UpstreamError(format!(
+                "Could not fetch CA certificate from the mock server. Expected response status 200 but was {}",
+                status
+            )));
+        }
+
+        serde_json::from_slice(&body).map_err(JsonDeserializationError)
+    }
+
     #[cfg(feature = "record")]
     async fn export_recording(&self, id: usize) -> Result<Option<Bytes>, ServerAdapterError> {
         let request = self.build_request("GET", &format!("recordings/{}", id), None)?;
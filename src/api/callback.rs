@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{Method, common::data::CallbackDefinition};
+
+/// Configures an outgoing HTTP request (e.g., a webhook) that the mock server sends after it
+/// has served a mock. Use it with [Then::callback](struct.Then.html#method.callback).
+///
+/// The URL, header values and body may contain placeholders that are filled in from the
+/// request that triggered the callback:
+/// - `{{request.method}}`, `{{request.scheme}}`, `{{request.uri}}`, `{{request.path}}`
+///   and `{{request.body}}`,
+/// - `{{request.header.<name>}}` for the value of a request header,
+/// - `{{request.query.<name>}}` for the value of a query parameter,
+/// - `{{request.json.<path>}}` for a value in a JSON request body, where `<path>` is a
+///   dot-separated list of object keys and array indices (e.g., `{{request.json.items.0.id}}`).
+///
+/// Unknown placeholders are replaced with an empty string. Values inserted into the URL are
+/// percent-encoded, except for `request.scheme`, `request.uri` and `request.path`.
+#[derive(Default)]
+pub struct CallbackBuilder {
+    pub(crate) definition: CallbackDefinition,
+}
+
+impl CallbackBuilder {
+    /// Sets the HTTP method of the callback request. Defaults to `POST`.
+    ///
+    /// # Parameters
+    /// - `method`: The HTTP method (e.g., `POST`, `"PUT"`).
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    pub fn method<IntoMethod: Into<Method>>(mut self, method: IntoMethod) -> Self {
+        self.definition.method = Some(method.into().to_string());
+        self
+    }
+
+    /// Sets the absolute URL the callback request is sent to. The URL may contain placeholders.
+    ///
+    /// # Parameters
+    /// - `url`: The target URL (e.g., `"http://localhost:8080/webhooks/{{request.json.id}}"`).
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    pub fn url<IntoString: Into<String>>(mut self, url: IntoString) -> Self {
+        self.definition.url = Some(url.into());
+        self
+    }
+
+    /// Adds a header to the callback request. The value may contain placeholders.
+    ///
+    /// # Parameters
+    /// - `name`: The header name.
+    /// - `value`: The header value.
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    pub fn header<Key: Into<String>, Value: Into<String>>(mut self, name: Key, value: Value) -> Self {
+        self.definition
+            .headers
+            .get_or_insert_with(Vec::new)
+            .push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body of the callback request. The body may contain placeholders.
+    ///
+    /// # Parameters
+    /// - `body`: The request body.
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    pub fn body<IntoString: Into<String>>(mut self, body: IntoString) -> Self {
+        self.definition.body = Some(body.into());
+        self.definition.json_body = None;
+        self
+    }
+
+    /// Sets a JSON body for the callback request and adds a `Content-Type: application/json`
+    /// header. String values in the JSON document may contain placeholders. Placeholder values
+    /// are JSON-escaped, so the body stays valid JSON whatever the triggering request contains.
+    ///
+    /// # Parameters
+    /// - `body`: A value that can be serialized to JSON.
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    ///
+    /// # Panics
+    /// Panics if the value cannot be serialized to JSON.
+    pub fn json_body<T: Serialize>(mut self, body: T) -> Self {
+        let body = serde_json::to_value(&body).expect("cannot serialize callback body to JSON");
+        self.definition.json_body = Some(body);
+        self.definition.body = None;
+        self.header("content-type", "application/json")
+    }
+
+    /// Sets a delay between serving the mock and sending the callback request.
+    ///
+    /// # Parameters
+    /// - `duration`: The delay.
+    ///
+    /// # Returns
+    /// The modified `CallbackBuilder` for method chaining.
+    pub fn delay<D: Into<Duration>>(mut self, duration: D) -> Self {
+        self.definition.delay = Some(duration.into().as_millis() as u64);
+        self
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use crate::{api::MockServerAdapter, common::util::write_file};
+
+/// Determines how a cassette (see [MockServer::cassette](struct.MockServer.html#method.cassette))
+/// uses its file and the upstream server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Forwards all requests to the upstream server and records them. The cassette file is
+    /// overwritten with the recorded interactions when the mock server is dropped.
+    Record,
+    /// Replays the interactions from the cassette file. Requests that are not part of the
+    /// cassette are not forwarded. The cassette file is not modified.
+    Replay,
+    /// Replays the interactions from the cassette file (if it exists) and forwards and records
+    /// all other requests. Newly recorded interactions are added to the cassette file when the
+    /// mock server is dropped.
+    RecordMissing,
+    /// Forwards all requests to the upstream server. The cassette file is neither read nor written.
+    Passthrough,
+}
+
+/// A cassette that is saved when the mock server is dropped.
+pub(crate) struct ActiveCassette {
+    pub path: PathBuf,
+    pub recording_id: usize,
+    /// The content of the cassette file when the cassette was inserted, if newly recorded
+    /// interactions are added to it.
+    pub existing_content: Option<String>,
+}
+
+impl ActiveCassette {
+    /// Writes the recorded interactions (plus the existing ones, if any) to the cassette file.
+    pub(crate) async fn save(
+        &self,
+        adapter: &(dyn MockServerAdapter + Send + Sync),
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let recorded = adapter
+            .export_recording(self.recording_id)
+            .await?
+            .ok_or("the recording of the cassette could not be found on the mock server")?;
+
+        let mut content = self.existing_content.clone().unwrap_or_default();
+        if !content.trim().is_empty() && !recorded.is_empty() {
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str("---\n");
+        }
+        content.push_str(&String::from_utf8_lossy(&recorded));
+
+        write_file(&self.path, &content.into(), true).await
+    }
+}
@@ -1,9 +1,13 @@
 #[cfg(feature = "remote")]
 pub use adapter::remote::RemoteMockServerAdapter;
 pub use adapter::{MockServerAdapter, local::LocalMockServerAdapter};
+#[cfg(feature = "proxy")]
+pub use callback::CallbackBuilder;
+#[cfg(feature = "record")]
+pub use cassette::CassetteMode;
 pub use mock::{Mock, MockExt};
 #[cfg(feature = "proxy")]
-pub use proxy::{ForwardingRule, ForwardingRuleBuilder, ProxyRule, ProxyRuleBuilder};
+pub use proxy::{ForwardingRule, ForwardingRuleBuilder, IntoForwardingTargets, ProxyRule, ProxyRuleBuilder};
 #[cfg(feature = "record")]
 pub use proxy::{Recording, RecordingRuleBuilder};
 pub use server::MockServer;
@@ -12,6 +16,10 @@ pub use spec::{Then, When};
 use crate::common;
 
 mod adapter;
+#[cfg(feature = "proxy")]
+mod callback;
+#[cfg(feature = "record")]
+mod cassette;
 mod mock;
 mod output;
 mod proxy;
@@ -22,3 +30,7 @@ pub mod spec;
 pub type Regex = common::data::HttpMockRegex;
 
 pub use crate::common::data::Method;
+#[cfg(feature = "record")]
+pub use crate::common::data::{BodyMatchMode, RepeatedRequests};
+#[cfg(feature = "proxy")]
+pub use crate::common::data::{Fault, ForwardingTarget, LoadBalance};
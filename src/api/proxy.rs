@@ -1,20 +1,24 @@
 #[cfg(feature = "record")]
 use std::path::{Path, PathBuf};
-use std::{cell::Cell, rc::Rc};
+use std::{cell::Cell, rc::Rc, sync::Arc, time::Duration};
 
 #[cfg(feature = "record")]
 use bytes::Bytes;
 
-#[cfg(feature = "record")]
-use crate::common::util::write_file;
 use crate::{
     When,
     api::server::MockServer,
     common::{
-        data::{RecordingRuleConfig, RequestRequirements},
+        data::{
+            BodyMatchMode, Fault, ForwardingRuleConfig, ForwardingTarget, HttpMockRegex, HttpMockRequest,
+            HttpMockResponse, LoadBalance, ProxyRuleConfig, RecordingRuleConfig, RepeatedRequests,
+        },
         util::Join,
     },
+    server::recording::VOLATILE_RESPONSE_HEADERS,
 };
+#[cfg(feature = "record")]
+use crate::{common::util::write_file, server::har};
 
 /// Represents a forwarding rule on a [MockServer](struct.MockServer.html), allowing HTTP requests
 /// that meet specific criteria to be redirected to a designated destination. Each rule is
@@ -224,20 +228,7 @@ impl<'a> Recording<'a> {
         scenario: IntoString,
     ) -> Result<PathBuf, Box<dyn std::error::Error>> {
         let rec = self.export_async().await?;
-
-        let scenario = scenario.into();
-        let dir = dir.as_ref();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_secs();
-        let filename = format!("{}_{}.yaml", scenario, timestamp);
-        let filepath = dir.join(filename);
-
-        if let Some(bytes) = rec {
-            return write_file(&filepath, &bytes, true).await;
-        }
-
-        Err("No recording data available".into())
+        write_recording(dir.as_ref(), scenario.into(), "yaml", rec).await
     }
 
     /// Synchronously saves the recording to the default directory (`target/httpmock/recordings`) with the scenario name.
@@ -267,24 +258,208 @@ impl<'a> Recording<'a> {
         &self,
         scenario: IntoString,
     ) -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("target")
-            .join("httpmock")
-            .join("recordings");
-        self.save_to_async(path, scenario).await
+        self.save_to_async(default_recording_dir(), scenario).await
+    }
+
+    /// Synchronously export the recording as an HTTP Archive (HAR 1.2) document.
+    ///
+    /// Parts of the request URLs that were not recorded (e.g., the host of requests that
+    /// were forwarded to another server) are filled in with the mock server's address.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the JSON encoded HAR document as `Option<Bytes>` (absent when no recording could be found),
+    /// or an error if the export operation fails.
+    ///
+    /// # Errors
+    /// Errors if the recording cannot be converted or issues with connecting to a remote server.
+    #[cfg(feature = "record")]
+    pub fn export_har(&self) -> Result<Option<Bytes>, Box<dyn std::error::Error>> {
+        self.export_har_async().join()
+    }
+
+    /// Asynchronously export the recording as an HTTP Archive (HAR 1.2) document.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the JSON encoded HAR document as `Option<Bytes>` (absent when no recording could be found),
+    /// or an error if the export operation fails.
+    ///
+    /// # Errors
+    /// Errors if the recording cannot be converted or issues with connecting to a remote server.
+    #[cfg(feature = "record")]
+    pub async fn export_har_async(&self) -> Result<Option<Bytes>, Box<dyn std::error::Error>> {
+        let Some(yaml) = self.export_async().await? else {
+            return Ok(None);
+        };
+
+        let yaml = std::str::from_utf8(&yaml)?;
+        Ok(Some(har::recording_to_har(yaml, &self.server.base_url())?))
+    }
+
+    /// Synchronously saves the recording as an HTTP Archive (HAR 1.2) file to a specified directory
+    /// with a timestamped filename. The file is named using a combination of the provided scenario
+    /// name and a UNIX timestamp, with the extension `.har`.
+    ///
+    /// # Parameters
+    /// - `dir`: The directory path where the file will be saved.
+    /// - `scenario_name`: A descriptive name for the scenario, used as part of the filename.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the `PathBuf` of the created file, or an error if the save operation fails.
+    #[cfg(feature = "record")]
+    pub fn save_har_to<PathRef: AsRef<Path>, IntoString: Into<String>>(
+        &self,
+        dir: PathRef,
+        scenario_name: IntoString,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.save_har_to_async(dir, scenario_name).join()
+    }
+
+    /// Asynchronously saves the recording as an HTTP Archive (HAR 1.2) file to the specified directory.
+    ///
+    /// # Parameters
+    /// - `dir`: The directory path where the file will be saved.
+    /// - `scenario`: A string representing the scenario name, used as part of the filename.
+    ///
+    /// # Returns
+    /// Returns an `async` `Result` with the `PathBuf` of the saved file or an error if unable to save.
+    #[cfg(feature = "record")]
+    pub async fn save_har_to_async<PathRef: AsRef<Path>, IntoString: Into<String>>(
+        &self,
+        dir: PathRef,
+        scenario: IntoString,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let rec = self.export_har_async().await?;
+        write_recording(dir.as_ref(), scenario.into(), "har", rec).await
+    }
+
+    /// Synchronously saves the recording as an HTTP Archive (HAR 1.2) file to the default directory
+    /// (`target/httpmock/recordings`) with the scenario name.
+    ///
+    /// # Parameters
+    /// - `scenario_name`: A descriptive name for the scenario, which helps identify the recording file.
+    ///
+    /// # Returns
+    /// Returns a `Result` with the `PathBuf` to the saved file or an error.
+    #[cfg(feature = "record")]
+    pub fn save_har<IntoString: Into<String>>(
+        &self,
+        scenario_name: IntoString,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.save_har_async(scenario_name).join()
+    }
+
+    /// Asynchronously saves the recording as an HTTP Archive (HAR 1.2) file to the default directory
+    /// structured under `target/httpmock/recordings`.
+    ///
+    /// # Parameters
+    /// - `scenario`: A descriptive name for the test scenario, used in naming the saved file.
+    ///
+    /// # Returns
+    /// Returns an `async` `Result` with the `PathBuf` of the saved file or an error.
+    #[cfg(feature = "record")]
+    pub async fn save_har_async<IntoString: Into<String>>(
+        &self,
+        scenario: IntoString,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.save_har_to_async(default_recording_dir(), scenario).await
+    }
+}
+
+#[cfg(feature = "record")]
+fn default_recording_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("httpmock")
+        .join("recordings")
+}
+
+#[cfg(feature = "record")]
+async fn write_recording(
+    dir: &Path,
+    scenario: String,
+    extension: &str,
+    content: Option<Bytes>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let filepath = dir.join(format!("{}_{}.{}", scenario, timestamp, extension));
+
+    if let Some(bytes) = content {
+        return write_file(&filepath, &bytes, true).await;
+    }
+
+    Err("No recording data available".into())
+}
+
+/// Types that can be passed to [MockServer::forward_to](struct.MockServer.html#method.forward_to)
+/// as the target(s) of a forwarding rule: a single base URL, a list of base URLs (each with
+/// weight 1), or a list of base URLs paired with weights (see [LoadBalance]).
+pub trait IntoForwardingTargets {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget>;
+}
+
+impl IntoForwardingTargets for String {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        vec![ForwardingTarget::new(self, 1)]
+    }
+}
+
+impl IntoForwardingTargets for &String {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        vec![ForwardingTarget::new(self, 1)]
+    }
+}
+
+impl IntoForwardingTargets for &str {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        vec![ForwardingTarget::new(self, 1)]
+    }
+}
+
+impl IntoForwardingTargets for Vec<ForwardingTarget> {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        self
+    }
+}
+
+impl IntoForwardingTargets for Vec<String> {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        self.into_iter().map(|url| ForwardingTarget::new(url, 1)).collect()
+    }
+}
+
+impl IntoForwardingTargets for Vec<&str> {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        self.into_iter().map(|url| ForwardingTarget::new(url, 1)).collect()
+    }
+}
+
+impl IntoForwardingTargets for Vec<(String, u32)> {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        self.into_iter()
+            .map(|(url, weight)| ForwardingTarget::new(url, weight))
+            .collect()
+    }
+}
+
+impl IntoForwardingTargets for Vec<(&str, u32)> {
+    fn into_forwarding_targets(self) -> Vec<ForwardingTarget> {
+        self.into_iter()
+            .map(|(url, weight)| ForwardingTarget::new(url, weight))
+            .collect()
     }
 }
 
 pub struct ForwardingRuleBuilder {
-    pub(crate) request_requirements: Rc<Cell<RequestRequirements>>,
-    pub(crate) headers: Rc<Cell<Vec<(String, String)>>>,
+    pub(crate) config: Rc<Cell<ForwardingRuleConfig>>,
 }
 
 impl ForwardingRuleBuilder {
     pub fn add_request_header<Key: Into<String>, Value: Into<String>>(self, key: Key, value: Value) -> Self {
-        let mut headers = self.headers.take();
-        headers.push((key.into(), value.into()));
-        self.headers.set(headers);
+        let mut config = self.config.take();
+        config.request_header.push((key.into(), value.into()));
+        self.config.set(config);
         self
     }
 
@@ -292,24 +467,314 @@ impl ForwardingRuleBuilder {
     where
         WhenSpecFn: FnOnce(When),
     {
+        let mut config = self.config.take();
+
+        let request_requirements = Rc::new(Cell::new(config.request_requirements));
+
         when(When {
-            expectations: self.request_requirements.clone(),
+            expectations: request_requirements.clone(),
         });
+
+        config.request_requirements = request_requirements.take();
+
+        self.config.set(config);
+
+        self
+    }
+
+    /// Only forwards requests that do not match any mock. By default, requests that match the
+    /// rule are forwarded even if a mock matches as well.
+    pub fn fallback(self, fallback: bool) -> Self {
+        let mut config = self.config.take();
+        config.fallback = fallback;
+        self.config.set(config);
+        self
+    }
+
+    /// Sets how requests are distributed when the rule has several targets (see [LoadBalance]).
+    /// By default, requests are forwarded to the targets in turn.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "proxy")]
+    /// # {
+    /// use httpmock::{LoadBalance, MockServer};
+    /// use reqwest::blocking::Client;
+    ///
+    /// let stable = MockServer::start();
+    /// stable.mock(|when, then| {
+    ///     when.any_request();
+    ///     then.body("stable");
+    /// });
+    ///
+    /// let canary = MockServer::start();
+    /// canary.mock(|when, then| {
+    ///     when.any_request();
+    ///     then.body("canary");
+    /// });
+    ///
+    /// // Forward three out of four requests to the stable version and one to the canary.
+    /// let server = MockServer::start();
+    /// server.forward_to(vec![(stable.base_url(), 3), (canary.base_url(), 1)], |rule| {
+    ///     rule.load_balance(LoadBalance::Weighted);
+    /// });
+    ///
+    /// let client = Client::new();
+    /// let bodies: Vec<String> = (0..4)
+    ///     .map(|_| client.get(server.url("/")).send().unwrap().text().unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(bodies, vec!["stable", "stable", "stable", "canary"]);
+    /// # }
+    /// ```
+    pub fn load_balance(self, strategy: LoadBalance) -> Self {
+        let mut config = self.config.take();
+        config.load_balance = strategy;
+        self.config.set(config);
+        self
+    }
+
+    /// Sets whether a request is forwarded to the next target of the rule if the selected target
+    /// refuses the connection (or cannot be connected to for another reason). Disabled by
+    /// default, in which case the client receives an error response.
+    pub fn failover(self, failover: bool) -> Self {
+        let mut config = self.config.take();
+        config.failover = failover;
+        self.config.set(config);
+        self
+    }
+
+    /// Sends forwarded requests through an HTTP proxy (e.g., `http://proxy.internal:3128`),
+    /// overriding the upstream proxy the mock server was configured with (see
+    /// [HttpMockServerBuilder::upstream_proxy](crate::server::HttpMockServerBuilder::upstream_proxy)).
+    /// HTTPS targets are reached through a `CONNECT` tunnel. Credentials in the URL are sent
+    /// using basic authentication.
+    pub fn upstream_proxy<IntoString: Into<String>>(self, url: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.upstream_proxy = Some(url.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Delays every response to forwarded requests by the given duration (e.g., to test how clients cope
+    /// with a slow upstream server). Durations of more than `u64::MAX` milliseconds are capped.
+    pub fn delay<D: Into<Duration>>(self, duration: D) -> Self {
+        let millis = u64::try_from(duration.into().as_millis()).unwrap_or(u64::MAX);
+        let mut config = self.config.take();
+        config.faults.delay = Some(millis);
+        self.config.set(config);
+        self
+    }
+
+    /// Returns `response` instead of the upstream response for a share of the requests. The
+    /// requests are not sent upstream.
+    ///
+    /// # Parameters
+    /// - `probability`: The probability (between 0 and 1) with which a request fails.
+    /// - `response`: The response to return instead of the upstream response.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "proxy")]
+    /// # {
+    /// use httpmock::prelude::*;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let target = MockServer::start();
+    /// target.mock(|when, then| {
+    ///     when.any_request();
+    ///     then.status(200);
+    /// });
+    ///
+    /// // Fail every request (use e.g. 0.1 to fail one in ten requests).
+    /// let server = MockServer::start();
+    /// server.forward_to(target.base_url(), |rule| {
+    ///     rule.error_rate(1.0, HttpMockResponse::builder().status(503).build());
+    /// });
+    ///
+    /// let response = Client::new().get(server.url("/")).send().unwrap();
+    /// assert_eq!(response.status(), 503);
+    /// # }
+    /// ```
+    pub fn error_rate(self, probability: f64, response: HttpMockResponse) -> Self {
+        let mut config = self.config.take();
+        config.faults.error_rate = probability;
+        config.faults.error_response = Some(response);
+        self.config.set(config);
+        self
+    }
+
+    /// Injects a network fault (see [Fault]) into a share of the responses. Can be called
+    /// multiple times to inject different faults.
+    ///
+    /// # Parameters
+    /// - `probability`: The probability (between 0 and 1) with which the fault occurs.
+    /// - `fault`: The fault to inject.
+    pub fn fault(self, probability: f64, fault: Fault) -> Self {
+        let mut config = self.config.take();
+        config.faults.faults.push((probability, fault));
+        self.config.set(config);
+        self
+    }
+
+    /// Seeds the random number generator that decides which faults are injected (see
+    /// [ForwardingRuleBuilder::error_rate] and [ForwardingRuleBuilder::fault]) and, with
+    /// [LoadBalance::Random], which target a request is forwarded to. A rule with a seed
+    /// behaves the same way in every test run. By default, the generator is seeded randomly.
+    ///
+    /// # Parameters
+    /// - `seed`: The seed of the random number generator.
+    pub fn random_seed(self, seed: u64) -> Self {
+        let mut config = self.config.take();
+        config.random_seed = Some(seed);
+        self.config.set(config);
+        self
+    }
+
+    /// Removes a header (case-insensitive) from requests before they are forwarded. Headers are
+    /// removed before headers are added by [ForwardingRuleBuilder::add_request_header], so both
+    /// can be combined to replace a header (e.g., `authorization`).
+    pub fn remove_request_header<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.request_transform.remove_header.push(name.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Removes `prefix` from the path of requests before they are forwarded, e.g.,
+    /// `strip_path_prefix("/api")` forwards `/api/v2/users` to `/v2/users`. Paths that do not
+    /// start with `prefix` are forwarded unchanged. The prefix is stripped before paths are
+    /// rewritten by [ForwardingRuleBuilder::rewrite_path].
+    pub fn strip_path_prefix<IntoString: Into<String>>(self, prefix: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.request_transform.strip_path_prefix = Some(prefix.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Replaces all parts of the request path that match `regex` with `replacement` (which may
+    /// refer to capture groups, e.g., `$1`) before requests are forwarded. The query string is
+    /// not part of the path.
+    pub fn rewrite_path<IntoRegex: Into<HttpMockRegex>, IntoString: Into<String>>(
+        self,
+        regex: IntoRegex,
+        replacement: IntoString,
+    ) -> Self {
+        let mut config = self.config.take();
+        config
+            .request_transform
+            .rewrite_path
+            .push((regex.into(), replacement.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Sets a query parameter on requests before they are forwarded. Existing values of the
+    /// parameter are replaced.
+    pub fn set_query_param<Name: Into<String>, Value: Into<String>>(self, name: Name, value: Value) -> Self {
+        let mut config = self.config.take();
+        config
+            .request_transform
+            .set_query_param
+            .push((name.into(), value.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Modifies the JSON bodies of requests with a closure before they are forwarded. Bodies
+    /// that are not JSON are forwarded unchanged.
+    ///
+    /// > **Important:** This is only supported by the local server. Creating the rule on a
+    /// > remote/standalone server fails.
+    pub fn transform_json_body<F>(self, f: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        let mut config = self.config.take();
+        config.request_transform.transform_json_body = Some(Arc::new(f));
+        self.config.set(config);
+        self
+    }
+
+    /// Adds a header to responses received from the upstream server. Existing headers with the
+    /// same name are kept (see [ForwardingRuleBuilder::remove_response_header]).
+    pub fn add_response_header<Key: Into<String>, Value: Into<String>>(self, key: Key, value: Value) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.add_header.push((key.into(), value.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Removes a header (case-insensitive) from responses received from the upstream server.
+    /// Headers are removed before headers are added by [ForwardingRuleBuilder::add_response_header].
+    pub fn remove_response_header<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.remove_header.push(name.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Replaces the status code of responses received from the upstream server.
+    pub fn override_status(self, status: u16) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.status = Some(status);
+        self.config.set(config);
+        self
+    }
+
+    /// Replaces all text that matches `regex` in the bodies of responses received from the
+    /// upstream server with `replacement` (which may refer to capture groups, e.g., `$1`).
+    /// Bodies that are not valid UTF-8 are not modified.
+    pub fn replace_body_regex<IntoRegex: Into<HttpMockRegex>, IntoString: Into<String>>(
+        self,
+        regex: IntoRegex,
+        replacement: IntoString,
+    ) -> Self {
+        let mut config = self.config.take();
+        config
+            .response_transform
+            .replace_body_regex
+            .push((regex.into(), replacement.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Applies a JSON merge patch (RFC 7396) to the JSON bodies of responses received from the
+    /// upstream server, e.g., to add a field (`{"error": "timeout"}`) or to remove one
+    /// (`{"items": null}`). Bodies that are not JSON are not modified.
+    pub fn patch_json_body<IntoValue: Into<serde_json::Value>>(self, json_merge_patch: IntoValue) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.json_merge_patch.push(json_merge_patch.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Modifies responses received from the upstream server with a closure. The closure receives
+    /// the request and the response (after all other response modifications were applied) and
+    /// returns the response that is sent to the client.
+    ///
+    /// > **Important:** Like [Then::respond_with](struct.Then.html#method.respond_with), this is
+    /// > only supported by the local server. Creating the rule on a remote/standalone server fails.
+    pub fn map_response<F>(self, f: F) -> Self
+    where
+        F: Fn(&HttpMockRequest, HttpMockResponse) -> HttpMockResponse + Send + Sync + 'static,
+    {
+        let mut config = self.config.take();
+        config.response_transform.map_response = Some(Arc::new(f));
+        self.config.set(config);
         self
     }
 }
 
 pub struct ProxyRuleBuilder {
-    // TODO: These fields are visible to the user, make them not public
-    pub(crate) request_requirements: Rc<Cell<RequestRequirements>>,
-    pub(crate) headers: Rc<Cell<Vec<(String, String)>>>,
+    pub(crate) config: Rc<Cell<ProxyRuleConfig>>,
 }
 
 impl ProxyRuleBuilder {
     pub fn add_request_header<Key: Into<String>, Value: Into<String>>(self, key: Key, value: Value) -> Self {
-        let mut headers = self.headers.take();
-        headers.push((key.into(), value.into()));
-        self.headers.set(headers);
+        let mut config = self.config.take();
+        config.request_header.push((key.into(), value.into()));
+        self.config.set(config);
         self
     }
 
@@ -317,10 +782,201 @@ impl ProxyRuleBuilder {
     where
         WhenSpecFn: FnOnce(When),
     {
+        let mut config = self.config.take();
+
+        let request_requirements = Rc::new(Cell::new(config.request_requirements));
+
         when(When {
-            expectations: self.request_requirements.clone(),
+            expectations: request_requirements.clone(),
         });
 
+        config.request_requirements = request_requirements.take();
+
+        self.config.set(config);
+
+        self
+    }
+
+    /// Turns this rule into a passthrough rule for `CONNECT` tunnels: instead of intercepting the
+    /// TLS traffic inside the tunnel (using certificates generated by the mock server), the
+    /// bytes are passed through to the target host unmodified. This is useful for clients that
+    /// pin certificates or for hosts whose traffic should not be intercepted.
+    ///
+    /// By default, all tunnels are passed through. Use [ProxyRuleBuilder::tunnel_allow_host]
+    /// and [ProxyRuleBuilder::tunnel_deny_host] to select hosts. A passthrough rule only applies
+    /// to `CONNECT` requests, so the filter and request/response modifications of this rule
+    /// have no effect.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "proxy")]
+    /// # {
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// // Intercept all tunnels except the ones to GitHub.
+    /// server.proxy(|rule| {
+    ///     rule.passthrough_tunnel()
+    ///         .tunnel_allow_host("github.com")
+    ///         .tunnel_allow_host("*.github.com");
+    /// });
+    /// # }
+    /// ```
+    pub fn passthrough_tunnel(self) -> Self {
+        let mut config = self.config.take();
+        config.passthrough_tunnel.enabled = true;
+        self.config.set(config);
+        self
+    }
+
+    /// Only passes through `CONNECT` tunnels to hosts that match one of the allowed host patterns
+    /// (see [ProxyRuleBuilder::passthrough_tunnel]). A pattern is either a host name
+    /// (e.g., `example.com`) or a wildcard for all subdomains (e.g., `*.example.com`).
+    pub fn tunnel_allow_host<IntoString: Into<String>>(self, pattern: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.passthrough_tunnel.allow_hosts.push(pattern.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Never passes through `CONNECT` tunnels to hosts that match this host pattern (see
+    /// [ProxyRuleBuilder::tunnel_allow_host]), even if they are allowed.
+    pub fn tunnel_deny_host<IntoString: Into<String>>(self, pattern: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.passthrough_tunnel.deny_hosts.push(pattern.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Sends proxied requests through another HTTP proxy (e.g., `http://proxy.internal:3128`),
+    /// overriding the upstream proxy the mock server was configured with (see
+    /// [HttpMockServerBuilder::upstream_proxy](crate::server::HttpMockServerBuilder::upstream_proxy)).
+    /// HTTPS requests are sent through a `CONNECT` tunnel. Credentials in the URL are sent
+    /// using basic authentication.
+    pub fn upstream_proxy<IntoString: Into<String>>(self, url: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.upstream_proxy = Some(url.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Delays every response to proxied requests by the given duration (e.g., to test how clients cope
+    /// with a slow upstream server). Durations of more than `u64::MAX` milliseconds are capped.
+    pub fn delay<D: Into<Duration>>(self, duration: D) -> Self {
+        let millis = u64::try_from(duration.into().as_millis()).unwrap_or(u64::MAX);
+        let mut config = self.config.take();
+        config.faults.delay = Some(millis);
+        self.config.set(config);
+        self
+    }
+
+    /// Returns `response` instead of the upstream response for a share of the requests. The
+    /// requests are not sent upstream.
+    ///
+    /// # Parameters
+    /// - `probability`: The probability (between 0 and 1) with which a request fails.
+    /// - `response`: The response to return instead of the upstream response.
+    pub fn error_rate(self, probability: f64, response: HttpMockResponse) -> Self {
+        let mut config = self.config.take();
+        config.faults.error_rate = probability;
+        config.faults.error_response = Some(response);
+        self.config.set(config);
+        self
+    }
+
+    /// Injects a network fault (see [Fault]) into a share of the responses. Can be called
+    /// multiple times to inject different faults.
+    ///
+    /// # Parameters
+    /// - `probability`: The probability (between 0 and 1) with which the fault occurs.
+    /// - `fault`: The fault to inject.
+    pub fn fault(self, probability: f64, fault: Fault) -> Self {
+        let mut config = self.config.take();
+        config.faults.faults.push((probability, fault));
+        self.config.set(config);
+        self
+    }
+
+    /// Seeds the random number generator that decides which faults are injected (see
+    /// [ProxyRuleBuilder::error_rate] and [ProxyRuleBuilder::fault]). A rule with a seed behaves
+    /// the same way in every test run. By default, the generator is seeded randomly.
+    ///
+    /// # Parameters
+    /// - `seed`: The seed of the random number generator.
+    pub fn random_seed(self, seed: u64) -> Self {
+        let mut config = self.config.take();
+        config.random_seed = Some(seed);
+        self.config.set(config);
+        self
+    }
+
+    /// Adds a header to responses received from the upstream server. Existing headers with the
+    /// same name are kept (see [ProxyRuleBuilder::remove_response_header]).
+    pub fn add_response_header<Key: Into<String>, Value: Into<String>>(self, key: Key, value: Value) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.add_header.push((key.into(), value.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Removes a header (case-insensitive) from responses received from the upstream server.
+    /// Headers are removed before headers are added by [ProxyRuleBuilder::add_response_header].
+    pub fn remove_response_header<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.remove_header.push(name.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Replaces the status code of responses received from the upstream server.
+    pub fn override_status(self, status: u16) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.status = Some(status);
+        self.config.set(config);
+        self
+    }
+
+    /// Replaces all text that matches `regex` in the bodies of responses received from the
+    /// upstream server with `replacement` (which may refer to capture groups, e.g., `$1`).
+    /// Bodies that are not valid UTF-8 are not modified.
+    pub fn replace_body_regex<IntoRegex: Into<HttpMockRegex>, IntoString: Into<String>>(
+        self,
+        regex: IntoRegex,
+        replacement: IntoString,
+    ) -> Self {
+        let mut config = self.config.take();
+        config
+            .response_transform
+            .replace_body_regex
+            .push((regex.into(), replacement.into()));
+        self.config.set(config);
+        self
+    }
+
+    /// Applies a JSON merge patch (RFC 7396) to the JSON bodies of responses received from the
+    /// upstream server, e.g., to add a field (`{"error": "timeout"}`) or to remove one
+    /// (`{"items": null}`). Bodies that are not JSON are not modified.
+    pub fn patch_json_body<IntoValue: Into<serde_json::Value>>(self, json_merge_patch: IntoValue) -> Self {
+        let mut config = self.config.take();
+        config.response_transform.json_merge_patch.push(json_merge_patch.into());
+        self.config.set(config);
+        self
+    }
+
+    /// Modifies responses received from the upstream server with a closure. The closure receives
+    /// the request and the response (after all other response modifications were applied) and
+    /// returns the response that is sent to the client.
+    ///
+    /// > **Important:** Like [Then::respond_with](struct.Then.html#method.respond_with), this is
+    /// > only supported by the local server. Creating the rule on a remote/standalone server fails.
+    pub fn map_response<F>(self, f: F) -> Self
+    where
+        F: Fn(&HttpMockRequest, HttpMockResponse) -> HttpMockResponse + Send + Sync + 'static,
+    {
+        let mut config = self.config.take();
+        config.response_transform.map_response = Some(Arc::new(f));
+        self.config.set(config);
         self
     }
 }
@@ -370,4 +1026,240 @@ impl RecordingRuleBuilder {
 
         self
     }
+
+    /// Records only the response headers in `headers` (case-insensitive). By default, all
+    /// response headers are recorded.
+    pub fn record_response_headers<IntoString: Into<String>>(self, headers: Vec<IntoString>) -> Self {
+        let mut config = self.config.take();
+        config
+            .record_response_headers
+            .get_or_insert_with(Vec::new)
+            .extend(headers.into_iter().map(Into::into));
+        self.config.set(config);
+
+        self
+    }
+
+    /// Does not record the response headers in `headers` (case-insensitive).
+    pub fn drop_response_headers<IntoString: Into<String>>(self, headers: Vec<IntoString>) -> Self {
+        let mut config = self.config.take();
+        config.drop_response_headers.extend(headers.into_iter().map(Into::into));
+        self.config.set(config);
+
+        self
+    }
+
+    /// Replaces the value of a recorded response header (case-insensitive) with `value`.
+    pub fn normalize_response_header<Name: Into<String>, Value: Into<String>>(self, name: Name, value: Value) -> Self {
+        let mut config = self.config.take();
+        config.normalize_response_headers.push((name.into(), value.into()));
+        self.config.set(config);
+
+        self
+    }
+
+    /// Replaces the values of response headers that usually change with every response
+    /// (`Date`, `Expires`, `Last-Modified`, `ETag` and common request ID headers, such as
+    /// `X-Request-Id`) with fixed values.
+    pub fn normalize_volatile_response_headers(self) -> Self {
+        let mut config = self.config.take();
+        config.normalize_response_headers.extend(
+            VOLATILE_RESPONSE_HEADERS
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+        self.config.set(config);
+
+        self
+    }
+
+    /// Replaces the JSON values selected by a JSONPath expression (e.g., `$.requestId` or
+    /// `$..updatedAt`) in recorded response bodies with `value`.
+    pub fn normalize_json_field<IntoString: Into<String>, IntoValue: Into<serde_json::Value>>(
+        self,
+        path: IntoString,
+        value: IntoValue,
+    ) -> Self {
+        let mut config = self.config.take();
+        config.normalize_json_fields.push((path.into(), value.into()));
+        self.config.set(config);
+
+        self
+    }
+
+    /// Replaces all JSON string values in recorded response bodies that contain an ISO 8601
+    /// timestamp (e.g., `2024-05-01T12:30:00Z`) with `placeholder`.
+    pub fn normalize_json_timestamps<IntoString: Into<String>>(self, placeholder: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.normalize_json_timestamps = Some(placeholder.into());
+        self.config.set(config);
+
+        self
+    }
+
+    /// Only records requests that were forwarded or proxied to another server, but not requests
+    /// that were answered by a mock.
+    pub fn record_upstream_only(self, upstream_only: bool) -> Self {
+        let mut config = self.config.take();
+        config.upstream_only = upstream_only;
+        self.config.set(config);
+
+        self
+    }
+
+    /// Sets how requests that are recorded more than once are turned into mocks (see
+    /// [RepeatedRequests]). By default, every recorded request creates a separate mock.
+    ///
+    /// Use [RepeatedRequests::Sequence] to replay polling flows, where the same request receives
+    /// a different response each time.
+    pub fn repeated_requests(self, mode: RepeatedRequests) -> Self {
+        let mut config = self.config.take();
+        config.repeated_requests = mode;
+        self.config.set(config);
+
+        self
+    }
+
+    /// Appends each recorded interaction to the file at `path` as soon as it was recorded,
+    /// instead of keeping all interactions in memory until the recording is exported. The file is
+    /// created (or truncated) when the recording starts and always contains a valid recording, so
+    /// that it can be played back even if the test process terminates unexpectedly.
+    ///
+    /// Exporting or saving the recording reads the file. The file is written by the mock server,
+    /// so for remote mock servers, `path` refers to the file system of the remote server. Remote
+    /// mock servers only accept paths inside their recording stream directory (see
+    /// [HttpMockServerBuilder::recording_stream_dir](crate::server::HttpMockServerBuilder::recording_stream_dir)
+    /// and the `--recording-stream-dir` option of the standalone server). Relative paths are
+    /// resolved against this directory.
+    ///
+    /// Streaming recordings cannot collapse repeated requests (see
+    /// [RecordingRuleBuilder::repeated_requests]).
+    #[cfg(feature = "record")]
+    pub fn stream_to<IntoPathBuf: Into<PathBuf>>(self, path: IntoPathBuf) -> Self {
+        let mut config = self.config.take();
+        config.stream_to = Some(path.into());
+        self.config.set(config);
+
+        self
+    }
+
+    /// Stops appending interactions to the file of a streaming recording (see
+    /// [RecordingRuleBuilder::stream_to]) once it contains `max` interactions.
+    #[cfg(feature = "record")]
+    pub fn stream_max_interactions(self, max: usize) -> Self {
+        let mut config = self.config.take();
+        config.stream_max_interactions = Some(max);
+        self.config.set(config);
+
+        self
+    }
+
+    /// Limits the size of the file of a streaming recording (see [RecordingRuleBuilder::stream_to])
+    /// to `max` bytes. Interactions that would make the file exceed this size are not recorded.
+    #[cfg(feature = "record")]
+    pub fn stream_max_bytes(self, max: u64) -> Self {
+        let mut config = self.config.take();
+        config.stream_max_bytes = Some(max);
+        self.config.set(config);
+
+        self
+    }
+
+    /// Sets how mocks created from this recording match request bodies (see [BodyMatchMode]).
+    /// By default, request bodies must be equal to the recorded body byte-for-byte.
+    pub fn body_match_mode(self, mode: BodyMatchMode) -> Self {
+        let mut config = self.config.take();
+        config.body_match_mode = mode;
+        self.config.set(config);
+
+        self
+    }
+
+    /// Matches recorded JSON request bodies semantically, so that the order of object keys and
+    /// whitespace do not matter on playback. Same as `body_match_mode(BodyMatchMode::Json)`.
+    pub fn match_body_as_json(self) -> Self {
+        self.body_match_mode(BodyMatchMode::Json)
+    }
+
+    /// Does not match request bodies on playback. Same as `body_match_mode(BodyMatchMode::None)`.
+    pub fn ignore_body(self) -> Self {
+        self.body_match_mode(BodyMatchMode::None)
+    }
+
+    /// Does not match the query parameters in `names` on playback (e.g., timestamps or nonces).
+    pub fn ignore_query_params<IntoString: Into<String>>(self, names: Vec<IntoString>) -> Self {
+        let mut config = self.config.take();
+        config.ignore_query_params.extend(names.into_iter().map(Into::into));
+        self.config.set(config);
+
+        self
+    }
+
+    /// Sets whether requests may contain query parameters that were not recorded (`true`, the
+    /// default) or whether mocks created from this recording only match requests that contain
+    /// exactly the recorded query parameters (`false`).
+    ///
+    /// Additional query parameters are always allowed when query parameters are ignored (see
+    /// [RecordingRuleBuilder::ignore_query_params]).
+    pub fn match_query_subset(self, subset: bool) -> Self {
+        let mut config = self.config.take();
+        config.exact_query = !subset;
+        self.config.set(config);
+
+        self
+    }
+
+    /// Redacts the value of a header in recorded requests and responses (case-insensitive).
+    ///
+    /// Recorded response headers get the value `[REDACTED]`. Recorded request headers (see
+    /// [RecordingRuleBuilder::record_request_header]) are only required to be present on playback.
+    pub fn redact_header<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.redact_headers.push(name.into());
+        self.config.set(config);
+
+        self
+    }
+
+    /// Redacts the JSON values selected by a JSONPath expression (e.g., `$.user.password` or
+    /// `$..token`) in recorded request and response bodies.
+    ///
+    /// Selected values in response bodies are replaced with `"[REDACTED]"`. Selected fields in
+    /// request bodies are removed, and the recorded mock only requires them to exist on playback.
+    ///
+    /// Only keys, array indices, wildcards and recursive descent are supported.
+    pub fn redact_json_field<IntoString: Into<String>>(self, path: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.redact_json_fields.push(path.into());
+        self.config.set(config);
+
+        self
+    }
+
+    /// Redacts the value of a query parameter in recorded requests. The recorded mock only
+    /// requires the parameter to be present on playback.
+    pub fn redact_query_param<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        let mut config = self.config.take();
+        config.redact_query_params.push(name.into());
+        self.config.set(config);
+
+        self
+    }
+
+    /// Replaces all text that matches `regex` in recorded response bodies with `replacement`
+    /// (which may refer to capture groups, e.g., `$1`).
+    ///
+    /// Matching text is also removed from recorded request bodies, and the recorded mock
+    /// accepts any text in its place on playback.
+    pub fn redact_body_regex<IntoRegex: Into<HttpMockRegex>, IntoString: Into<String>>(
+        self,
+        regex: IntoRegex,
+        replacement: IntoString,
+    ) -> Self {
+        let mut config = self.config.take();
+        config.redact_body_regex.push((regex.into(), replacement.into()));
+        self.config.set(config);
+
+        self
+    }
 }
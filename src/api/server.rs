@@ -1,27 +1,35 @@
 #[cfg(feature = "record")]
-use std::path::PathBuf;
+use std::sync::Mutex;
 use std::{
     cell::Cell,
     future::pending,
     net::SocketAddr,
+    path::PathBuf,
     rc::Rc,
     sync::{Arc, LazyLock},
     thread,
 };
 
 use async_object_pool::Pool;
+use bytes::Bytes;
 use tokio::sync::oneshot::channel;
 
 #[cfg(feature = "remote")]
 use crate::api::RemoteMockServerAdapter;
+#[cfg(any(feature = "record", feature = "openapi"))]
+use crate::api::mock::MockSet;
 #[cfg(feature = "record")]
 use crate::api::{
+    cassette::{ActiveCassette, CassetteMode},
     common::data::RecordingRuleConfig,
-    mock::MockSet,
     proxy::{Recording, RecordingRuleBuilder},
 };
 #[cfg(feature = "remote")]
 use crate::common::http::HttpMockHttpClient;
+#[cfg(feature = "openapi")]
+use crate::server::openapi;
+#[cfg(feature = "record")]
+use crate::server::{har, wiremock};
 use crate::{
     Mock,
     api::{
@@ -31,13 +39,13 @@ use crate::{
     common::{
         data::{MockDefinition, MockServerHttpResponse, RequestRequirements},
         runtime,
-        util::{Join, read_env, with_retry},
+        util::{Join, read_env, with_retry, write_file},
     },
-    server::{HttpMockServerBuilder, state},
+    server::{HttpMockServerBuilder, pact, state},
 };
 #[cfg(feature = "proxy")]
 use crate::{
-    api::proxy::{ForwardingRule, ForwardingRuleBuilder, ProxyRule, ProxyRuleBuilder},
+    api::proxy::{ForwardingRule, ForwardingRuleBuilder, IntoForwardingTargets, ProxyRule, ProxyRuleBuilder},
     common::data::{ForwardingRuleConfig, ProxyRuleConfig},
 };
 
@@ -54,6 +62,8 @@ use crate::{
 pub struct MockServer {
     pub(crate) server_adapter: Option<Arc<dyn MockServerAdapter + Send + Sync>>,
     pool: Arc<Pool<Arc<dyn MockServerAdapter + Send + Sync>>>,
+    #[cfg(feature = "record")]
+    cassettes: Mutex<Vec<ActiveCassette>>,
 }
 
 impl MockServer {
@@ -64,6 +74,8 @@ impl MockServer {
         let server = Self {
             server_adapter: Some(server_adapter),
             pool,
+            #[cfg(feature = "record")]
+            cassettes: Mutex::new(Vec::new()),
         };
 
         server.reset_async().await;
@@ -349,6 +361,51 @@ impl MockServer {
         self.url("")
     }
 
+    /// Returns the CA certificate that the mock server uses to sign the certificates it
+    /// generates for HTTPS connections. Add it to the trusted root certificates of your HTTP
+    /// client to send HTTPS requests to the mock server.
+    ///
+    /// Unless a CA was configured, mock servers use a CA that is generated once per process.
+    ///
+    /// # Returns
+    /// The CA certificate in PEM format, or `None` if the mock server does not generate
+    /// certificates (e.g., a remote server that serves static certificates only).
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::{blocking::Client, Certificate};
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let ca_cert = server.ca_certificate_pem().unwrap();
+    /// let client = Client::builder()
+    ///     .add_root_certificate(Certificate::from_pem(ca_cert.as_bytes()).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let response = client.get(server.url("/hello")).send().unwrap();
+    /// assert_eq!(response.status(), 404);
+    /// ```
+    #[cfg(feature = "https")]
+    pub fn ca_certificate_pem(&self) -> Option<String> {
+        self.ca_certificate_pem_async().join()
+    }
+
+    /// Returns the CA certificate that the mock server uses to sign the certificates it
+    /// generates for HTTPS connections. See [`MockServer::ca_certificate_pem`].
+    ///
+    /// # Returns
+    /// The CA certificate in PEM format, or `None` if the mock server does not generate
+    /// certificates.
+    #[cfg(feature = "https")]
+    pub async fn ca_certificate_pem_async(&self) -> Option<String> {
+        let server_adapter = self.server_adapter.as_ref().unwrap();
+        with_retry(3, || server_adapter.ca_certificate())
+            .await
+            .expect("Cannot fetch CA certificate from the mock server.")
+    }
+
     /// Creates a [Mock](struct.Mock.html) object on the mock server.
     ///
     /// # Arguments
@@ -512,7 +569,10 @@ impl MockServer {
     /// **NOT** contain the host name as an expectation to allow the recording to be reused.
     ///
     /// # Arguments
-    /// * `to_base_url` - A string that represents the base URL to which the request should be forwarded.
+    /// * `to_base_url` - The base URL to which the request should be forwarded. Several base URLs
+    ///   (optionally with weights) can be passed to balance requests across several targets
+    ///   (see [IntoForwardingTargets](trait.IntoForwardingTargets.html) and
+    ///   [ForwardingRuleBuilder::load_balance](struct.ForwardingRuleBuilder.html#method.load_balance)).
     /// * `rule` - A closure that takes a `ForwardingRuleBuilder` to configure the forwarding rule.
     ///
     /// # Returns
@@ -557,14 +617,14 @@ impl MockServer {
     /// # Feature
     /// This method is only available when the `proxy` feature is enabled.
     #[cfg(feature = "proxy")]
-    pub fn forward_to<IntoString, ForwardingRuleBuilderFn>(
+    pub fn forward_to<IntoTargets, ForwardingRuleBuilderFn>(
         &self,
-        to_base_url: IntoString,
+        to_base_url: IntoTargets,
         rule: ForwardingRuleBuilderFn,
     ) -> ForwardingRule<'_>
     where
         ForwardingRuleBuilderFn: FnOnce(ForwardingRuleBuilder),
-        IntoString: Into<String>,
+        IntoTargets: IntoForwardingTargets,
     {
         self.forward_to_async(to_base_url, rule).join()
     }
@@ -574,7 +634,10 @@ impl MockServer {
     /// contain the host name as an expectation to allow the recording to be reused.
     ///
     /// # Arguments
-    /// * `target_base_url` - A string that represents the base URL to which the request should be forwarded.
+    /// * `target_base_url` - The base URL to which the request should be forwarded. Several base
+    ///   URLs (optionally with weights) can be passed to balance requests across several targets
+    ///   (see [IntoForwardingTargets](trait.IntoForwardingTargets.html) and
+    ///   [ForwardingRuleBuilder::load_balance](struct.ForwardingRuleBuilder.html#method.load_balance)).
     /// * `rule` - A closure that takes a `ForwardingRuleBuilder` to configure the forwarding rule.
     ///
     /// # Returns
@@ -621,32 +684,32 @@ impl MockServer {
     /// # Feature
     /// This method is only available when the `proxy` feature is enabled.
     #[cfg(feature = "proxy")]
-    pub async fn forward_to_async<'a, IntoString, ForwardingRuleBuilderFn>(
+    pub async fn forward_to_async<'a, IntoTargets, ForwardingRuleBuilderFn>(
         &'a self,
-        target_base_url: IntoString,
+        target_base_url: IntoTargets,
         rule: ForwardingRuleBuilderFn,
     ) -> ForwardingRule<'a>
     where
         ForwardingRuleBuilderFn: FnOnce(ForwardingRuleBuilder),
-        IntoString: Into<String>,
+        IntoTargets: IntoForwardingTargets,
     {
-        let headers = Rc::new(Cell::new(Vec::new()));
-        let req = Rc::new(Cell::new(RequestRequirements::new()));
+        let config = Rc::new(Cell::new(ForwardingRuleConfig {
+            targets: target_base_url.into_forwarding_targets(),
+            request_requirements: RequestRequirements::new(),
+            ..ForwardingRuleConfig::default()
+        }));
 
-        rule(ForwardingRuleBuilder {
-            headers: headers.clone(),
-            request_requirements: req.clone(),
-        });
+        rule(ForwardingRuleBuilder { config: config.clone() });
+
+        // Standalone servers of earlier versions only read `target_base_url`.
+        let mut config = config.take();
+        config.sync_target_base_url();
 
         let response = self
             .server_adapter
             .as_ref()
             .unwrap()
-            .create_forwarding_rule(ForwardingRuleConfig {
-                target_base_url: target_base_url.into(),
-                request_requirements: req.take(),
-                request_header: headers.take(),
-            })
+            .create_forwarding_rule(config)
             .await
             .expect("Cannot deserialize mock server response");
 
@@ -786,22 +849,15 @@ impl MockServer {
     where
         ProxyRuleBuilderFn: FnOnce(ProxyRuleBuilder),
     {
-        let headers = Rc::new(Cell::new(Vec::new()));
-        let req = Rc::new(Cell::new(RequestRequirements::new()));
+        let config = Rc::new(Cell::new(ProxyRuleConfig::default()));
 
-        rule(ProxyRuleBuilder {
-            headers: headers.clone(),
-            request_requirements: req.clone(),
-        });
+        rule(ProxyRuleBuilder { config: config.clone() });
 
         let response = self
             .server_adapter
             .as_ref()
             .unwrap()
-            .create_proxy_rule(ProxyRuleConfig {
-                request_requirements: req.take(),
-                request_header: headers.take(),
-            })
+            .create_proxy_rule(config.take())
             .await
             .expect("Cannot deserialize mock server response");
 
@@ -984,8 +1040,7 @@ impl MockServer {
     {
         let config = Rc::new(Cell::new(RecordingRuleConfig {
             request_requirements: RequestRequirements::new(),
-            record_headers: Vec::new(),
-            record_response_delays: false,
+            ..RecordingRuleConfig::default()
         }));
 
         rule(RecordingRuleBuilder { config: config.clone() });
@@ -1301,6 +1356,518 @@ impl MockServer {
             server: self,
         }
     }
+
+    /// Inserts a VCR-style cassette: a recording file that is used to replay interactions with an
+    /// upstream server and that is (re-)recorded from that upstream server as needed.
+    ///
+    /// Depending on `mode`, the mock server replays the interactions stored in the cassette file,
+    /// forwards requests to `upstream` and records them, or both (see [CassetteMode]). Recorded
+    /// interactions are written to the cassette file when the mock server is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: The path of the cassette file (a recording in YAML format, see [MockServer::playback]).
+    /// * `upstream`: The base URL of the upstream server (e.g., `https://api.github.com`).
+    /// * `mode`: Determines whether the cassette is replayed, recorded, or both.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::{CassetteMode, MockServer};
+    /// use reqwest::blocking::get;
+    ///
+    /// let upstream = MockServer::start();
+    /// upstream.mock(|when, then| {
+    ///     when.path("/hello");
+    ///     then.status(200).body("Hi from upstream!");
+    /// });
+    ///
+    /// let cassette = std::env::temp_dir().join("httpmock_cassette_doc_example.yaml");
+    /// # let _ = std::fs::remove_file(&cassette);
+    ///
+    /// // The first run records the interaction, because the cassette does not exist yet.
+    /// {
+    ///     let server = MockServer::start();
+    ///     server.cassette(&cassette, upstream.base_url(), CassetteMode::RecordMissing);
+    ///
+    ///     let response = get(server.url("/hello")).unwrap();
+    ///     assert_eq!(response.text().unwrap(), "Hi from upstream!");
+    /// }
+    ///
+    /// // Later runs replay the recorded interaction without contacting the upstream server.
+    /// let server = MockServer::start();
+    /// server.cassette(&cassette, upstream.base_url(), CassetteMode::Replay);
+    ///
+    /// let response = get(server.url("/hello")).unwrap();
+    /// assert_eq!(response.text().unwrap(), "Hi from upstream!");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cassette file cannot be read in `Replay` mode, or if the cassette cannot be
+    /// saved when the mock server is dropped.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub fn cassette<IntoPathBuf: Into<PathBuf>, IntoString: Into<String>>(
+        &self,
+        path: IntoPathBuf,
+        upstream: IntoString,
+        mode: CassetteMode,
+    ) {
+        self.cassette_async(path, upstream, mode).join()
+    }
+
+    /// Asynchronously inserts a VCR-style cassette.
+    /// See [MockServer::cassette] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub async fn cassette_async<IntoPathBuf: Into<PathBuf>, IntoString: Into<String>>(
+        &self,
+        path: IntoPathBuf,
+        upstream: IntoString,
+        mode: CassetteMode,
+    ) {
+        let (path, upstream) = (path.into(), upstream.into());
+
+        let existing_content = match mode {
+            CassetteMode::Replay => Some(
+                std::fs::read_to_string(&path)
+                    .unwrap_or_else(|err| panic!("cannot read cassette '{}': {}", path.display(), err)),
+            ),
+            CassetteMode::RecordMissing => std::fs::read_to_string(&path).ok(),
+            CassetteMode::Record | CassetteMode::Passthrough => None,
+        };
+
+        if let Some(content) = existing_content.as_ref().filter(|content| !content.trim().is_empty()) {
+            self.playback_from_yaml_async(content).await;
+        }
+
+        if mode != CassetteMode::Replay {
+            self.forward_to_async(upstream, |rule| {
+                rule.fallback(mode == CassetteMode::RecordMissing).filter(|when| {
+                    when.any_request();
+                });
+            })
+            .await;
+        }
+
+        if matches!(mode, CassetteMode::Record | CassetteMode::RecordMissing) {
+            let recording = self
+                .record_async(|rule| {
+                    rule.record_upstream_only(true).filter(|when| {
+                        when.any_request();
+                    });
+                })
+                .await;
+
+            self.cassettes.lock().unwrap().push(ActiveCassette {
+                path,
+                recording_id: recording.id,
+                existing_content,
+            });
+        }
+    }
+
+    /// Configures the mock server to respond with the responses of an HTTP Archive (HAR 1.2) file,
+    /// such as a file exported from the network tab of a browser's developer tools or saved with
+    /// [Recording::save_har](struct.Recording.html#method.save_har).
+    ///
+    /// One mock is created per archived request/response pair. Each mock matches the method, path,
+    /// query parameters and body of the archived request and responds with the archived status,
+    /// headers and body. Request headers are not matched. Entries without a response
+    /// (status `0`, e.g., cancelled requests) are skipped.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: A path to the HAR file.
+    ///
+    /// # Returns
+    ///
+    /// * `MockSet`: An object representing the set of mocks that were created from the HAR file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// server.playback_har("tests/resources/simple_archive.har");
+    ///
+    /// let response = Client::new()
+    ///     .get(format!("{}/api/users?page=1", server.base_url()))
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.status(), 200);
+    /// assert_eq!(response.text().unwrap(), r#"[{"id":1,"name":"Alice"}]"#);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file cannot be read or does not contain a valid HAR document.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub fn playback_har<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        self.playback_har_async(path).join()
+    }
+
+    /// Asynchronously configures the mock server to respond with the responses of an HTTP Archive
+    /// (HAR 1.2) file. See [MockServer::playback_har] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub async fn playback_har_async<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        use std::fs;
+
+        let path = path.into();
+        let content = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "could not read from file {}",
+                path.as_os_str().to_str().map_or(String::new(), |p| p.to_string())
+            )
+        });
+
+        self.playback_from_har_async(content).await
+    }
+
+    /// Configures the mock server to respond with the responses of an HTTP Archive (HAR 1.2)
+    /// document that is provided as a JSON string. See [MockServer::playback_har] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub fn playback_from_har<AsStrRef: AsRef<str>>(&self, content: AsStrRef) -> MockSet<'_> {
+        self.playback_from_har_async(content).join()
+    }
+
+    /// Asynchronously configures the mock server to respond with the responses of an HTTP Archive
+    /// (HAR 1.2) document that is provided as a JSON string. See [MockServer::playback_har] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub async fn playback_from_har_async<AsStrRef: AsRef<str>>(&self, content: AsStrRef) -> MockSet<'_> {
+        let definitions = har::mock_definitions_from_har(content.as_ref())
+            .unwrap_or_else(|err| panic!("cannot create mocks from HAR document: {}", err));
+
+        self.create_mock_set(&definitions).await
+    }
+
+    /// Creates mocks from WireMock JSON stub mappings.
+    ///
+    /// `path` may point to a single mapping file or to a directory. Mapping files (`*.json`) are
+    /// read from the directory and from its `mappings` subdirectory, so a WireMock root directory
+    /// can be used as is. A file may contain a single stub mapping or an object with a `mappings` array.
+    /// Stubs are ordered by their `priority`, because the mock server responds with the first matching mock.
+    ///
+    /// The following parts of a stub mapping are supported:
+    /// - request: `method`, `url` (requests must not have other query parameters than the listed
+    ///   ones), `urlPath`, `urlPattern` (matched against the path),
+    ///   `urlPathPattern`, `urlPathTemplate`, `basicAuthCredentials`, as well as `headers` and
+    ///   `queryParameters` with `equalTo` (optionally `caseInsensitive`), `contains`, `matches`
+    ///   and `absent`,
+    /// - request body: `equalTo`, `contains`, `matches`, `equalToJson` (optionally `ignoreExtraElements`)
+    ///   and `matchesJsonPath` (see [When::json_body_path](struct.When.html#method.json_body_path)),
+    /// - response: `status`, `headers`, `body`, `jsonBody`, `base64Body`, `bodyFileName` (read from the
+    ///   `__files` directory next to the mapping file or next to its parent directory, which the
+    ///   name must not point outside of) and `fixedDelayMilliseconds`.
+    ///
+    /// Mappings that use other features (e.g., scenarios or response templating) are rejected,
+    /// rather than served with different behavior.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: A path to a mapping file or a directory containing mapping files.
+    ///
+    /// # Returns
+    ///
+    /// * `MockSet`: An object representing the set of mocks that were created from the mappings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// server.mocks_from_wiremock("tests/resources/wiremock");
+    ///
+    /// let response = Client::new()
+    ///     .get(format!("{}/api/users/42", server.base_url()))
+    ///     .header("accept", "application/json")
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.status(), 200);
+    /// assert_eq!(response.text().unwrap(), r#"{"id":42,"name":"Alice"}"#);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mapping file cannot be read or uses an unsupported feature.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub fn mocks_from_wiremock<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        self.mocks_from_wiremock_async(path).join()
+    }
+
+    /// Asynchronously creates mocks from WireMock JSON stub mappings.
+    /// See [MockServer::mocks_from_wiremock] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `record` feature is enabled.
+    #[cfg(feature = "record")]
+    pub async fn mocks_from_wiremock_async<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        let definitions = wiremock::read_mappings(&path.into())
+            .unwrap_or_else(|err| panic!("cannot create mocks from WireMock mappings: {}", err));
+
+        self.create_mock_set(&definitions).await
+    }
+
+    /// Creates one mock per operation of an OpenAPI 3.x document (YAML or JSON).
+    ///
+    /// Each mock matches the HTTP method and the path template of an operation (e.g.,
+    /// `/pets/{petId}`, prefixed with the path of the first server URL, if any) and responds
+    /// with the documented example of the operation's success response. If the document
+    /// contains no example, the response body is generated from the response schema.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: The path to the OpenAPI document.
+    ///
+    /// # Returns
+    ///
+    /// * `MockSet`: An object representing the set of mocks that were created from the document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// server.mocks_from_openapi("tests/resources/petstore_openapi.yaml");
+    ///
+    /// let response = Client::new()
+    ///     .get(format!("{}/v1/pets/42", server.base_url()))
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.status(), 200);
+    /// assert_eq!(response.text().unwrap(), r#"{"id":1,"name":"Rex","tag":"dog"}"#);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the document cannot be read or is not a valid OpenAPI 3.x document.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `openapi` feature is enabled.
+    #[cfg(feature = "openapi")]
+    pub fn mocks_from_openapi<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        self.mocks_from_openapi_async(path).join()
+    }
+
+    /// Asynchronously creates one mock per operation of an OpenAPI 3.x document (YAML or JSON).
+    /// See [MockServer::mocks_from_openapi] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `openapi` feature is enabled.
+    #[cfg(feature = "openapi")]
+    pub async fn mocks_from_openapi_async<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        self.create_mocks_from_openapi(path.into(), false).await
+    }
+
+    /// Creates one mock per operation of an OpenAPI 3.x document (YAML or JSON), like
+    /// [MockServer::mocks_from_openapi], but additionally validates requests against the document.
+    ///
+    /// A request that targets an operation is only answered with the documented response if
+    /// - its path parameters have the documented type (`integer`, `number`, `boolean` or an `enum`),
+    /// - it contains all required query parameters (of the documented type) and headers,
+    /// - it contains a body with one of the documented content types, if the request body is required.
+    ///
+    /// Otherwise, the mock server responds with `400 Bad Request` and a JSON body describing the
+    /// operation the request was matched against. Request bodies are not validated against their schema.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: The path to the OpenAPI document.
+    ///
+    /// # Returns
+    ///
+    /// * `MockSet`: An object representing the set of mocks that were created from the document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// server.mocks_from_openapi_with_validation("tests/resources/petstore_openapi.yaml");
+    ///
+    /// // The path parameter "petId" must be an integer.
+    /// let response = Client::new()
+    ///     .get(format!("{}/v1/pets/abc", server.base_url()))
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.status(), 400);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the document cannot be read or is not a valid OpenAPI 3.x document.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `openapi` feature is enabled.
+    #[cfg(feature = "openapi")]
+    pub fn mocks_from_openapi_with_validation<IntoPathBuf: Into<PathBuf>>(&self, path: IntoPathBuf) -> MockSet<'_> {
+        self.mocks_from_openapi_with_validation_async(path).join()
+    }
+
+    /// Asynchronously creates one mock per operation of an OpenAPI 3.x document and validates
+    /// requests against it. See [MockServer::mocks_from_openapi_with_validation] for details.
+    ///
+    /// # Feature
+    ///
+    /// This method is only available when the `openapi` feature is enabled.
+    #[cfg(feature = "openapi")]
+    pub async fn mocks_from_openapi_with_validation_async<IntoPathBuf: Into<PathBuf>>(
+        &self,
+        path: IntoPathBuf,
+    ) -> MockSet<'_> {
+        self.create_mocks_from_openapi(path.into(), true).await
+    }
+
+    /// Writes a Pact contract (specification v3) that describes the interactions with all mocks
+    /// that have been called at least once.
+    ///
+    /// Each exercised mock becomes one interaction. The example request is the first request
+    /// that matched the mock and the example response is the mock's response. Request
+    /// requirements that are more relaxed than exact comparisons (e.g., `path_matches`,
+    /// `query_param_exists`, `header_prefix` or `json_body_includes`) are translated into Pact
+    /// matching rules. Mocks that compute their response dynamically (e.g., with `respond_with`)
+    /// are skipped.
+    ///
+    /// # Parameters
+    ///
+    /// * `consumer`: The name of the consumer (the application under test).
+    /// * `provider`: The name of the provider (the service that is being mocked).
+    /// * `path`: The path of the Pact file. If it points to an existing directory, the file is
+    ///   written to `<consumer>-<provider>.json` inside that directory. An existing file is
+    ///   overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PathBuf, Box<dyn std::error::Error>>`: The path of the written file or an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use httpmock::MockServer;
+    /// use reqwest::blocking::get;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// server.mock(|when, then| {
+    ///     when.method("GET").path_matches(r"^/users/\d+$");
+    ///     then.status(200).json_body(serde_json::json!({ "name": "Alice" }));
+    /// });
+    ///
+    /// get(server.url("/users/42")).unwrap();
+    ///
+    /// let path = server
+    ///     .write_pact("frontend", "user-service", "target/pacts/frontend-user-service.json")
+    ///     .unwrap();
+    ///
+    /// let pact: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    /// assert_eq!(pact["interactions"][0]["request"]["path"], "/users/42");
+    /// ```
+    pub fn write_pact<ConsumerString: Into<String>, ProviderString: Into<String>, IntoPathBuf: Into<PathBuf>>(
+        &self,
+        consumer: ConsumerString,
+        provider: ProviderString,
+        path: IntoPathBuf,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.write_pact_async(consumer, provider, path).join()
+    }
+
+    /// Asynchronously writes a Pact contract (specification v3) that describes the interactions
+    /// with all mocks that have been called at least once.
+    /// See [MockServer::write_pact] for details.
+    pub async fn write_pact_async<
+        ConsumerString: Into<String>,
+        ProviderString: Into<String>,
+        IntoPathBuf: Into<PathBuf>,
+    >(
+        &self,
+        consumer: ConsumerString,
+        provider: ProviderString,
+        path: IntoPathBuf,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let (consumer, provider, mut path) = (consumer.into(), provider.into(), path.into());
+
+        let mocks = self.server_adapter.as_ref().unwrap().fetch_mocks().await?;
+        let pact = pact::build_pact(&consumer, &provider, &mocks);
+
+        if path.is_dir() {
+            path = path.join(format!("{}-{}.json", consumer, provider));
+        }
+
+        let content = Bytes::from(serde_json::to_vec_pretty(&pact)?);
+        write_file(&path, &content, true).await
+    }
+
+    #[cfg(feature = "openapi")]
+    async fn create_mocks_from_openapi(&self, path: PathBuf, validate_requests: bool) -> MockSet<'_> {
+        let definitions = openapi::read_mock_definitions(&path, validate_requests)
+            .unwrap_or_else(|err| panic!("cannot create mocks from OpenAPI document: {}", err));
+
+        self.create_mock_set(&definitions).await
+    }
+
+    #[cfg(any(feature = "record", feature = "openapi"))]
+    async fn create_mock_set(&self, definitions: &[MockDefinition]) -> MockSet<'_> {
+        let mut ids = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            let mock = self
+                .server_adapter
+                .as_ref()
+                .unwrap()
+                .create_mock(definition)
+                .await
+                .expect("Cannot deserialize mock server response");
+            ids.push(mock.id);
+        }
+
+        MockSet { ids, server: self }
+    }
 }
 
 /// Implements the `Drop` trait for `MockServer`.
@@ -1319,14 +1886,41 @@ impl Drop for MockServer {
     /// This behavior is part of the `MockServer` struct and does not require any additional features to be enabled.
     fn drop(&mut self) {
         let adapter = self.server_adapter.take().unwrap();
+
+        // Cassettes must be saved before the server is returned to the pool, where it may be reset
+        // for another test. Errors are only reported afterwards, so that the server is returned
+        // in any case.
+        #[cfg(feature = "record")]
+        let save_errors: Vec<String> = self
+            .cassettes
+            .get_mut()
+            .unwrap()
+            .drain(..)
+            .filter_map(|cassette| {
+                cassette
+                    .save(adapter.as_ref())
+                    .join()
+                    .err()
+                    .map(|err| format!("cannot save cassette to '{}': {}", cassette.path.display(), err))
+            })
+            .collect();
+
         self.pool.put(adapter).join();
+
+        #[cfg(feature = "record")]
+        if !save_errors.is_empty() && !thread::panicking() {
+            panic!("{}", save_errors.join("\n"));
+        }
     }
 }
 
 const LOCAL_SERVER_ADAPTER_GENERATOR: fn() -> Arc<dyn MockServerAdapter + Send + Sync> = || {
     let (addr_sender, addr_receiver) = channel::<SocketAddr>();
     let state_manager = Arc::new(state::Manager::default());
-    let srv = HttpMockServerBuilder::new()
+    let builder = HttpMockServerBuilder::new();
+    #[cfg(feature = "proxy")]
+    let builder = builder.upstream_proxy_option(std::env::var("HTTPMOCK_UPSTREAM_PROXY").ok());
+    let srv = builder
         .build_with_state(state_manager.clone())
         .expect("cannot build mock server");
 
@@ -4,10 +4,13 @@ use bytes::Bytes;
 use serde::Serialize;
 use serde_json::Value;
 
+#[cfg(feature = "proxy")]
+use crate::api::CallbackBuilder;
 use crate::{
     Method, Regex,
     common::{
-        data::{MockServerHttpResponse, RequestRequirements},
+        data::{MockServerHttpResponse, RequestRequirements, TlsVersion},
+        gate::ResponseGate,
         util::{HttpMockBytes, get_test_resource_file_path, update_cell},
     },
     prelude::{HttpMockRequest, HttpMockResponse},
@@ -183,6 +186,239 @@ impl When {
     }
     // @docs-group: Scheme
 
+    /// Sets the requirement that the client presented a TLS client certificate.
+    ///
+    /// Client certificates are only requested by mock servers that have mutual TLS enabled
+    /// (see [HttpMockServerBuilder::https_client_ca](server/struct.HttpMockServerBuilder.html#method.https_client_ca)).
+    /// Requests that were not sent over TLS never match.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// // Start a new mock server
+    /// let server = MockServer::start();
+    ///
+    /// // Create a mock that only matches requests from authenticated clients
+    /// let mock = server.mock(|when, then| {
+    ///     when.client_cert_present();
+    ///     then.status(200);
+    /// });
+    ///
+    /// // A plain HTTP request cannot carry a client certificate
+    /// let response = reqwest::blocking::get(server.url("/test")).unwrap();
+    ///
+    /// assert_eq!(response.status(), 404);
+    /// mock.assert_calls(0);
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn client_cert_present(self) -> Self {
+        update_cell(&self.expectations, |e| {
+            e.client_cert_present = Some(true);
+        });
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the requirement that the subject of the TLS client certificate includes all
+    /// attributes of the given distinguished name.
+    ///
+    /// Attribute types are case-insensitive, attribute values are case-sensitive. For example,
+    /// `CN=my-client` matches a certificate with the subject `CN=my-client, O=Acme`.
+    ///
+    /// This function may be called multiple times to add multiple requirements.
+    ///
+    /// # Parameters
+    /// - `subject`: A distinguished name, e.g. `CN=my-client` or `CN=my-client, O=Acme`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// // Only requests from clients with a certificate for "billing-service" match
+    /// let mock = server.mock(|when, then| {
+    ///     when.client_cert_subject("CN=billing-service, O=Acme");
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn client_cert_subject<IntoString: Into<String>>(self, subject: IntoString) -> Self {
+        update_cell(&self.expectations, |e| {
+            push_to(&mut e.client_cert_subject, subject.into())
+        });
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the requirement that the TLS client certificate contains the given subject
+    /// alternative name.
+    ///
+    /// DNS names, IP addresses, e-mail addresses and URIs are supported. The comparison is
+    /// case-insensitive.
+    ///
+    /// This function may be called multiple times to add multiple requirements.
+    ///
+    /// # Parameters
+    /// - `san`: The subject alternative name, e.g. `billing.internal`, `10.0.0.1` or
+    ///   `spiffe://acme.com/billing`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.client_cert_san("spiffe://acme.com/billing");
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn client_cert_san<IntoString: Into<String>>(self, san: IntoString) -> Self {
+        update_cell(&self.expectations, |e| push_to(&mut e.client_cert_san, san.into()));
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the server name that the client must have requested using the TLS SNI extension.
+    ///
+    /// Clients usually send the host name of the URL as SNI, but not IP addresses. The comparison
+    /// is case-insensitive. Requests that were not sent over TLS never match.
+    ///
+    /// # Parameters
+    /// - `server_name`: The expected server name, e.g. `api.example.com`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.tls_sni("api.example.com");
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn tls_sni<IntoString: Into<String>>(self, server_name: IntoString) -> Self {
+        update_cell(&self.expectations, |e| {
+            e.tls_sni = Some(server_name.into());
+        });
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the minimum TLS protocol version that must have been negotiated for the request.
+    /// Requests that were not sent over TLS never match.
+    ///
+    /// # Parameters
+    /// - `version`: The minimum [TlsVersion].
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::{prelude::*, TlsVersion};
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.tls_version_at_least(TlsVersion::Tls13);
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn tls_version_at_least(self, version: TlsVersion) -> Self {
+        update_cell(&self.expectations, |e| {
+            e.tls_version_at_least = Some(version);
+        });
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the application protocol that must have been negotiated using TLS ALPN, e.g. `h2`
+    /// or `http/1.1`. Requests that were not sent over TLS never match.
+    ///
+    /// # Parameters
+    /// - `protocol`: The expected ALPN protocol ID.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.alpn("h2");
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn alpn<IntoString: Into<String>>(self, protocol: IntoString) -> Self {
+        update_cell(&self.expectations, |e| {
+            e.alpn = Some(protocol.into());
+        });
+        self
+    }
+    // @docs-group: TLS
+
+    /// Sets the HTTP version that requests must use, e.g., to ensure that a client talks HTTP/2
+    /// to the mock server. HTTP/2 requests are matched regardless of whether they were sent over
+    /// TLS, in cleartext with prior knowledge, or after an `h2c` upgrade.
+    ///
+    /// **Note**: The mock server only accepts HTTP/2 connections if the `http2` feature is enabled.
+    ///
+    /// Some requests that ask for an `h2c` upgrade are served over HTTP/1.1 instead and
+    /// therefore do not match `Version::HTTP_2`:
+    /// - Requests with a body are not upgraded.
+    /// - Requests whose headers do not fit into a single HTTP/2 frame (16 KiB) are not upgraded.
+    /// - Requests with an invalid `HTTP2-Settings` header are not upgraded.
+    ///
+    /// # Parameters
+    /// - `version`: The expected [http::Version], e.g., `Version::HTTP_2`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use http::Version;
+    /// use httpmock::prelude::*;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.http_version(Version::HTTP_2);
+    ///     then.status(200);
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    /// The modified `When` instance to allow for method chaining.
+    ///
+    pub fn http_version(self, version: http::Version) -> Self {
+        update_cell(&self.expectations, |e| {
+            e.http_version = Some(format!("{:?}", version));
+        });
+        self
+    }
+    // @docs-group: Protocol
+
     /// Sets the expected HTTP method for which the mock server should respond.
     ///
     /// This method ensures that the mock server only matches requests that use the specified HTTP method,
@@ -3591,6 +3827,51 @@ impl When {
     }
     // @docs-group: Body
 
+    /// Sets the requirement that the request body is a JSON document that contains a value at
+    /// the given JSONPath expression.
+    ///
+    /// Only the subset of JSONPath that selects values by their location is supported:
+    /// the root `$`, child keys (`.name` or `['name']`), array indices (`[0]`, or `[-1]` for the
+    /// last element), wildcards (`.*` or `[*]`) and recursive descent (`..name`).
+    /// Filter expressions (`[?(...)]`), unions and slices are not supported.
+    ///
+    /// **Note:** This method does not automatically set the `Content-Type` header to `application/json`.
+    ///
+    /// # Parameters
+    /// - `path`: The JSONPath expression (e.g., `"$.order.items[0].id"`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    /// use reqwest::blocking::Client;
+    ///
+    /// let server = MockServer::start();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.json_body_path("$.order.items[*].id");
+    ///     then.status(200);
+    /// });
+    ///
+    /// let response = Client::new()
+    ///     .post(server.url("/orders"))
+    ///     .body(r#"{ "order": { "items": [ { "id": 1 } ] } }"#)
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// mock.assert();
+    /// assert_eq!(response.status(), 200);
+    /// ```
+    ///
+    /// # Returns
+    /// The updated `When` instance to allow method chaining.
+    pub fn json_body_path<IntoString: Into<String>>(self, path: IntoString) -> Self {
+        update_cell(&self.expectations, |e| {
+            push_to(&mut e.json_body_path, path.into());
+        });
+        self
+    }
+    // @docs-group: Body
+
     /// Adds a key-value pair to the requirements for an `application/x-www-form-urlencoded` request body.
     ///
     /// This method sets an expectation for a specific key-value pair to be included in the request body
@@ -4397,6 +4678,177 @@ impl When {
     }
     // @docs-group: Custom
 
+    /// Adds a named request predicate that must return `true` for a request to match.
+    ///
+    /// The predicate must have been registered on the mock server using
+    /// [HttpMockServerBuilder::predicate](server/struct.HttpMockServerBuilder.html#method.predicate).
+    /// Unlike [When::is_true], named predicates can be used with remote/standalone mock servers
+    /// and in static mock files, because only the name of the predicate is sent to the server.
+    ///
+    /// # Parameters
+    /// - `name`: The name under which the predicate has been registered on the server.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "remote")]
+    /// # {
+    /// use httpmock::prelude::*;
+    /// use httpmock::server::HttpMockServerBuilder;
+    ///
+    /// // Arrange: Register the predicate on the server (e.g., in your own standalone server binary)
+    /// let mock_server = HttpMockServerBuilder::new()
+    ///     .predicate("is_admin", |req: &HttpMockRequest| {
+    ///         req.headers().get("x-role").is_some_and(|role| role == "admin")
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// # let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    /// # std::thread::spawn(move || {
+    /// #     let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    /// #     runtime.block_on(mock_server.start_with_signals(Some(addr_sender), std::future::pending()))
+    /// # });
+    /// # let address = addr_receiver.blocking_recv().unwrap().to_string();
+    ///
+    /// // Connect to the server and refer to the predicate by name
+    /// let server = MockServer::connect(&address);
+    ///
+    /// let m = server.mock(|when, then| {
+    ///     when.path("/admin").predicate("is_admin");
+    ///     then.status(200);
+    /// });
+    ///
+    /// // Act: Send the HTTP request
+    /// let response = reqwest::blocking::Client::new()
+    ///     .get(server.url("/admin"))
+    ///     .header("x-role", "admin")
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// // Assert
+    /// m.assert();
+    /// assert_eq!(response.status(), 200);
+    /// # }
+    /// ```
+    ///
+    /// # Returns
+    /// `When`: Returns the modified `When` object with the named predicate added to the expectations.
+    ///
+    /// # Note
+    /// Creating the mock fails if no predicate with the given name has been registered on the server.
+    pub fn predicate<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        update_cell(&self.expectations, |e| push_to(&mut e.predicate, name.into()));
+        self
+    }
+    // @docs-group: Custom
+
+    /// Adds a named request predicate that must return `false` for a request to match.
+    ///
+    /// The predicate must have been registered on the mock server using
+    /// [HttpMockServerBuilder::predicate](server/struct.HttpMockServerBuilder.html#method.predicate).
+    /// Unlike [When::is_false], named predicates can be used with remote/standalone mock servers
+    /// and in static mock files, because only the name of the predicate is sent to the server.
+    ///
+    /// # Parameters
+    /// - `name`: The name under which the predicate has been registered on the server.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "remote")]
+    /// # {
+    /// use httpmock::prelude::*;
+    /// use httpmock::server::HttpMockServerBuilder;
+    ///
+    /// // Arrange: Register the predicate on the server (e.g., in your own standalone server binary)
+    /// let mock_server = HttpMockServerBuilder::new()
+    ///     .predicate("is_admin", |req: &HttpMockRequest| {
+    ///         req.headers().get("x-role").is_some_and(|role| role == "admin")
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// # let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    /// # std::thread::spawn(move || {
+    /// #     let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    /// #     runtime.block_on(mock_server.start_with_signals(Some(addr_sender), std::future::pending()))
+    /// # });
+    /// # let address = addr_receiver.blocking_recv().unwrap().to_string();
+    ///
+    /// // Connect to the server and refer to the predicate by name
+    /// let server = MockServer::connect(&address);
+    ///
+    /// let m = server.mock(|when, then| {
+    ///     when.path("/public").predicate_not("is_admin");
+    ///     then.status(200);
+    /// });
+    ///
+    /// // Act: Send the HTTP request
+    /// let response = reqwest::blocking::get(server.url("/public")).unwrap();
+    ///
+    /// // Assert
+    /// m.assert();
+    /// assert_eq!(response.status(), 200);
+    /// # }
+    /// ```
+    ///
+    /// # Returns
+    /// `When`: Returns the modified `When` object with the named predicate added to the expectations.
+    ///
+    /// # Note
+    /// Creating the mock fails if no predicate with the given name has been registered on the server.
+    pub fn predicate_not<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        update_cell(&self.expectations, |e| push_to(&mut e.predicate_not, name.into()));
+        self
+    }
+    // @docs-group: Custom
+
+    /// Adds a script that must evaluate to `true` for a request to match.
+    ///
+    /// The script is written in [Rhai](https://rhai.rs) and evaluated on the mock server. It can
+    /// access a read-only view of the request through the `request` variable, which provides the
+    /// properties `method`, `scheme`, `uri`, `path`, `host`, `port`, `headers` (with lowercase
+    /// names), `query`, `body` and `json` (the parsed JSON body or `()`), as well as the
+    /// functions `header(name)` and `query_param(name)`.
+    ///
+    /// Unlike [When::is_true], scripts are part of the mock definition, so they can be used with
+    /// remote/standalone mock servers and in static mock files.
+    ///
+    /// # Parameters
+    /// - `script`: The script source code. The script must evaluate to a boolean.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// // Arrange
+    /// let server = MockServer::start();
+    ///
+    /// let m = server.mock(|when, then| {
+    ///     when.script_predicate(r#"request.path.ends_with("Test") && request.query_param("page") == "2""#);
+    ///     then.status(200);
+    /// });
+    ///
+    /// // Act: Send the HTTP request
+    /// let response = reqwest::blocking::get(server.url("/thisIsMyTest?page=2")).unwrap();
+    ///
+    /// // Assert
+    /// m.assert();
+    /// assert_eq!(response.status(), 200);
+    /// ```
+    ///
+    /// # Returns
+    /// `When`: Returns the modified `When` object with the script added to the expectations.
+    ///
+    /// # Note
+    /// This method requires the `scripting` feature to be enabled. Creating the mock fails if the
+    /// script cannot be compiled. Scripts that fail while being evaluated do not match. Request
+    /// matching blocks the mock server, so a predicate script may only perform 10,000 operations
+    /// per request. Scripts that exceed this budget do not match.
+    #[cfg(feature = "scripting")]
+    pub fn script_predicate<IntoString: Into<String>>(self, script: IntoString) -> Self {
+        update_cell(&self.expectations, |e| push_to(&mut e.script_predicate, script.into()));
+        self
+    }
+    // @docs-group: Custom
+
     /// Applies a specified function to enhance or modify the `When` instance. This method allows for the
     /// encapsulation of multiple matching conditions into a single function, maintaining a clear and fluent
     /// interface for setting up HTTP request expectations.
@@ -5032,6 +5484,8 @@ impl Then {
     ///   handling path and will delay responses.
     /// - If you need to combine static defaults with dynamic tweaks, compute them inside
     ///   the closure (e.g., start from `HttpMockResponse::builder()` and adjust as needed).
+    /// - Creating the mock fails if it also uses [Then::script](struct.Then.html#method.script) or
+    ///   [Then::responder](struct.Then.html#method.responder).
     pub fn respond_with<F>(self, f: F) -> Self
     where
         F: Fn(&HttpMockRequest) -> HttpMockResponse + Send + Sync + 'static,
@@ -5041,4 +5495,258 @@ impl Then {
         });
         self
     }
+
+    /// Creates the response using a named responder that has been registered on the mock server.
+    ///
+    /// The responder must have been registered using
+    /// [HttpMockServerBuilder::responder](server/struct.HttpMockServerBuilder.html#method.responder).
+    /// Unlike [Then::respond_with], named responders can be used with remote/standalone mock
+    /// servers and in static mock files, because only the name of the responder is sent to the server.
+    ///
+    /// # Parameters
+    /// - `name`: The name under which the responder has been registered on the server.
+    ///
+    /// # Returns
+    /// Returns `self` to allow chaining of method calls on the `Mock` object.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "remote")]
+    /// # {
+    /// use httpmock::prelude::*;
+    /// use httpmock::server::HttpMockServerBuilder;
+    ///
+    /// // Arrange: Register the responder on the server (e.g., in your own standalone server binary)
+    /// let mock_server = HttpMockServerBuilder::new()
+    ///     .responder("echo_path", |req: &HttpMockRequest| {
+    ///         HttpMockResponse::builder().status(200).body(req.uri().path().to_string()).build()
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// # let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    /// # std::thread::spawn(move || {
+    /// #     let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    /// #     runtime.block_on(mock_server.start_with_signals(Some(addr_sender), std::future::pending()))
+    /// # });
+    /// # let address = addr_receiver.blocking_recv().unwrap().to_string();
+    ///
+    /// // Connect to the server and refer to the responder by name
+    /// let server = MockServer::connect(&address);
+    ///
+    /// let m = server.mock(|when, then| {
+    ///     when.path_prefix("/echo");
+    ///     then.responder("echo_path");
+    /// });
+    ///
+    /// // Act: Send the HTTP request
+    /// let response = reqwest::blocking::get(server.url("/echo/hello")).unwrap();
+    ///
+    /// // Assert
+    /// m.assert();
+    /// assert_eq!(response.text().unwrap(), "/echo/hello");
+    /// # }
+    /// ```
+    ///
+    /// # Notes
+    /// Creating the mock fails if no responder with the given name has been registered on the server.
+    pub fn responder<IntoString: Into<String>>(self, name: IntoString) -> Self {
+        update_cell(&self.response_template, |r| {
+            r.responder = Some(name.into());
+        });
+        self
+    }
+    // @docs-group: Miscellaneous
+
+    /// Creates the response using a script that is evaluated on the mock server.
+    ///
+    /// The script is written in [Rhai](https://rhai.rs). It can access a read-only view of the
+    /// request through the `request` variable (see [When::script_predicate] for the available
+    /// properties and functions). The script must evaluate to one of the following:
+    /// - a response created with the `response()` builder, which provides the methods
+    ///   `status(code)`, `header(name, value)`, `body(text)` and `json_body(value)`,
+    /// - an object map with the optional keys `status`, `headers` and `body`
+    ///   (non-string bodies are serialized to JSON), or
+    /// - a string, which is used as the body of a `200 OK` response.
+    ///
+    /// Unlike [Then::respond_with], scripts are part of the mock definition, so they can be used
+    /// with remote/standalone mock servers and in static mock files.
+    ///
+    /// # Parameters
+    /// - `script`: The script source code.
+    ///
+    /// # Returns
+    /// Returns `self` to allow chaining of method calls on the `Mock` object.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    ///
+    /// // Arrange
+    /// let server = MockServer::start();
+    ///
+    /// let m = server.mock(|when, then| {
+    ///     when.method(POST).path("/users");
+    ///     then.script(r#"
+    ///         let user = request.json;
+    ///         response()
+    ///             .status(201)
+    ///             .json_body(#{ id: 1, name: user.name })
+    ///     "#);
+    /// });
+    ///
+    /// // Act: Send the HTTP request
+    /// let response = reqwest::blocking::Client::new()
+    ///     .post(server.url("/users"))
+    ///     .body(r#"{"name": "Fred"}"#)
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// // Assert
+    /// m.assert();
+    /// assert_eq!(response.status(), 201);
+    /// assert_eq!(response.text().unwrap(), r#"{"id":1,"name":"Fred"}"#);
+    /// ```
+    ///
+    /// # Notes
+    /// This method requires the `scripting` feature to be enabled. Creating the mock fails if the
+    /// script cannot be compiled. Scripts that fail while being evaluated or return a status code
+    /// outside of the range 100 to 999 result in a `500 Internal Server Error` response.
+    #[cfg(feature = "scripting")]
+    pub fn script<IntoString: Into<String>>(self, script: IntoString) -> Self {
+        update_cell(&self.response_template, |r| {
+            r.script = Some(script.into());
+        });
+        self
+    }
+    // @docs-group: Miscellaneous
+
+    /// Holds back the response until the test releases it through the provided [ResponseGate].
+    ///
+    /// Matching requests are parked on the mock server after any configured [Then::delay]
+    /// has elapsed. Each call to [ResponseGate::release] lets exactly one parked request
+    /// continue with the response configured on this mock, while [ResponseGate::release_with]
+    /// replaces the response for that request. This allows to deterministically test
+    /// concurrency, cancellation and timeout behaviour of a client without sleep-based timing.
+    ///
+    /// # Parameters
+    /// - `gate`: The [ResponseGate] that controls when responses are sent. Clone the gate
+    ///   before passing it here to keep a handle for the test.
+    ///
+    /// # Returns
+    /// Returns `self` to allow chaining of method calls on the `Mock` object.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    /// use httpmock::ResponseGate;
+    /// use reqwest::blocking::Client;
+    ///
+    /// // Arrange
+    /// let server = MockServer::start();
+    /// let gate = ResponseGate::new();
+    ///
+    /// let mock = server.mock(|when, then| {
+    ///     when.path("/gated");
+    ///     then.status(200).wait_for(gate.clone());
+    /// });
+    ///
+    /// // Act
+    /// let url = server.url("/gated");
+    /// let request = std::thread::spawn(move || Client::new().get(url).send().unwrap());
+    ///
+    /// gate.wait_for_pending(1);
+    /// gate.release();
+    ///
+    /// // Assert
+    /// assert_eq!(request.join().unwrap().status(), 200);
+    /// mock.assert();
+    /// ```
+    ///
+    /// # Notes
+    /// Response gates are only supported by the local mock server. Using them with a
+    /// remote/standalone mock server results in an error when the mock is created.
+    pub fn wait_for(self, gate: ResponseGate) -> Self {
+        update_cell(&self.response_template, |r| {
+            r.wait_for = Some(gate);
+        });
+        self
+    }
+    // @docs-group: Network
+
+    /// Sends an outgoing HTTP request (e.g., a webhook) after the mock has been served.
+    ///
+    /// This is useful to test asynchronous flows in which a service first accepts a request and
+    /// later notifies the client through a callback (e.g., payment or notification providers).
+    /// The callback is sent in the background after the response has been written to the client
+    /// connection, so it does not delay the response. With HTTP/2, the client might not have read
+    /// the response yet when the callback arrives. The URL, header values and body of the callback may contain
+    /// placeholders that are filled in from the triggering request (see [CallbackBuilder](struct.CallbackBuilder.html)).
+    ///
+    /// This method can be called multiple times to send multiple callbacks.
+    ///
+    /// # Parameters
+    /// - `spec`: A function that configures the callback using a [CallbackBuilder](struct.CallbackBuilder.html).
+    ///
+    /// # Returns
+    /// Returns `self` to allow chaining of method calls on the `Mock` object.
+    ///
+    /// # Example
+    /// ```rust
+    /// use httpmock::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// // Arrange: A server that receives the webhook ...
+    /// let webhook_server = MockServer::start();
+    /// let webhook = webhook_server.mock(|when, then| {
+    ///     when.method(POST)
+    ///         .path("/webhooks/payments")
+    ///         .json_body(serde_json::json!({ "payment": "pay-42", "status": "settled" }));
+    ///     then.status(204);
+    /// });
+    ///
+    /// // ... and a server that simulates the payment provider.
+    /// let server = MockServer::start();
+    /// let payment = server.mock(|when, then| {
+    ///     when.method(POST).path("/payments");
+    ///     then.status(202).callback(|cb| {
+    ///         cb.method(POST)
+    ///             .url(webhook_server.url("/webhooks/payments"))
+    ///             .json_body(serde_json::json!({ "payment": "{{request.json.id}}", "status": "settled" }))
+    ///             .delay(Duration::from_millis(10))
+    ///     });
+    /// });
+    ///
+    /// // Act
+    /// let response = reqwest::blocking::Client::new()
+    ///     .post(server.url("/payments"))
+    ///     .body(r#"{ "id": "pay-42" }"#)
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// // Wait for the webhook to arrive
+    /// for _ in 0..100 {
+    ///     if webhook.calls() > 0 {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(20));
+    /// }
+    ///
+    /// // Assert
+    /// assert_eq!(response.status(), 202);
+    /// payment.assert();
+    /// webhook.assert();
+    /// ```
+    ///
+    /// # Feature
+    /// This method is only available when the `proxy` feature is enabled.
+    #[cfg(feature = "proxy")]
+    pub fn callback<CallbackSpecFn>(self, spec: CallbackSpecFn) -> Self
+    where
+        CallbackSpecFn: FnOnce(CallbackBuilder) -> CallbackBuilder,
+    {
+        let callback = spec(CallbackBuilder::default()).definition;
+        update_cell(&self.response_template, |r| push_to(&mut r.callbacks, callback));
+        self
+    }
+    // @docs-group: Network
 }
@@ -2,11 +2,14 @@ extern crate serde_regex;
 
 use std::{
     cmp::Ordering,
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt,
     fmt::Debug,
+    path::PathBuf,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
@@ -17,11 +20,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub type ResponseCallback = Arc<dyn Fn(&HttpMockRequest) -> HttpMockResponse + Send + Sync>;
+pub type ResponseMapper = Arc<dyn Fn(&HttpMockRequest, HttpMockResponse) -> HttpMockResponse + Send + Sync>;
+pub type JsonBodyTransform = Arc<dyn Fn(Value) -> Value + Send + Sync>;
 pub type RequestPredicate = Arc<dyn Fn(&HttpMockRequest) -> bool + Send + Sync>;
 
+#[cfg(feature = "proxy")]
+use crate::common::random::SharedRng;
 use crate::{
     common::{
         data::Error::{HeaderDeserialization, RequestConversion, StaticMockConversion},
+        gate::ResponseGate,
+        json_path::JsonPath,
         util::HttpMockBytes,
     },
     server::{RequestMetadata, matchers::generic::MatchingStrategy},
@@ -48,6 +57,8 @@ pub struct HttpMockRequest {
     headers: Vec<(String, String)>,
     version: String,
     body: HttpMockBytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsInfo>,
 }
 
 impl HttpMockRequest {
@@ -74,9 +85,15 @@ impl HttpMockRequest {
             headers,
             version,
             body,
+            tls: None,
         }
     }
 
+    pub(crate) fn with_tls(mut self, tls: Option<TlsInfo>) -> Self {
+        self.tls = tls;
+        self
+    }
+
     /// Parses and returns the URI of the request.
     ///
     /// # Attention
@@ -268,6 +285,24 @@ impl HttpMockRequest {
         self.version.as_ref()
     }
 
+    /// Returns information about the TLS connection the request was received on.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the request was not sent over TLS.
+    pub fn tls(&self) -> Option<&TlsInfo> {
+        self.tls.as_ref()
+    }
+
+    /// Returns the certificate the client presented during the TLS handshake.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the request was not sent over TLS or the client did not present a certificate.
+    pub fn client_certificate(&self) -> Option<&ClientCertificate> {
+        self.tls.as_ref().and_then(|tls| tls.client_certificate.as_ref())
+    }
+
     #[cfg(feature = "cookies")]
     pub(crate) fn cookies(&self) -> Result<Vec<(String, String)>, Error> {
         let mut result = Vec::new();
@@ -282,6 +317,75 @@ impl HttpMockRequest {
     }
 }
 
+/// Information about the TLS connection a request was received on.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TlsInfo {
+    /// The server name the client requested using the SNI extension, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sni: Option<String>,
+    /// The negotiated TLS protocol version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<TlsVersion>,
+    /// The negotiated ALPN protocol (e.g. `h2` or `http/1.1`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+    /// The name of the negotiated cipher suite, e.g. `TLS13_AES_128_GCM_SHA256`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cipher_suite: Option<String>,
+    /// The certificate the client presented during the TLS handshake, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<ClientCertificate>,
+}
+
+/// A TLS protocol version.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TlsVersion {
+    /// TLS 1.0
+    #[serde(rename = "TLSv1.0")]
+    Tls10,
+    /// TLS 1.1
+    #[serde(rename = "TLSv1.1")]
+    Tls11,
+    /// TLS 1.2
+    #[serde(rename = "TLSv1.2")]
+    Tls12,
+    /// TLS 1.3
+    #[serde(rename = "TLSv1.3")]
+    Tls13,
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TlsVersion::Tls10 => "TLSv1.0",
+            TlsVersion::Tls11 => "TLSv1.1",
+            TlsVersion::Tls12 => "TLSv1.2",
+            TlsVersion::Tls13 => "TLSv1.3",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A certificate that a client presented during a mutual TLS handshake.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClientCertificate {
+    /// The subject distinguished name, e.g. `CN=my-client, O=Acme`. Attributes appear in the
+    /// order in which they are stored in the certificate.
+    pub subject: String,
+    /// The subject alternative names: DNS names, IP addresses, e-mail addresses and URIs.
+    #[serde(default)]
+    pub subject_alt_names: Vec<String>,
+}
+
+/// Splits a distinguished name such as `CN=my-client, O=Acme` into `(type, value)` pairs.
+/// Attribute types are converted to upper case.
+pub(crate) fn parse_distinguished_name(name: &str) -> Vec<(String, String)> {
+    name.split(',')
+        .filter_map(|attribute| attribute.split_once('='))
+        .map(|(kind, value)| (kind.trim().to_uppercase(), value.trim().to_string()))
+        .collect()
+}
+
 fn http_headers_to_vec<T>(req: &http::Request<T>) -> Result<Vec<(String, String)>, Error> {
     req.headers()
         .iter()
@@ -318,7 +422,8 @@ where
             headers,
             format!("{:?}", value.version()),
             body,
-        ))
+        )
+        .with_tls(metadata.tls.clone()))
     }
 }
 
@@ -627,6 +732,17 @@ pub struct MockServerHttpResponse {
     pub delay: Option<u64>,
     #[serde(skip)]
     pub respond_with: Option<ResponseCallback>,
+    #[serde(skip)]
+    pub wait_for: Option<ResponseGate>,
+    pub responder: Option<String>,
+    pub script: Option<String>,
+    pub callbacks: Option<Vec<CallbackDefinition>>,
+    /// Responses that are served one after another to consecutive matching requests. The last
+    /// response is repeated once all responses were served. If set, the status, headers, body,
+    /// responder and script are ignored, while `delay`, `wait_for` and `callbacks` apply to every
+    /// response of the sequence that does not set its own.
+    #[serde(default)]
+    pub sequence: Option<Vec<MockServerHttpResponse>>,
 }
 
 impl MockServerHttpResponse {
@@ -637,8 +753,32 @@ impl MockServerHttpResponse {
             body: None,
             delay: None,
             respond_with: None,
+            wait_for: None,
+            responder: None,
+            script: None,
+            callbacks: None,
+            sequence: None,
         }
     }
+
+    /// Returns the response for the `call`-th matching request (starting at 1). For sequences,
+    /// this is the element of the sequence at that position, which inherits the delay, the
+    /// response gate and the callbacks of this response unless it sets its own.
+    pub(crate) fn for_call(&self, call: usize) -> MockServerHttpResponse {
+        let Some(sequence) = self.sequence.as_ref().filter(|sequence| !sequence.is_empty()) else {
+            return self.clone();
+        };
+
+        let mut response = sequence[call.saturating_sub(1).min(sequence.len() - 1)].clone();
+        response.delay = response.delay.or(self.delay);
+        if response.wait_for.is_none() {
+            response.wait_for = self.wait_for.clone();
+        }
+        if response.callbacks.is_none() {
+            response.callbacks = self.callbacks.clone();
+        }
+        response
+    }
 }
 
 impl Default for MockServerHttpResponse {
@@ -669,10 +809,29 @@ impl TryFrom<&http::Response<Bytes>> for MockServerHttpResponse {
             },
             delay: None,
             respond_with: None,
+            wait_for: None,
+            responder: None,
+            script: None,
+            callbacks: None,
+            sequence: None,
         })
     }
 }
 
+/// An outgoing HTTP request (e.g., a webhook) that the mock server sends after it has
+/// served a mock. The URL, header values and body may contain placeholders that are
+/// filled in from the request that triggered the callback.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CallbackDefinition {
+    pub method: Option<String>,
+    pub url: Option<String>,
+    pub headers: Option<Vec<(String, String)>>,
+    pub body: Option<String>,
+    /// A JSON body whose string values may contain placeholders. Takes precedence over `body`.
+    pub json_body: Option<Value>,
+    pub delay: Option<u64>,
+}
+
 /// Serializes and deserializes the response body to/from a Base64 string.
 mod opt_vector_serde_base64 {
     use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
@@ -732,6 +891,11 @@ impl fmt::Debug for MockServerHttpResponse {
                     .map(|x| String::from_utf8_lossy(x.as_ref()).to_string()),
             )
             .field("delay", &self.delay)
+            .field("wait_for", &self.wait_for)
+            .field("responder", &self.responder)
+            .field("script", &self.script)
+            .field("callbacks", &self.callbacks)
+            .field("sequence", &self.sequence)
             .finish()
     }
 }
@@ -861,6 +1025,9 @@ pub struct RequestRequirements {
     pub json_body_not: Option<Value>, // NEW
     pub json_body_includes: Option<Vec<Value>>,
     pub json_body_excludes: Option<Vec<Value>>, // NEW
+    pub json_body_path: Option<Vec<String>>,
+    #[serde(skip)]
+    pub(crate) resolved_json_body_path: Option<Vec<JsonPath>>,
     pub form_urlencoded_tuple: Option<Vec<(String, String)>>,
     pub form_urlencoded_tuple_not: Option<Vec<(String, String)>>, // NEW
     pub form_urlencoded_tuple_exists: Option<Vec<String>>,
@@ -873,10 +1040,26 @@ pub struct RequestRequirements {
     pub form_urlencoded_tuple_suffix_not: Option<Vec<(String, String)>>, // NEW
     pub form_urlencoded_tuple_matches: Option<Vec<(HttpMockRegex, HttpMockRegex)>>, // NEW
     pub form_urlencoded_tuple_count: Option<Vec<(HttpMockRegex, HttpMockRegex, usize)>>, // NEW
+    pub client_cert_present: Option<bool>,
+    pub client_cert_subject: Option<Vec<String>>,
+    pub client_cert_san: Option<Vec<String>>,
+    pub tls_sni: Option<String>,
+    pub tls_version_at_least: Option<TlsVersion>,
+    pub alpn: Option<String>,
+    pub http_version: Option<String>,
     #[serde(skip)]
     pub is_true: Option<Vec<RequestPredicate>>, // NEW + DEPRECATE matches() -> point to using "is_true" instead
     #[serde(skip)]
     pub is_false: Option<Vec<RequestPredicate>>, // NEW
+    pub predicate: Option<Vec<String>>,
+    pub predicate_not: Option<Vec<String>>,
+    #[serde(skip)]
+    pub resolved_predicate: Option<Vec<RequestPredicate>>,
+    #[serde(skip)]
+    pub resolved_predicate_not: Option<Vec<RequestPredicate>>,
+    pub script_predicate: Option<Vec<String>>,
+    #[serde(skip)]
+    pub resolved_script_predicate: Option<Vec<RequestPredicate>>,
 }
 
 impl RequestRequirements {
@@ -907,6 +1090,9 @@ pub struct ActiveMock {
     pub call_counter: usize,
     pub definition: MockDefinition,
     pub is_static: bool,
+    /// The first request that matched this mock, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_request: Option<HttpMockRequest>,
 }
 
 impl ActiveMock {
@@ -916,6 +1102,7 @@ impl ActiveMock {
             definition,
             call_counter,
             is_static,
+            first_request: None,
         }
     }
 }
@@ -924,11 +1111,60 @@ impl ActiveMock {
 pub struct ActiveForwardingRule {
     pub id: usize,
     pub config: ForwardingRuleConfig,
+    /// The number of requests that were forwarded by this rule before the current one.
+    #[serde(default)]
+    pub forwarded_requests: usize,
+    #[cfg(feature = "proxy")]
+    #[serde(skip)]
+    pub(crate) rng: SharedRng,
 }
 
 impl ActiveForwardingRule {
     pub fn new(id: usize, config: ForwardingRuleConfig) -> Self {
-        ActiveForwardingRule { id, config }
+        ActiveForwardingRule {
+            id,
+            #[cfg(feature = "proxy")]
+            rng: SharedRng::new(config.random_seed),
+            config,
+            forwarded_requests: 0,
+        }
+    }
+
+    /// Returns the targets of the rule in the order they should be tried for the current request:
+    /// the target selected by the load balancing strategy first, followed by the remaining targets
+    /// (which are only used for failover).
+    #[cfg(feature = "proxy")]
+    pub(crate) fn targets_in_order(&self) -> Vec<&ForwardingTarget> {
+        let targets = &self.config.targets;
+        if targets.is_empty() {
+            return Vec::new();
+        }
+
+        let total_weight: u64 = targets.iter().map(|target| u64::from(target.weight)).sum();
+        let by_weight = |position: u64| {
+            let mut position = position % total_weight;
+            targets
+                .iter()
+                .position(|target| {
+                    let weight = u64::from(target.weight);
+                    if position < weight {
+                        true
+                    } else {
+                        position -= weight;
+                        false
+                    }
+                })
+                .unwrap_or(0)
+        };
+
+        let selected = match self.config.load_balance {
+            LoadBalance::RoundRobin => self.forwarded_requests % targets.len(),
+            LoadBalance::Weighted if total_weight > 0 => by_weight(self.forwarded_requests as u64),
+            LoadBalance::Random if total_weight > 0 => by_weight(self.rng.below(total_weight)),
+            LoadBalance::Weighted | LoadBalance::Random => 0,
+        };
+
+        targets[selected..].iter().chain(&targets[..selected]).collect()
     }
 }
 
@@ -936,11 +1172,19 @@ impl ActiveForwardingRule {
 pub struct ActiveProxyRule {
     pub id: usize,
     pub config: ProxyRuleConfig,
+    #[cfg(feature = "proxy")]
+    #[serde(skip)]
+    pub(crate) rng: SharedRng,
 }
 
 impl ActiveProxyRule {
     pub fn new(id: usize, config: ProxyRuleConfig) -> Self {
-        ActiveProxyRule { id, config }
+        ActiveProxyRule {
+            id,
+            #[cfg(feature = "proxy")]
+            rng: SharedRng::new(config.random_seed),
+            config,
+        }
     }
 }
 
@@ -949,6 +1193,16 @@ pub struct ActiveRecording {
     pub id: usize,
     pub config: RecordingRuleConfig,
     pub mocks: Vec<MockDefinition>,
+    /// The number of interactions that were appended to the file of a streaming recording.
+    #[serde(default)]
+    pub streamed_interactions: usize,
+    /// The number of bytes that were appended to the file of a streaming recording.
+    #[serde(default)]
+    pub streamed_bytes: u64,
+    /// The indices of the recorded mocks, by the serialized request requirements of the mocks.
+    /// Used to find the mock of a repeated request without serializing all recorded mocks again.
+    #[serde(skip)]
+    pub(crate) mock_indices: HashMap<String, usize>,
 }
 
 impl ActiveRecording {
@@ -957,6 +1211,9 @@ impl ActiveRecording {
             id,
             config,
             mocks: vec![],
+            streamed_interactions: 0,
+            streamed_bytes: 0,
+            mock_indices: HashMap::new(),
         }
     }
 }
@@ -1060,23 +1317,292 @@ pub struct Mismatch {
 // *************************************************************************************************
 
 #[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct RecordingRuleConfig {
     pub request_requirements: RequestRequirements,
     pub record_headers: Vec<String>,
     pub record_response_delays: bool,
+    pub redact_headers: Vec<String>,
+    pub redact_json_fields: Vec<String>,
+    pub redact_query_params: Vec<String>,
+    pub redact_body_regex: Vec<(HttpMockRegex, String)>,
+    pub record_response_headers: Option<Vec<String>>,
+    pub drop_response_headers: Vec<String>,
+    pub normalize_response_headers: Vec<(String, String)>,
+    pub normalize_json_fields: Vec<(String, Value)>,
+    pub normalize_json_timestamps: Option<String>,
+    pub body_match_mode: BodyMatchMode,
+    pub ignore_query_params: Vec<String>,
+    pub exact_query: bool,
+    pub upstream_only: bool,
+    pub repeated_requests: RepeatedRequests,
+    pub stream_to: Option<PathBuf>,
+    pub stream_max_interactions: Option<usize>,
+    pub stream_max_bytes: Option<u64>,
+}
+
+/// Determines how mocks created from a recording match the body of a request.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BodyMatchMode {
+    /// The request body must be byte-for-byte equal to the recorded body.
+    #[default]
+    Exact,
+    /// The request body must be semantically equal to the recorded JSON body (i.e., the order
+    /// of object keys and whitespace do not matter). Bodies that are not JSON are matched exactly.
+    Json,
+    /// The request body must contain all fields of the recorded JSON body, but may contain
+    /// additional fields. Bodies that are not JSON are matched exactly.
+    JsonIncludes,
+    /// The request body is not matched at all.
+    None,
+}
+
+/// Determines how a recording handles requests that are recorded more than once.
+///
+/// Two requests are considered the same if the mocks created from them would have identical
+/// request requirements (i.e., after redaction and the request matching options were applied).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RepeatedRequests {
+    /// Every request creates a separate mock. On playback, only the first of several mocks
+    /// for the same request will ever match.
+    #[default]
+    Keep,
+    /// Only the first request creates a mock. Later responses to the same request are discarded.
+    Collapse,
+    /// The responses to the same request are combined into a single mock that serves them in the
+    /// order they were recorded. The last response is repeated once all responses were served.
+    Sequence,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ProxyRuleConfig {
     pub request_requirements: RequestRequirements,
     pub request_header: Vec<(String, String)>,
+    #[serde(default)]
+    pub response_transform: ResponseTransform,
+    /// The URL of an HTTP proxy to send requests through. Overrides the upstream proxy of the server.
+    #[serde(default)]
+    pub upstream_proxy: Option<String>,
+    #[serde(default)]
+    pub passthrough_tunnel: PassthroughTunnel,
+    #[serde(default)]
+    pub faults: FaultInjection,
+    /// The seed of the random number generator that decides which faults are injected.
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+}
+
+/// Determines which `CONNECT` tunnels a proxy rule passes through to the target host unmodified,
+/// instead of intercepting the TLS traffic.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PassthroughTunnel {
+    pub enabled: bool,
+    /// Host patterns (e.g., `example.com` or `*.example.com`) of the tunnels to pass through.
+    /// All hosts are passed through if empty.
+    pub allow_hosts: Vec<String>,
+    /// Host patterns of the tunnels that are never passed through. Takes precedence over
+    /// `allow_hosts`.
+    pub deny_hosts: Vec<String>,
+}
+
+impl PassthroughTunnel {
+    /// Returns true if a tunnel to `host` is passed through.
+    pub fn applies_to(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.')),
+                None => pattern == host,
+            }
+        };
+
+        self.enabled
+            && (self.allow_hosts.is_empty() || self.allow_hosts.iter().any(matches))
+            && !self.deny_hosts.iter().any(matches)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ForwardingRuleConfig {
+    /// The base URL of the first target. Rules that were created without `targets` forward all
+    /// requests to this URL, as in earlier versions.
+    #[deprecated(
+        since = "0.8.4",
+        note = "forwarding rules can have several targets, please use `targets` instead"
+    )]
+    #[serde(default)]
     pub target_base_url: String,
+    #[serde(default)]
+    pub targets: Vec<ForwardingTarget>,
+    #[serde(default)]
+    pub load_balance: LoadBalance,
+    #[serde(default)]
+    pub failover: bool,
     pub request_requirements: RequestRequirements,
     pub request_header: Vec<(String, String)>,
+    #[serde(default)]
+    pub fallback: bool,
+    #[serde(default)]
+    pub request_transform: RequestTransform,
+    #[serde(default)]
+    pub response_transform: ResponseTransform,
+    /// The URL of an HTTP proxy to send requests through. Overrides the upstream proxy of the server.
+    #[serde(default)]
+    pub upstream_proxy: Option<String>,
+    #[serde(default)]
+    pub faults: FaultInjection,
+    /// The seed of the random number generator that decides which faults are injected and,
+    /// with [LoadBalance::Random], which target a request is forwarded to.
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+}
+
+impl ForwardingRuleConfig {
+    /// Makes `targets` and the deprecated `target_base_url` describe the same first target:
+    /// `targets` is filled from `target_base_url` if only the latter was set, and
+    /// `target_base_url` from the first target otherwise.
+    #[allow(deprecated)]
+    pub(crate) fn sync_target_base_url(&mut self) {
+        match self.targets.first() {
+            Some(target) => self.target_base_url = target.base_url.clone(),
+            None if !self.target_base_url.is_empty() => {
+                self.targets = vec![ForwardingTarget::new(self.target_base_url.clone(), 1)]
+            }
+            None => {}
+        }
+    }
+}
+
+/// Latency, error responses and network faults that proxy and forwarding rules inject into
+/// upstream traffic.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FaultInjection {
+    /// The delay (in milliseconds) added to every response.
+    pub delay: Option<u64>,
+    /// The probability (between 0 and 1) with which `error_response` is returned instead of
+    /// sending the request upstream.
+    pub error_rate: f64,
+    pub error_response: Option<HttpMockResponse>,
+    /// Faults paired with the probability (between 0 and 1) with which they occur.
+    pub faults: Vec<(f64, Fault)>,
+}
+
+impl FaultInjection {
+    pub fn is_empty(&self) -> bool {
+        self.delay.is_none() && self.error_response.is_none() && self.faults.is_empty()
+    }
+
+    /// Returns true if all probabilities are between 0 and 1.
+    pub fn has_valid_probabilities(&self) -> bool {
+        std::iter::once(self.error_rate)
+            .chain(self.faults.iter().map(|(probability, _)| *probability))
+            .all(|probability| (0.0..=1.0).contains(&probability))
+    }
+}
+
+/// A fault that proxy and forwarding rules can inject into upstream traffic
+/// (see [ProxyRuleBuilder::fault](crate::ProxyRuleBuilder::fault)).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Closes the connection to the client without sending a response. The request is not sent
+    /// upstream.
+    CloseConnection,
+    /// Returns the upstream response with an empty body.
+    EmptyBody,
+    /// Delays the response by the given duration on top of any other delay (e.g., to simulate
+    /// occasional latency spikes).
+    Delay(Duration),
+}
+
+/// A server that a forwarding rule forwards requests to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ForwardingTarget {
+    /// The base URL of the server (e.g., `http://localhost:8080`).
+    pub base_url: String,
+    /// The relative share of requests this target receives with [LoadBalance::Weighted] and
+    /// [LoadBalance::Random]. Targets with weight 0 are never selected by these strategies and
+    /// only receive requests through failover.
+    pub weight: u32,
+}
+
+impl ForwardingTarget {
+    pub fn new<IntoString: Into<String>>(base_url: IntoString, weight: u32) -> Self {
+        Self {
+            base_url: base_url.into(),
+            weight,
+        }
+    }
+}
+
+/// Determines which target a forwarding rule with several targets forwards a request to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoadBalance {
+    /// Requests are forwarded to the targets in turn.
+    #[default]
+    RoundRobin,
+    /// Requests are distributed in proportion to the weights of the targets, deterministically:
+    /// with weights 9 and 1, nine requests are forwarded to the first target, then one request
+    /// to the second target, and so on. Targets with weight 0 are only reachable through failover.
+    Weighted,
+    /// Each request is forwarded to a randomly chosen target, where the probability of a target
+    /// being chosen is proportional to its weight. The target is picked by its position in the
+    /// cumulative weights (like [LoadBalance::Weighted]), so targets with weight 0 are never chosen
+    /// and only reachable through failover.
+    Random,
+}
+
+/// Modifications that forwarding rules apply to requests before they are sent to the target
+/// server. Headers are removed before the headers of the rule are added, and path prefixes are
+/// stripped before paths are rewritten.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RequestTransform {
+    pub remove_header: Vec<String>,
+    pub strip_path_prefix: Option<String>,
+    pub rewrite_path: Vec<(HttpMockRegex, String)>,
+    pub set_query_param: Vec<(String, String)>,
+    #[serde(skip)]
+    pub transform_json_body: Option<JsonBodyTransform>,
+}
+
+impl RequestTransform {
+    pub fn is_empty(&self) -> bool {
+        self.remove_header.is_empty()
+            && self.strip_path_prefix.is_none()
+            && self.rewrite_path.is_empty()
+            && self.set_query_param.is_empty()
+            && self.transform_json_body.is_none()
+    }
+}
+
+/// Modifications that proxy and forwarding rules apply to responses received from the upstream
+/// server, in the order of the fields.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ResponseTransform {
+    pub remove_header: Vec<String>,
+    pub add_header: Vec<(String, String)>,
+    pub status: Option<u16>,
+    pub replace_body_regex: Vec<(HttpMockRegex, String)>,
+    pub json_merge_patch: Vec<Value>,
+    #[serde(skip)]
+    pub map_response: Option<ResponseMapper>,
+}
+
+impl ResponseTransform {
+    pub fn is_empty(&self) -> bool {
+        self.remove_header.is_empty()
+            && self.add_header.is_empty()
+            && self.status.is_none()
+            && self.replace_body_regex.is_empty()
+            && self.json_merge_patch.is_empty()
+            && self.map_response.is_none()
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -1281,6 +1807,8 @@ pub struct StaticRequestRequirements {
     pub json_body_includes: Option<Vec<Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub json_body_excludes: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_body_path: Option<Vec<String>>,
 
     // x-www-form-urlencoded fields
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1307,6 +1835,36 @@ pub struct StaticRequestRequirements {
     pub form_urlencoded_matches: Option<Vec<NameValuePatternPair>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub form_urlencoded_count: Option<Vec<KeyValuePatternCountTriple>>,
+
+    // Client certificate-related fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_present: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_subject: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_san: Option<Vec<String>>,
+
+    // TLS connection-related fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_sni: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_version_at_least: Option<TlsVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+
+    // Protocol-related fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<String>,
+
+    // Named predicates registered on the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate_not: Option<Vec<String>>,
+
+    // Scripted predicates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_predicate: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1321,6 +1879,98 @@ pub struct StaticHTTPResponse {
     pub body_base64: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub delay: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback: Option<Vec<StaticCallbackDefinition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<Vec<StaticHTTPResponse>>,
+}
+
+impl From<StaticHTTPResponse> for MockServerHttpResponse {
+    fn from(value: StaticHTTPResponse) -> Self {
+        MockServerHttpResponse {
+            status: value.status,
+            headers: from_name_value_string_pair_vec(value.header),
+            body: from_string_to_bytes_choose(value.body, value.body_base64),
+            delay: value.delay,
+            respond_with: None,
+            wait_for: None,
+            responder: value.responder,
+            script: value.script,
+            callbacks: value.callback.map(|callbacks| {
+                callbacks
+                    .into_iter()
+                    .map(|cb| CallbackDefinition {
+                        method: cb.method,
+                        url: Some(cb.url),
+                        headers: from_name_value_string_pair_vec(cb.header),
+                        body: cb.body,
+                        json_body: cb.json_body,
+                        delay: cb.delay,
+                    })
+                    .collect()
+            }),
+            sequence: value
+                .sequence
+                .map(|sequence| sequence.into_iter().map(MockServerHttpResponse::from).collect()),
+        }
+    }
+}
+
+impl From<MockServerHttpResponse> for StaticHTTPResponse {
+    fn from(value: MockServerHttpResponse) -> Self {
+        let (body, body_base64) = from_bytes_to_string(value.body);
+
+        StaticHTTPResponse {
+            status: value.status,
+            header: to_name_value_string_pair_vec(value.headers),
+            body,
+            body_base64,
+            // Reason for the cast to u64: The Duration::as_millis method returns the total
+            // number of milliseconds contained within the Duration as a u128. This is
+            // because Duration::as_millis needs to handle larger values that
+            // can result from multiplying the seconds (stored internally as a u64)
+            // by 1000 and adding the milliseconds (also a u64), potentially
+            // exceeding the u64 limit.
+            delay: value.delay,
+            responder: value.responder,
+            script: value.script,
+            callback: value.callbacks.map(|callbacks| {
+                callbacks
+                    .into_iter()
+                    .map(|cb| StaticCallbackDefinition {
+                        method: cb.method,
+                        url: cb.url.unwrap_or_default(),
+                        header: to_name_value_string_pair_vec(cb.headers),
+                        body: cb.body,
+                        json_body: cb.json_body,
+                        delay: cb.delay,
+                    })
+                    .collect()
+            }),
+            sequence: value
+                .sequence
+                .map(|sequence| sequence.into_iter().map(StaticHTTPResponse::from).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StaticCallbackDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Vec<NameValueStringPair>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1425,6 +2075,8 @@ impl TryInto<MockDefinition> for StaticMockDefinition {
                 json_body_not: self.when.json_body_not,
                 json_body_includes: self.when.json_body_includes,
                 json_body_excludes: self.when.json_body_excludes,
+                json_body_path: self.when.json_body_path,
+                resolved_json_body_path: None,
 
                 // x-www-form-urlencoded fields
                 form_urlencoded_tuple: from_name_value_string_pair_vec(self.when.form_urlencoded_tuple),
@@ -1441,17 +2093,34 @@ impl TryInto<MockDefinition> for StaticMockDefinition {
 
                 form_urlencoded_tuple_count: from_key_value_pattern_count_triple_vec(self.when.form_urlencoded_count),
 
+                // Client certificate
+                client_cert_present: self.when.client_cert_present,
+                client_cert_subject: self.when.client_cert_subject,
+                client_cert_san: self.when.client_cert_san,
+
+                // TLS connection
+                tls_sni: self.when.tls_sni,
+                tls_version_at_least: self.when.tls_version_at_least,
+                alpn: self.when.alpn,
+
+                // Protocol
+                http_version: self.when.http_version,
+
                 // Boolean dynamic checks
                 is_true: None,
                 is_false: None,
+
+                // Named predicates registered on the server
+                predicate: self.when.predicate,
+                predicate_not: self.when.predicate_not,
+                resolved_predicate: None,
+                resolved_predicate_not: None,
+
+                // Scripted predicates
+                script_predicate: self.when.script_predicate,
+                resolved_script_predicate: None,
             },
-            response: MockServerHttpResponse {
-                status: self.then.status,
-                headers: from_name_value_string_pair_vec(self.then.header),
-                body: from_string_to_bytes_choose(self.then.body, self.then.body_base64),
-                delay: self.then.delay,
-                respond_with: None,
-            },
+            response: self.then.into(),
         })
     }
 }
@@ -1592,8 +2261,6 @@ impl TryFrom<&MockDefinition> for StaticMockDefinition {
     fn try_from(value: &MockDefinition) -> Result<Self, Self::Error> {
         let value = value.clone();
 
-        let (response_body, response_body_base64) = from_bytes_to_string(value.response.body);
-
         let (request_body, request_body_base64) = from_bytes_to_string(value.request.body);
         let (request_body_not, request_body_not_base64) = bytes_to_string_vec(value.request.body_not);
         let (request_body_includes, request_body_includes_base64) = bytes_to_string_vec(value.request.body_includes);
@@ -1712,6 +2379,7 @@ impl TryFrom<&MockDefinition> for StaticMockDefinition {
                 json_body_not: value.request.json_body_not,
                 json_body_includes: value.request.json_body_includes,
                 json_body_excludes: value.request.json_body_excludes,
+                json_body_path: value.request.json_body_path,
 
                 // Form URL-encoded fields
                 form_urlencoded_tuple: to_name_value_string_pair_vec(value.request.form_urlencoded_tuple),
@@ -1731,20 +2399,28 @@ impl TryFrom<&MockDefinition> for StaticMockDefinition {
                 form_urlencoded_matches: to_name_value_pattern_pair_vec(value.request.form_urlencoded_tuple_matches),
 
                 form_urlencoded_count: to_key_value_pattern_count_triple_vec(value.request.form_urlencoded_tuple_count),
+
+                // Client certificate
+                client_cert_present: value.request.client_cert_present,
+                client_cert_subject: value.request.client_cert_subject,
+                client_cert_san: value.request.client_cert_san,
+
+                // TLS connection
+                tls_sni: value.request.tls_sni,
+                tls_version_at_least: value.request.tls_version_at_least,
+                alpn: value.request.alpn,
+
+                // Protocol
+                http_version: value.request.http_version,
+
+                // Named predicates registered on the server
+                predicate: value.request.predicate,
+                predicate_not: value.request.predicate_not,
+
+                // Scripted predicates
+                script_predicate: value.request.script_predicate,
             },
-            then: StaticHTTPResponse {
-                status: value.response.status,
-                header: to_name_value_string_pair_vec(value.response.headers),
-                body: response_body,
-                body_base64: response_body_base64,
-                // Reason for the cast to u64: The Duration::as_millis method returns the total
-                // number of milliseconds contained within the Duration as a u128. This is
-                // because Duration::as_millis needs to handle larger values that
-                // can result from multiplying the seconds (stored internally as a u64)
-                // by 1000 and adding the milliseconds (also a u64), potentially
-                // exceeding the u64 limit.
-                delay: value.response.delay,
-            },
+            then: value.response.into(),
         })
     }
 }
@@ -0,0 +1,198 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{oneshot, watch};
+
+use crate::common::{data::HttpMockResponse, util::Join};
+
+/// A handle that holds back responses of a mock until the test explicitly releases them.
+///
+/// Requests matching a mock that was configured with
+/// [Then::wait_for](struct.Then.html#method.wait_for) are parked on the mock server
+/// until the test calls [ResponseGate::release](#method.release) or
+/// [ResponseGate::release_with](#method.release_with). This allows to deterministically
+/// test concurrency, cancellation and timeout behaviour of a client without relying on
+/// sleep-based timing.
+///
+/// Releases that happen while no request is parked are not lost: they are kept and consumed
+/// by the next requests that arrive at the gate, in the order they were issued.
+///
+/// A `ResponseGate` is cheap to clone. All clones refer to the same gate.
+///
+/// **Attention**: Response gates are only supported by the local mock server. They cannot
+/// be used with remote/standalone mock servers.
+#[derive(Clone, Default)]
+pub struct ResponseGate {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    parked: watch::Sender<usize>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            parked: watch::Sender::new(0),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    next_waiter_id: usize,
+    waiters: VecDeque<Waiter>,
+    permits: VecDeque<Option<HttpMockResponse>>,
+}
+
+struct Waiter {
+    id: usize,
+    sender: oneshot::Sender<Option<HttpMockResponse>>,
+}
+
+impl ResponseGate {
+    /// Creates a new, closed gate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of requests that are currently parked at this gate.
+    pub fn pending(&self) -> usize {
+        self.inner.state.lock().unwrap().waiters.len()
+    }
+
+    /// Lets the oldest parked request continue and respond as configured on the mock.
+    /// If no request is currently parked, the next request that arrives passes the gate
+    /// without waiting.
+    pub fn release(&self) {
+        self.open(None)
+    }
+
+    /// Lets the oldest parked request continue and respond with the provided response
+    /// instead of the one configured on the mock. If no request is currently parked, the
+    /// next request that arrives will receive this response without waiting.
+    ///
+    /// # Parameters
+    /// - `response`: The response to send for the released request.
+    pub fn release_with<IntoResponse: Into<HttpMockResponse>>(&self, response: IntoResponse) {
+        self.open(Some(response.into()))
+    }
+
+    /// Lets all currently parked requests continue. Requests arriving afterwards will be
+    /// parked again.
+    ///
+    /// # Returns
+    /// The number of requests that were released.
+    pub fn release_all(&self) -> usize {
+        let mut state = self.inner.state.lock().unwrap();
+
+        let mut released = 0;
+        while let Some(waiter) = state.waiters.pop_front() {
+            if waiter.sender.send(None).is_ok() {
+                released += 1;
+            }
+        }
+
+        self.inner.parked.send_replace(0);
+
+        released
+    }
+
+    /// Blocks the current thread until at least `count` requests are parked at this gate.
+    ///
+    /// # Parameters
+    /// - `count`: The number of parked requests to wait for.
+    pub fn wait_for_pending(&self, count: usize) {
+        self.wait_for_pending_async(count).join()
+    }
+
+    /// Waits asynchronously until at least `count` requests are parked at this gate.
+    ///
+    /// # Parameters
+    /// - `count`: The number of parked requests to wait for.
+    pub async fn wait_for_pending_async(&self, count: usize) {
+        let mut receiver = self.inner.parked.subscribe();
+        receiver
+            .wait_for(|parked| *parked >= count)
+            .await
+            .expect("response gate was closed unexpectedly");
+    }
+
+    fn open(&self, response: Option<HttpMockResponse>) {
+        let mut state = self.inner.state.lock().unwrap();
+
+        let mut response = response;
+        while let Some(waiter) = state.waiters.pop_front() {
+            match waiter.sender.send(response) {
+                Ok(()) => {
+                    self.inner.parked.send_replace(state.waiters.len());
+                    return;
+                }
+                // The request was cancelled in the meantime, so try the next one.
+                Err(returned) => response = returned,
+            }
+        }
+
+        state.permits.push_back(response);
+        self.inner.parked.send_replace(0);
+    }
+
+    /// Parks the current request until it is released. Returns the response the request
+    /// was released with, if any.
+    pub(crate) async fn pass(&self) -> Option<HttpMockResponse> {
+        let (receiver, _guard) = {
+            let mut state = self.inner.state.lock().unwrap();
+
+            if let Some(response) = state.permits.pop_front() {
+                return response;
+            }
+
+            let id = state.next_waiter_id;
+            state.next_waiter_id += 1;
+
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push_back(Waiter { id, sender });
+            self.inner.parked.send_replace(state.waiters.len());
+
+            (
+                receiver,
+                WaiterGuard {
+                    gate: self.inner.clone(),
+                    id,
+                },
+            )
+        };
+
+        receiver.await.unwrap_or(None)
+    }
+}
+
+impl fmt::Debug for ResponseGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.state.lock().unwrap();
+        f.debug_struct("ResponseGate")
+            .field("pending", &state.waiters.len())
+            .field("permits", &state.permits.len())
+            .finish()
+    }
+}
+
+/// Removes a parked request from the gate when the request is dropped before it has been
+/// released (e.g., because the client disconnected).
+struct WaiterGuard {
+    gate: Arc<Inner>,
+    id: usize,
+}
+
+impl Drop for WaiterGuard {
+    fn drop(&mut self) {
+        let mut state = self.gate.state.lock().unwrap();
+        state.waiters.retain(|w| w.id != self.id);
+        self.gate.parked.send_replace(state.waiters.len());
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// A single step of a JSONPath expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// `.name`, `['name']` or `["name"]`
+    Key(String),
+    /// `[0]`, or `[-1]` for the last element
+    Index(i64),
+    /// `.*` or `[*]`
+    Wildcard,
+    /// `..name`, `..*` or `..[0]`: the following segment is applied at any depth.
+    Descendant(Box<Segment>),
+}
+
+/// A parsed JSONPath expression.
+///
+/// Only the subset of JSONPath that selects values by location is supported: the root `$`,
+/// child keys (`.name`, `['name']`), array indices (`[0]`, `[-1]`), wildcards (`.*`, `[*]`)
+/// and recursive descent (`..name`). Filter expressions (`[?(...)]`), unions and slices are
+/// not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JsonPath {
+    expression: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut rest = expression
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| format!("JSONPath expression '{}' must start with '$'", expression))?;
+
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            let (segment, remaining) = if let Some(after) = rest.strip_prefix("..") {
+                let (segment, remaining) = if after.starts_with('[') {
+                    parse_bracket(after, expression)?
+                } else {
+                    parse_name(after, expression)?
+                };
+                (Segment::Descendant(Box::new(segment)), remaining)
+            } else if let Some(after) = rest.strip_prefix('.') {
+                parse_name(after, expression)?
+            } else if rest.starts_with('[') {
+                parse_bracket(rest, expression)?
+            } else {
+                return Err(format!("unexpected '{}' in JSONPath expression '{}'", rest, expression));
+            };
+
+            segments.push(segment);
+            rest = remaining;
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            segments,
+        })
+    }
+
+    /// Returns all values in `value` that are selected by this path.
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![value];
+
+        for segment in &self.segments {
+            current = current
+                .into_iter()
+                .flat_map(|value| select_segment(segment, value))
+                .collect();
+        }
+
+        current
+    }
+
+    /// Returns `true` if this path selects at least one value in `value`.
+    pub fn exists(&self, value: &Value) -> bool {
+        !self.select(value).is_empty()
+    }
+
+    /// Calls `f` with every value in `value` that is selected by this path.
+    pub fn for_each_mut(&self, value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+        visit_mut(&self.segments, value, f);
+    }
+
+    /// Removes all values that are selected by this path from `value` and returns how many
+    /// values were removed. The root value itself cannot be removed.
+    pub fn remove(&self, value: &mut Value) -> usize {
+        let Some((last, parents)) = self.segments.split_last() else {
+            return 0;
+        };
+
+        let mut removed = 0;
+        visit_mut(parents, value, &mut |parent| removed += remove_segment(last, parent));
+        removed
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn parse_name<'a>(input: &'a str, expression: &str) -> Result<(Segment, &'a str), String> {
+    let end = input.find(['.', '[']).unwrap_or(input.len());
+    let name = &input[..end];
+
+    match name {
+        "" => Err(format!("missing key in JSONPath expression '{}'", expression)),
+        "*" => Ok((Segment::Wildcard, &input[end..])),
+        name => Ok((Segment::Key(name.to_string()), &input[end..])),
+    }
+}
+
+fn parse_bracket<'a>(input: &'a str, expression: &str) -> Result<(Segment, &'a str), String> {
+    let inner = &input[1..];
+
+    for quote in ['\'', '"'] {
+        if let Some(quoted) = inner.strip_prefix(quote) {
+            let end = quoted
+                .find(quote)
+                .ok_or_else(|| format!("unterminated key in JSONPath expression '{}'", expression))?;
+            let remaining = quoted[end + 1..]
+                .strip_prefix(']')
+                .ok_or_else(|| format!("missing ']' in JSONPath expression '{}'", expression))?;
+            return Ok((Segment::Key(quoted[..end].to_string()), remaining));
+        }
+    }
+
+    let end = inner
+        .find(']')
+        .ok_or_else(|| format!("missing ']' in JSONPath expression '{}'", expression))?;
+    let content = inner[..end].trim();
+    let remaining = &inner[end + 1..];
+
+    if content == "*" {
+        return Ok((Segment::Wildcard, remaining));
+    }
+
+    content.parse::<i64>().map(|index| (Segment::Index(index), remaining)).map_err(|_| {
+        format!(
+            "unsupported selector '[{}]' in JSONPath expression '{}' (only keys, indices and wildcards are supported)",
+            content, expression
+        )
+    })
+}
+
+fn select_segment<'a>(segment: &Segment, value: &'a Value) -> Vec<&'a Value> {
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (Segment::Index(index), Value::Array(values)) => resolve_index(*index, values.len())
+            .and_then(|index| values.get(index))
+            .into_iter()
+            .collect(),
+        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+        (Segment::Wildcard, Value::Array(values)) => values.iter().collect(),
+        (Segment::Descendant(segment), value) => {
+            let mut result = select_segment(segment, value);
+            let children: Vec<&Value> = match value {
+                Value::Object(map) => map.values().collect(),
+                Value::Array(values) => values.iter().collect(),
+                _ => Vec::new(),
+            };
+            for child in children {
+                result.extend(select_segment(&Segment::Descendant(segment.clone()), child));
+            }
+            result
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn visit_mut(segments: &[Segment], value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    let Some((segment, rest)) = segments.split_first() else {
+        f(value);
+        return;
+    };
+
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(key) {
+                visit_mut(rest, child, f);
+            }
+        }
+        (Segment::Index(index), Value::Array(values)) => {
+            if let Some(index) = resolve_index(*index, values.len()) {
+                visit_mut(rest, &mut values[index], f);
+            }
+        }
+        (Segment::Wildcard, value) => {
+            for child in children_mut(value) {
+                visit_mut(rest, child, f);
+            }
+        }
+        (Segment::Descendant(segment), value) => {
+            let mut here = vec![segment.as_ref().clone()];
+            here.extend_from_slice(rest);
+            visit_mut(&here, value, f);
+
+            for child in children_mut(value) {
+                visit_mut(segments, child, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn remove_segment(segment: &Segment, parent: &mut Value) -> usize {
+    match (segment, parent) {
+        (Segment::Key(key), Value::Object(map)) => usize::from(map.remove(key).is_some()),
+        (Segment::Index(index), Value::Array(values)) => match resolve_index(*index, values.len()) {
+            Some(index) => {
+                values.remove(index);
+                1
+            }
+            None => 0,
+        },
+        (Segment::Wildcard, Value::Object(map)) => {
+            let removed = map.len();
+            map.clear();
+            removed
+        }
+        (Segment::Wildcard, Value::Array(values)) => {
+            let removed = values.len();
+            values.clear();
+            removed
+        }
+        (Segment::Descendant(segment), parent) => {
+            let mut removed = remove_segment(segment, parent);
+            for child in children_mut(parent) {
+                removed += remove_segment(&Segment::Descendant(segment.clone()), child);
+            }
+            removed
+        }
+        _ => 0,
+    }
+}
+
+fn children_mut(value: &mut Value) -> Vec<&mut Value> {
+    match value {
+        Value::Object(map) => map.values_mut().collect(),
+        Value::Array(values) => values.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Resolves a possibly negative array index (counting from the end) to a position in an array
+/// of length `len`.
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    usize::try_from(index).ok().filter(|index| *index < len)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::JsonPath;
+
+    #[test]
+    fn select_test() {
+        let value = json!({
+            "store": {
+                "books": [
+                    { "title": "A", "price": 8 },
+                    { "title": "B", "isbn": "123" }
+                ],
+                "name": "Corner Shop"
+            }
+        });
+
+        let select = |path: &str| JsonPath::parse(path).unwrap().select(&value);
+
+        assert_eq!(select("$"), vec![&value]);
+        assert_eq!(select("$.store.name"), vec!["Corner Shop"]);
+        assert_eq!(select("$['store']['name']"), vec!["Corner Shop"]);
+        assert_eq!(select("$.store.books[1].title"), vec!["B"]);
+        assert_eq!(select("$.store.books[-1].isbn"), vec!["123"]);
+        assert_eq!(select("$.store.books[*].title"), vec!["A", "B"]);
+        assert_eq!(select("$..title"), vec!["A", "B"]);
+        assert_eq!(select("$..isbn"), vec!["123"]);
+        assert!(select("$.store.books[2]").is_empty());
+        assert!(select("$.store.name.first").is_empty());
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        assert!(JsonPath::parse("store.name").is_err());
+        assert!(JsonPath::parse("$.store[?(@.price < 10)]").is_err());
+        assert!(JsonPath::parse("$.store[0:2]").is_err());
+        assert!(JsonPath::parse("$.store['name'").is_err());
+        assert!(JsonPath::parse("$.").is_err());
+    }
+
+    #[test]
+    fn modify_test() {
+        let mut value = json!({
+            "user": { "name": "Alice", "password": "secret" },
+            "tokens": [{ "password": "a" }, { "password": "b", "id": 1 }]
+        });
+
+        JsonPath::parse("$.user.name")
+            .unwrap()
+            .for_each_mut(&mut value, &mut |v| *v = json!("[REDACTED]"));
+        assert_eq!(value["user"]["name"], "[REDACTED]");
+
+        assert_eq!(JsonPath::parse("$..password").unwrap().remove(&mut value), 3);
+        assert_eq!(
+            value,
+            json!({ "user": { "name": "[REDACTED]" }, "tokens": [{}, { "id": 1 }] })
+        );
+
+        assert_eq!(JsonPath::parse("$.tokens[-1]").unwrap().remove(&mut value), 1);
+        assert_eq!(JsonPath::parse("$").unwrap().remove(&mut value), 0);
+        assert_eq!(value["tokens"], json!([{}]));
+    }
+}
@@ -1,4 +1,8 @@
 pub(crate) mod data;
+pub(crate) mod gate;
+pub(crate) mod json_path;
+#[cfg(feature = "proxy")]
+pub(crate) mod random;
 pub(crate) mod runtime;
 pub mod util;
 
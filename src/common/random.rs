@@ -0,0 +1,41 @@
+use std::sync::{Arc, Mutex};
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+/// The random number generator of a proxy or forwarding rule. It decides which faults are
+/// injected into a response and which target a request is forwarded to with
+/// `LoadBalance::Random`.
+///
+/// Clones share the state of the generator, so a seeded generator produces the same sequence
+/// of values for a rule, no matter how often the rule is cloned.
+#[derive(Clone)]
+pub(crate) struct SharedRng(Arc<Mutex<StdRng>>);
+
+impl SharedRng {
+    /// Creates a generator from `seed`, or from the operating system's entropy source if there
+    /// is no seed.
+    pub(crate) fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => rand::make_rng(),
+        };
+
+        Self(Arc::new(Mutex::new(rng)))
+    }
+
+    /// Returns true with the given probability (between 0 and 1).
+    pub(crate) fn occurs(&self, probability: f64) -> bool {
+        self.0.lock().unwrap().random::<f64>() < probability
+    }
+
+    /// Returns a random number that is smaller than `bound`, which must not be 0.
+    pub(crate) fn below(&self, bound: u64) -> u64 {
+        self.0.lock().unwrap().random_range(0..bound)
+    }
+}
+
+impl Default for SharedRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
@@ -97,14 +97,20 @@
 mod common;
 
 pub use api::{Method, Mock, MockExt, MockServer, Regex, Then, When};
-pub use common::data::{HttpMockRequest, HttpMockResponse};
+pub use common::{
+    data::{ClientCertificate, HttpMockRequest, HttpMockResponse, TlsInfo, TlsVersion},
+    gate::ResponseGate,
+};
 mod api;
 pub mod server;
 
-#[cfg(feature = "proxy")]
-pub use api::{ForwardingRule, ForwardingRuleBuilder, ProxyRule, ProxyRuleBuilder};
 #[cfg(feature = "record")]
-pub use api::{Recording, RecordingRuleBuilder};
+pub use api::{BodyMatchMode, CassetteMode, Recording, RecordingRuleBuilder, RepeatedRequests};
+#[cfg(feature = "proxy")]
+pub use api::{
+    CallbackBuilder, Fault, ForwardingRule, ForwardingRuleBuilder, ForwardingTarget, IntoForwardingTargets,
+    LoadBalance, ProxyRule, ProxyRuleBuilder,
+};
 
 pub mod prelude {
     #[doc(no_inline)]
@@ -1,6 +1,8 @@
 use std::{env, path::PathBuf};
 
 use clap::Parser;
+#[cfg(feature = "https")]
+use clap::Subcommand;
 use httpmock::server::HttpMockServerBuilder;
 use tracing_subscriber::EnvFilter;
 
@@ -18,6 +20,50 @@ struct ExecutionParameters {
     pub disable_access_log: bool,
     #[clap(short, long, env = "HTTPMOCK_REQUEST_HISTORY_LIMIT", default_value = "100")]
     pub request_history_limit: usize,
+    #[clap(long, env = "HTTPMOCK_OPENAPI_FILE")]
+    pub openapi: Option<PathBuf>,
+    #[clap(long, env = "HTTPMOCK_OPENAPI_VALIDATE")]
+    pub openapi_validate: bool,
+    #[clap(long, env = "HTTPMOCK_UPSTREAM_PROXY")]
+    pub upstream_proxy: Option<String>,
+    #[clap(long, env = "HTTPMOCK_RECORDING_STREAM_DIR")]
+    pub recording_stream_dir: Option<PathBuf>,
+    #[cfg(feature = "https")]
+    #[clap(long, env = "HTTPMOCK_CA_CERT_FILE", requires = "ca_key_file")]
+    pub ca_cert_file: Option<PathBuf>,
+    #[cfg(feature = "https")]
+    #[clap(long, env = "HTTPMOCK_CA_KEY_FILE", requires = "ca_cert_file")]
+    pub ca_key_file: Option<PathBuf>,
+    #[cfg(feature = "https")]
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[cfg(feature = "https")]
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manages the CA that signs the certificates of HTTPS connections.
+    #[clap(subcommand)]
+    Ca(CaCommand),
+}
+
+#[cfg(feature = "https")]
+#[derive(Subcommand, Debug)]
+enum CaCommand {
+    /// Generates a new CA certificate and private key.
+    Generate {
+        #[clap(long, default_value = "httpmock-ca.pem")]
+        cert_file: PathBuf,
+        #[clap(long, default_value = "httpmock-ca.key")]
+        key_file: PathBuf,
+    },
+    /// Exports the CA certificate of a running mock server. Prints it to stdout if no file is given.
+    Export {
+        #[clap(long, default_value = "127.0.0.1:5050")]
+        server: String,
+        #[clap(long)]
+        cert_file: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
@@ -28,6 +74,15 @@ async fn main() {
 
     let params: ExecutionParameters = ExecutionParameters::parse();
 
+    #[cfg(feature = "https")]
+    if let Some(Command::Ca(command)) = params.command {
+        if let Err(err) = run_ca_command(command).await {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tracing::info!("██╗  ██╗████████╗████████╗██████╗ ███╗   ███╗ ██████╗  ██████╗██╗  ██╗");
     tracing::info!("██║  ██║╚══██╔══╝╚══██╔══╝██╔══██╗████╗ ████║██╔═══██╗██╔════╝██║ ██╔╝");
     tracing::info!("███████║   ██║      ██║   ██████╔╝██╔████╔██║██║   ██║██║     █████╔╝");
@@ -43,14 +98,19 @@ async fn main() {
 
     tracing::info!("{params:?}");
 
-    let server = HttpMockServerBuilder::new()
+    let builder = HttpMockServerBuilder::new()
         .port(params.port)
         .expose(params.expose)
         .print_access_log(!params.disable_access_log)
         .history_limit(params.request_history_limit)
         .static_mock_dir_option(params.mock_files_dir)
-        .build()
-        .unwrap();
+        .recording_stream_dir_option(params.recording_stream_dir)
+        .openapi_spec_option(params.openapi)
+        .openapi_validate_requests(params.openapi_validate)
+        .upstream_proxy_option(params.upstream_proxy);
+    #[cfg(feature = "https")]
+    let builder = builder.https_ca_key_pair_files_option(params.ca_cert_file, params.ca_key_file);
+    let server = builder.build().unwrap();
 
     server
         .start_with_signals(None, shutdown_signal())
@@ -58,6 +118,58 @@ async fn main() {
         .expect("an error occurred during mock server execution");
 }
 
+#[cfg(feature = "https")]
+async fn run_ca_command(command: CaCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        CaCommand::Generate { cert_file, key_file } => {
+            if let Some(existing) = [&cert_file, &key_file].into_iter().find(|path| path.exists()) {
+                return Err(format!("'{}' already exists", existing.display()).into());
+            }
+
+            let (cert, key) = httpmock::server::generate_ca_certificate()?;
+            write_new_file(&key_file, &key, true)?;
+            write_new_file(&cert_file, &cert, false)?;
+            println!(
+                "Wrote CA certificate to '{}' and private key to '{}'",
+                cert_file.display(),
+                key_file.display()
+            );
+        }
+        CaCommand::Export { server, cert_file } => {
+            let cert = httpmock::MockServer::connect_async(&server)
+                .await
+                .ca_certificate_pem_async()
+                .await
+                .ok_or_else(|| format!("mock server at {} does not generate certificates", server))?;
+            match cert_file {
+                Some(path) => write_new_file(&path, &cert, false)?,
+                None => print!("{}", cert),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a file that must not exist yet, so that existing CA files are never overwritten.
+/// Private keys are only made readable for the current user.
+#[cfg(feature = "https")]
+fn write_new_file(path: &std::path::Path, content: &str, private: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    options.open(path)?.write_all(content.as_bytes())
+}
+
 #[cfg(not(target_os = "windows"))]
 async fn shutdown_signal() {
     let mut hangup_stream = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
@@ -0,0 +1,196 @@
+use std::{
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::Request;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::common::{
+    data::{CallbackDefinition, HttpMockRequest},
+    http::HttpClient,
+    runtime,
+};
+
+/// Matches placeholders such as `{{ request.path }}` or `{{request.header.x-request-id}}`.
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap());
+
+/// Holds back the callbacks of a served mock. The callbacks are sent as soon as all clones of
+/// the trigger were dropped.
+///
+/// The trigger is attached to the mock response as an extension. The transport moves it into
+/// the response body and hands it over to the client connection once hyper is done with the
+/// body. The connection drops it the next time it was flushed, so that callbacks are only sent
+/// after the response has been written to the socket. With HTTP/2, the client may not have read
+/// the response at that point yet.
+#[derive(Clone)]
+pub(crate) struct CallbackTrigger {
+    _release: Arc<oneshot::Sender<()>>,
+}
+
+/// Sends all callbacks of a mock in the background once the returned trigger was dropped.
+/// Callbacks are independent of each other and of the response to the triggering request,
+/// so failures are only logged.
+pub(crate) fn spawn_all(
+    http_client: &Arc<dyn HttpClient + Send + Sync + 'static>,
+    callbacks: Vec<CallbackDefinition>,
+    req: &HttpMockRequest,
+) -> CallbackTrigger {
+    let (sender, released) = oneshot::channel();
+    let http_client = http_client.clone();
+    let req = req.clone();
+
+    tokio::spawn(async move {
+        // The sender is never used, so this only returns once the trigger was dropped.
+        let _ = released.await;
+
+        for callback in callbacks {
+            let http_client = http_client.clone();
+            let req = req.clone();
+
+            tokio::spawn(async move {
+                if let Err(err) = send(http_client.as_ref(), &callback, &req).await {
+                    tracing::warn!("Cannot send callback to {:?}: {}", callback.url, err);
+                }
+            });
+        }
+    });
+
+    CallbackTrigger {
+        _release: Arc::new(sender),
+    }
+}
+
+async fn send(
+    http_client: &(dyn HttpClient + Send + Sync),
+    callback: &CallbackDefinition,
+    req: &HttpMockRequest,
+) -> Result<(), String> {
+    if let Some(delay) = callback.delay {
+        runtime::sleep(Duration::from_millis(delay)).await;
+    }
+
+    let url = callback.url.as_ref().ok_or("callback has no URL")?;
+
+    let mut builder = Request::builder()
+        .method(callback.method.as_deref().unwrap_or("POST"))
+        .uri(render_url(url, req));
+
+    for (name, value) in callback.headers.iter().flatten() {
+        builder = builder.header(name, render(value, req));
+    }
+
+    let body = match (&callback.json_body, &callback.body) {
+        (Some(json), _) => render_json(json, req).to_string(),
+        (None, Some(body)) => render(body, req),
+        (None, None) => String::new(),
+    };
+
+    let request = builder.body(Bytes::from(body)).map_err(|err| err.to_string())?;
+
+    let response = http_client.send(request).await.map_err(|err| err.to_string())?;
+
+    tracing::debug!("Callback to {} returned status {}", url, response.status());
+
+    Ok(())
+}
+
+/// Replaces all placeholders in `template` with values from the triggering request.
+///
+/// Supported placeholders are `request.method`, `request.scheme`, `request.uri`,
+/// `request.path`, `request.body`, `request.header.<name>`, `request.query.<name>` and
+/// `request.json.<path>`, where `<path>` is a dot-separated list of object keys and array
+/// indices. Unknown placeholders are replaced with an empty string.
+pub(crate) fn render(template: &str, req: &HttpMockRequest) -> String {
+    PLACEHOLDER
+        .replace_all(template, |caps: &Captures| resolve(&caps[1], req).unwrap_or_default())
+        .into_owned()
+}
+
+/// Replaces all placeholders in a URL template. Values are percent-encoded, so that they cannot
+/// change the structure of the URL (e.g., by adding a `/`, `?` or `#`). `request.scheme`,
+/// `request.uri` and `request.path` are inserted as they are, since they already are valid
+/// parts of a URL.
+fn render_url(template: &str, req: &HttpMockRequest) -> String {
+    PLACEHOLDER
+        .replace_all(template, |caps: &Captures| {
+            let value = resolve(&caps[1], req).unwrap_or_default();
+            match &caps[1] {
+                "request.scheme" | "request.uri" | "request.path" => value,
+                _ => percent_encode(&value),
+            }
+        })
+        .into_owned()
+}
+
+/// Percent-encodes all characters except the unreserved ones (RFC 3986, Section 2.3).
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Replaces all placeholders in the string values of a JSON document. Since the values are
+/// inserted into JSON strings, they are escaped when the document is serialized.
+fn render_json(template: &Value, req: &HttpMockRequest) -> Value {
+    match template {
+        Value::String(s) => Value::String(render(s, req)),
+        Value::Array(values) => Value::Array(values.iter().map(|value| render_json(value, req)).collect()),
+        Value::Object(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), render_json(value, req)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn resolve(expression: &str, req: &HttpMockRequest) -> Option<String> {
+    let expression = expression.strip_prefix("request.")?;
+
+    match expression {
+        "method" => return Some(req.method_str().to_string()),
+        "scheme" => return Some(req.scheme()),
+        "uri" => return Some(req.uri_str().to_string()),
+        "path" => return Some(req.uri().path().to_string()),
+        "body" => return Some(req.body_string()),
+        _ => {}
+    }
+
+    if let Some(name) = expression.strip_prefix("header.") {
+        return req
+            .headers_vec()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone());
+    }
+
+    if let Some(name) = expression.strip_prefix("query.") {
+        return req.query_params().into_iter().find(|(k, _)| k == name).map(|(_, v)| v);
+    }
+
+    if let Some(path) = expression.strip_prefix("json.") {
+        let json: Value = serde_json::from_slice(req.body_ref()).ok()?;
+        // Each segment is escaped as required by JSON pointers (RFC 6901).
+        let pointer: String = path
+            .split('.')
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect();
+        return json.pointer(&pointer).map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+    }
+
+    None
+}
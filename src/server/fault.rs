@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::Response;
+
+use crate::{
+    common::{
+        data::{Fault, FaultInjection},
+        random::SharedRng,
+        runtime,
+    },
+    server::{
+        handler::Error::{self, ConnectionClosedByFault, ResponseDataConversion},
+        transform::update_content_length,
+    },
+};
+
+/// Injects the latency, error responses and faults of a proxy or forwarding rule into the
+/// response to a request. `rng` is the random number generator of the rule.
+///
+/// `upstream` sends the request to the upstream server. It is not awaited if an error response
+/// is returned or the connection is closed instead.
+pub(crate) async fn inject_faults<Upstream>(
+    faults: &FaultInjection,
+    rng: &SharedRng,
+    upstream: Upstream,
+) -> Result<Response<Bytes>, Error>
+where
+    Upstream: Future<Output = Result<Response<Bytes>, Error>>,
+{
+    if faults.is_empty() {
+        return upstream.await;
+    }
+
+    let mut delay = Duration::from_millis(faults.delay.unwrap_or_default());
+    let mut close_connection = false;
+    let mut empty_body = false;
+
+    for (probability, fault) in &faults.faults {
+        if rng.occurs(*probability) {
+            match fault {
+                Fault::CloseConnection => close_connection = true,
+                Fault::EmptyBody => empty_body = true,
+                Fault::Delay(duration) => delay = delay.saturating_add(*duration),
+            }
+        }
+    }
+
+    if !delay.is_zero() {
+        runtime::sleep(delay).await;
+    }
+
+    if close_connection {
+        return Err(ConnectionClosedByFault);
+    }
+
+    if let Some(error_response) = &faults.error_response
+        && rng.occurs(faults.error_rate)
+    {
+        return error_response.try_into().map_err(ResponseDataConversion);
+    }
+
+    let mut response = upstream.await?;
+
+    if empty_body {
+        *response.body_mut() = Bytes::new();
+        update_content_length(response.headers_mut(), 0);
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,159 @@
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "scripting")]
+use crate::server::scripting;
+use crate::{
+    common::{
+        data::{
+            HttpMockRequest, HttpMockResponse, MockServerHttpResponse, RequestPredicate, RequestRequirements,
+            ResponseCallback,
+        },
+        json_path::JsonPath,
+    },
+    server::state::{Error, Error::UnregisteredFunction},
+};
+
+/// Named request predicates and responders that have been registered on the server.
+///
+/// Closures cannot be serialized, so mocks that are sent to a remote server or loaded from
+/// static mock files refer to server-side logic by name or provide it as a script instead.
+/// Names and scripts are resolved to functions whenever a mock or rule is added to the server.
+#[derive(Clone, Default)]
+pub(crate) struct FunctionRegistry {
+    predicates: HashMap<String, RequestPredicate>,
+    responders: HashMap<String, ResponseCallback>,
+}
+
+impl FunctionRegistry {
+    pub fn add_predicate<F>(&mut self, name: String, predicate: F)
+    where
+        F: Fn(&HttpMockRequest) -> bool + Send + Sync + 'static,
+    {
+        self.predicates.insert(name, Arc::new(predicate));
+    }
+
+    pub fn add_responder<F>(&mut self, name: String, responder: F)
+    where
+        F: Fn(&HttpMockRequest) -> HttpMockResponse + Send + Sync + 'static,
+    {
+        self.responders.insert(name, Arc::new(responder));
+    }
+
+    pub fn extend(&mut self, other: FunctionRegistry) {
+        self.predicates.extend(other.predicates);
+        self.responders.extend(other.responders);
+    }
+
+    /// Looks up all named predicates, compiles all scripts and parses all JSONPath expressions
+    /// referenced by `requirements` and stores the results alongside them, so that they do not
+    /// have to be resolved for every request. Resolving the same requirements twice is a no-op.
+    pub fn resolve_request_requirements(&self, requirements: &mut RequestRequirements) -> Result<(), Error> {
+        requirements.resolved_predicate = self.resolve_predicates(&requirements.predicate)?;
+        requirements.resolved_predicate_not = self.resolve_predicates(&requirements.predicate_not)?;
+        requirements.resolved_script_predicate = compile_script_predicates(&requirements.script_predicate)?;
+        requirements.resolved_json_body_path = parse_json_paths(&requirements.json_body_path)?;
+        Ok(())
+    }
+
+    /// Looks up the named responder or compiles the script referenced by `response`, if any,
+    /// and installs it as the dynamic responder of the response.
+    pub fn resolve_response(&self, response: &mut MockServerHttpResponse) -> Result<(), Error> {
+        if response.script.is_some() && response.responder.is_some() {
+            return Err(Error::ValidationError(
+                "a response cannot use both a script and a named responder".to_string(),
+            ));
+        }
+
+        if response.respond_with.is_some() && (response.script.is_some() || response.responder.is_some()) {
+            return Err(Error::ValidationError(
+                "a response cannot use both a custom responder function and a script or named responder".to_string(),
+            ));
+        }
+
+        if let Some(script) = &response.script {
+            response.respond_with = Some(compile_script_responder(script)?);
+        }
+
+        if let Some(name) = &response.responder {
+            let responder = self
+                .responders
+                .get(name)
+                .ok_or_else(|| UnregisteredFunction("responder", name.to_string()))?;
+
+            response.respond_with = Some(responder.clone());
+        }
+
+        for response in response.sequence.iter_mut().flatten() {
+            self.resolve_response(response)?;
+        }
+
+        Ok(())
+    }
+
+    fn resolve_predicates(&self, names: &Option<Vec<String>>) -> Result<Option<Vec<RequestPredicate>>, Error> {
+        let Some(names) = names else {
+            return Ok(None);
+        };
+
+        names
+            .iter()
+            .map(|name| {
+                self.predicates
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| UnregisteredFunction("predicate", name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+fn parse_json_paths(expressions: &Option<Vec<String>>) -> Result<Option<Vec<JsonPath>>, Error> {
+    let Some(expressions) = expressions else {
+        return Ok(None);
+    };
+
+    expressions
+        .iter()
+        .map(|expression| JsonPath::parse(expression).map_err(Error::ValidationError))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+#[cfg(feature = "scripting")]
+fn compile_script_predicates(scripts: &Option<Vec<String>>) -> Result<Option<Vec<RequestPredicate>>, Error> {
+    let Some(scripts) = scripts else {
+        return Ok(None);
+    };
+
+    scripts
+        .iter()
+        .map(|script| scripting::compile_predicate(script))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+#[cfg(not(feature = "scripting"))]
+fn compile_script_predicates(scripts: &Option<Vec<String>>) -> Result<Option<Vec<RequestPredicate>>, Error> {
+    match scripts {
+        None => Ok(None),
+        Some(_) => Err(scripting_disabled()),
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn compile_script_responder(script: &str) -> Result<ResponseCallback, Error> {
+    scripting::compile_responder(script)
+}
+
+#[cfg(not(feature = "scripting"))]
+fn compile_script_responder(script: &str) -> Result<ResponseCallback, Error> {
+    Err(scripting_disabled())
+}
+
+#[cfg(not(feature = "scripting"))]
+fn scripting_disabled() -> Error {
+    Error::ValidationError(
+        "scripts are not supported because the mock server was built without the `scripting` feature".to_string(),
+    )
+}
@@ -0,0 +1,268 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use base64::{
+    Engine,
+    alphabet::URL_SAFE,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use http::{HeaderMap, Request, header};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+
+/// The connection preface that HTTP/2 clients send before any frames.
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+const FRAME_HEADER_LEN: usize = 9;
+const FRAME_TYPE_HEADERS: u8 = 0x1;
+const FRAME_TYPE_SETTINGS: u8 = 0x4;
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_ACK: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+/// The initial maximum frame size, which applies before any settings have been exchanged.
+const MAX_FRAME_LEN: usize = 16_384;
+/// Each setting consists of a 16-bit identifier and a 32-bit value.
+const SETTING_LEN: usize = 6;
+
+/// The `HTTP2-Settings` header is base64url encoded. Padding should be omitted, but is accepted.
+const HTTP2_SETTINGS_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Header fields that are specific to an HTTP/1.1 connection and must not be sent over HTTP/2.
+const CONNECTION_HEADERS: [&str; 7] = [
+    "connection",
+    "host",
+    "http2-settings",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// A request that asks to upgrade the connection to cleartext HTTP/2, prepared to be replayed to
+/// an HTTP/2 server (see [replay_upgrade_request]).
+pub(crate) struct UpgradeRequest {
+    /// The payload of the SETTINGS frame from the `HTTP2-Settings` header.
+    settings: Vec<u8>,
+    /// The `HEADERS` frame that carries the request on stream 1.
+    headers_frame: Vec<u8>,
+}
+
+/// Checks whether a request asks to upgrade the connection to cleartext HTTP/2 (`h2c`),
+/// as described in RFC 7540, Section 3.2.
+///
+/// HTTP/2 has no way to receive the request that triggered the upgrade: the server is expected to
+/// treat it as if it was sent on stream 1. This is achieved by encoding the request into an HTTP/2
+/// `HEADERS` frame for stream 1, which is replayed to the HTTP/2 server after the upgrade
+/// (see [replay_upgrade_request]). The settings from the `HTTP2-Settings` header are replayed
+/// as well, so that they apply to the upgraded connection.
+///
+/// Requests with a body are not upgraded (a server may ignore the `Upgrade` header), so they are
+/// served over HTTP/1.1 instead. The same applies to requests with an invalid `HTTP2-Settings`
+/// header.
+///
+/// # Returns
+/// The request prepared for replay, or `None` if the request cannot be upgraded.
+pub(crate) fn upgrade_request<B>(req: &Request<B>) -> Option<UpgradeRequest> {
+    let headers = req.headers();
+    let mut http2_settings = headers.get_all("http2-settings").iter();
+    let (Some(http2_settings), None) = (http2_settings.next(), http2_settings.next()) else {
+        return None;
+    };
+
+    if req.version() != http::Version::HTTP_11
+        || !has_token(headers, header::UPGRADE, "h2c")
+        || !has_token(headers, header::CONNECTION, "upgrade")
+        || headers.contains_key(header::TRANSFER_ENCODING)
+        || headers
+            .get(header::CONTENT_LENGTH)
+            .is_some_and(|length| length.as_bytes() != b"0")
+    {
+        return None;
+    }
+
+    let settings = HTTP2_SETTINGS_ENGINE.decode(http2_settings.as_bytes()).ok()?;
+    if settings.len() % SETTING_LEN != 0 || settings.len() > MAX_FRAME_LEN {
+        return None;
+    }
+
+    let path = req.uri().path_and_query().map_or("/", |path| path.as_str());
+    let authority = req
+        .uri()
+        .authority()
+        .map(|authority| authority.as_str().as_bytes())
+        .or_else(|| headers.get(header::HOST).map(|host| host.as_bytes()));
+
+    let mut block = Vec::new();
+    encode_header(&mut block, b":method", req.method().as_str().as_bytes());
+    encode_header(&mut block, b":scheme", b"http");
+    if let Some(authority) = authority {
+        encode_header(&mut block, b":authority", authority);
+    }
+    encode_header(&mut block, b":path", path.as_bytes());
+
+    for (name, value) in headers {
+        let name = name.as_str();
+        if CONNECTION_HEADERS.contains(&name)
+            || has_token(headers, header::CONNECTION, name)
+            || (name == "te" && value.as_bytes() != b"trailers")
+        {
+            continue;
+        }
+        encode_header(&mut block, name.as_bytes(), value.as_bytes());
+    }
+
+    // The block must fit into a single frame, since we don't send CONTINUATION frames.
+    if block.len() > MAX_FRAME_LEN {
+        return None;
+    }
+
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + block.len());
+    frame.extend_from_slice(&(block.len() as u32).to_be_bytes()[1..]);
+    frame.push(FRAME_TYPE_HEADERS);
+    frame.push(FLAG_END_STREAM | FLAG_END_HEADERS);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.extend_from_slice(&block);
+
+    Some(UpgradeRequest {
+        settings,
+        headers_frame: frame,
+    })
+}
+
+/// Reads the HTTP/2 connection preface that the client sends after an `h2c` upgrade and
+/// replays it, followed by the request that triggered the upgrade, to the HTTP/2 server that
+/// serves the returned stream.
+///
+/// The settings from the `HTTP2-Settings` header are prepended to the client's first SETTINGS
+/// frame, so that the server acknowledges a single SETTINGS frame, as the client expects, and
+/// settings that the client sends again after the upgrade take precedence.
+///
+/// # Parameters
+/// - `stream`: The upgraded connection.
+/// - `upgrade`: The request returned by [upgrade_request].
+///
+/// # Returns
+/// The connection, which can be served as an HTTP/2 connection with prior knowledge.
+pub(crate) async fn replay_upgrade_request<S>(mut stream: S, upgrade: UpgradeRequest) -> io::Result<PrefixedStream<S>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut prefix = vec![0; PREFACE.len() + FRAME_HEADER_LEN];
+    stream.read_exact(&mut prefix).await?;
+    if &prefix[..PREFACE.len()] != PREFACE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid HTTP/2 connection preface after h2c upgrade",
+        ));
+    }
+
+    // The preface is followed by the client's SETTINGS frame. The request that triggered the
+    // upgrade must be placed after it, since the SETTINGS frame must be the first frame.
+    let frame_header = &prefix[PREFACE.len()..];
+    let payload_len = u32::from_be_bytes([0, frame_header[0], frame_header[1], frame_header[2]]) as usize;
+    if frame_header[3] != FRAME_TYPE_SETTINGS
+        || frame_header[4] & FLAG_ACK != 0
+        || payload_len + upgrade.settings.len() > MAX_FRAME_LEN
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected an HTTP/2 SETTINGS frame after the connection preface",
+        ));
+    }
+
+    let merged_len = (payload_len + upgrade.settings.len()) as u32;
+    prefix[PREFACE.len()..PREFACE.len() + 3].copy_from_slice(&merged_len.to_be_bytes()[1..]);
+    prefix.extend_from_slice(&upgrade.settings);
+
+    let mut payload = vec![0; payload_len];
+    stream.read_exact(&mut payload).await?;
+    prefix.extend_from_slice(&payload);
+    prefix.extend_from_slice(&upgrade.headers_frame);
+
+    Ok(PrefixedStream {
+        prefix,
+        position: 0,
+        inner: stream,
+    })
+}
+
+/// Returns true if a comma-separated header contains the token (case-insensitive).
+fn has_token(headers: &HeaderMap, name: header::HeaderName, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case(token))
+}
+
+/// Encodes a header field as an HPACK literal without indexing (RFC 7541, Section 6.2.2).
+/// Since it doesn't touch the dynamic table, the HTTP/2 server's decoder stays in sync with the
+/// client's encoder.
+fn encode_header(block: &mut Vec<u8>, name: &[u8], value: &[u8]) {
+    block.push(0);
+    encode_string(block, name);
+    encode_string(block, value);
+}
+
+/// Encodes a string literal without Huffman coding (RFC 7541, Section 5.2).
+fn encode_string(block: &mut Vec<u8>, value: &[u8]) {
+    encode_integer(block, value.len(), 7);
+    block.extend_from_slice(value);
+}
+
+/// Encodes an integer with an N-bit prefix (RFC 7541, Section 5.1).
+fn encode_integer(block: &mut Vec<u8>, mut value: usize, prefix_bits: u8) {
+    let max_prefix = (1 << prefix_bits) - 1;
+    if value < max_prefix {
+        block.push(value as u8);
+        return;
+    }
+
+    block.push(max_prefix as u8);
+    value -= max_prefix;
+    while value >= 128 {
+        block.push((value % 128 + 128) as u8);
+        value /= 128;
+    }
+    block.push(value as u8);
+}
+
+/// A stream that yields a prefix before the data read from the inner stream.
+pub(crate) struct PrefixedStream<S> {
+    prefix: Vec<u8>,
+    position: usize,
+    inner: S,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        if self.position < self.prefix.len() {
+            let len = buf.remaining().min(self.prefix.len() - self.position);
+            buf.put_slice(&self.prefix[self.position..self.position + len]);
+            self.position += len;
+            return Poll::Ready(Ok(()));
+        }
+
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "proxy")]
+use std::{collections::HashMap, sync::Mutex};
 use std::{
     convert::TryInto,
     fmt::{Debug, Display},
@@ -5,14 +7,21 @@ use std::{
     sync::Arc,
 };
 
+#[cfg(feature = "proxy")]
+use http::uri::Authority;
 use http::{HeaderValue, StatusCode, Uri};
 use hyper::{Method, Request, Response, body::Bytes};
 use path_tree::{Path, PathTree};
 use serde::{Serialize, de::DeserializeOwned};
 use thiserror::Error;
+#[cfg(feature = "proxy")]
+use tokio::net::TcpStream;
 #[cfg(feature = "record")]
 use tokio::time::Instant;
 
+#[cfg(feature = "record")]
+use std::path::PathBuf;
+
 #[cfg(feature = "record")]
 use crate::common::data::RecordingRuleConfig;
 #[cfg(feature = "proxy")]
@@ -20,7 +29,15 @@ use crate::common::data::{ActiveForwardingRule, ActiveProxyRule};
 #[cfg(any(feature = "remote", feature = "proxy"))]
 use crate::common::http::Error as HttpClientError;
 #[cfg(feature = "proxy")]
-use crate::common::http::HttpClient;
+use crate::common::http::{HttpClient, HttpMockHttpClient, connect_tunnel};
+#[cfg(feature = "proxy")]
+use crate::server::callback;
+#[cfg(feature = "proxy")]
+use crate::server::fault::inject_faults;
+#[cfg(feature = "record")]
+use crate::server::recording;
+#[cfg(feature = "proxy")]
+use crate::server::transform::{transform_request, transform_response};
 use crate::{
     common::{
         data,
@@ -63,6 +80,9 @@ pub enum Error {
     HttpClient(#[from] HttpClientError),
     #[error("invalid header: {0}")]
     InvalidHeader(String),
+    #[cfg(feature = "proxy")]
+    #[error("connection closed by fault injection")]
+    ConnectionClosedByFault,
 }
 
 enum RoutePath {
@@ -72,6 +92,8 @@ enum RoutePath {
     SingleMock,
     History,
     Verify,
+    #[cfg(feature = "https")]
+    CaCertificate,
     #[cfg(feature = "proxy")]
     SingleForwardingRule,
     #[cfg(feature = "proxy")]
@@ -93,12 +115,27 @@ pub(crate) struct Handler {
     state: Arc<state::Manager>,
     #[cfg(feature = "proxy")]
     http_client: Arc<dyn HttpClient + Send + Sync + 'static>,
+    /// The URL of the HTTP proxy that upstream connections are made through, if any.
+    #[cfg(feature = "proxy")]
+    upstream_proxy: Option<String>,
+    /// Clients for the upstream proxies of proxy and forwarding rules, by proxy URL.
+    #[cfg(feature = "proxy")]
+    upstream_proxy_clients: Mutex<HashMap<String, Arc<dyn HttpClient + Send + Sync + 'static>>>,
+    /// The CA certificate (in PEM format) that signs the server's generated certificates, if any.
+    #[cfg(feature = "https")]
+    ca_certificate: Option<String>,
+    /// The directory that recordings created through the management API may stream to, if any.
+    #[cfg(feature = "record")]
+    recording_stream_dir: Option<PathBuf>,
 }
 
 impl Handler {
     pub(crate) fn new(
         state: Arc<state::Manager>,
         #[cfg(feature = "proxy")] http_client: Arc<dyn HttpClient + Send + Sync + 'static>,
+        #[cfg(feature = "proxy")] upstream_proxy: Option<String>,
+        #[cfg(feature = "https")] ca_certificate: Option<String>,
+        #[cfg(feature = "record")] recording_stream_dir: Option<PathBuf>,
     ) -> Self {
         let mut path_tree: PathTree<RoutePath> = PathTree::new();
         #[allow(unused_must_use)]
@@ -110,6 +147,9 @@ impl Handler {
             path_tree.insert("/__httpmock__/verify", RoutePath::Verify);
             path_tree.insert("/__httpmock__/history", RoutePath::History);
 
+            #[cfg(feature = "https")]
+            path_tree.insert("/__httpmock__/ca_certificate", RoutePath::CaCertificate);
+
             #[cfg(feature = "proxy")]
             {
                 path_tree.insert("/__httpmock__/forwarding_rules", RoutePath::ForwardingRuleCollection);
@@ -130,7 +170,63 @@ impl Handler {
             state,
             #[cfg(feature = "proxy")]
             http_client,
+            #[cfg(feature = "proxy")]
+            upstream_proxy,
+            #[cfg(feature = "proxy")]
+            upstream_proxy_clients: Mutex::new(HashMap::new()),
+            #[cfg(feature = "https")]
+            ca_certificate,
+            #[cfg(feature = "record")]
+            recording_stream_dir,
+        }
+    }
+
+    /// Returns the proxy rule that passes a `CONNECT` tunnel to `host` through to the host
+    /// instead of intercepting it, if any.
+    #[cfg(feature = "proxy")]
+    pub(crate) fn find_passthrough_tunnel_rule(&self, host: &str) -> Option<ActiveProxyRule> {
+        self.state.find_passthrough_tunnel_rule(host)
+    }
+
+    /// Returns true if any mock sends callbacks after its response was served.
+    #[cfg(feature = "proxy")]
+    pub(crate) fn has_callbacks(&self) -> bool {
+        self.state.has_callbacks()
+    }
+
+    /// Opens the connection to the target host of a `CONNECT` tunnel that is passed through,
+    /// using the upstream proxy of the rule or, if the rule has none, of the server.
+    #[cfg(feature = "proxy")]
+    pub(crate) async fn connect_passthrough_tunnel(
+        &self,
+        rule: &ActiveProxyRule,
+        authority: &Authority,
+    ) -> Result<TcpStream, Error> {
+        let upstream_proxy = rule.config.upstream_proxy.as_ref().or(self.upstream_proxy.as_ref());
+        Ok(connect_tunnel(authority, upstream_proxy.map(String::as_str)).await?)
+    }
+
+    /// Returns the client to send upstream requests with: the server's own client, unless a rule
+    /// overrides the upstream proxy.
+    #[cfg(feature = "proxy")]
+    fn upstream_client(
+        &self,
+        upstream_proxy: Option<&str>,
+    ) -> Result<Arc<dyn HttpClient + Send + Sync + 'static>, Error> {
+        let Some(proxy_url) = upstream_proxy else {
+            return Ok(self.http_client.clone());
+        };
+
+        let mut clients = self.upstream_proxy_clients.lock().unwrap();
+        if let Some(client) = clients.get(proxy_url) {
+            return Ok(client.clone());
         }
+
+        let client: Arc<dyn HttpClient + Send + Sync + 'static> =
+            Arc::new(HttpMockHttpClient::with_upstream_proxy(None, proxy_url)?);
+        clients.insert(proxy_url.to_string(), client.clone());
+
+        Ok(client)
     }
 
     pub(crate) async fn handle(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
@@ -157,6 +253,7 @@ impl Handler {
                     _ => {}
                 },
                 RoutePath::MockCollection => match method {
+                    Method::GET => return self.handle_read_mocks(),
                     Method::POST => return self.handle_add_mock(req),
                     Method::DELETE => return self.handle_delete_all_mocks(),
                     _ => {}
@@ -171,6 +268,12 @@ impl Handler {
                         return self.handle_verify(req);
                     }
                 }
+                #[cfg(feature = "https")]
+                RoutePath::CaCertificate => {
+                    if method == Method::GET {
+                        return self.handle_read_ca_certificate();
+                    }
+                }
                 #[cfg(feature = "proxy")]
                 RoutePath::ForwardingRuleCollection => match method {
                     Method::POST => return self.handle_add_forwarding_rule(req),
@@ -218,6 +321,15 @@ impl Handler {
         response::<()>(StatusCode::OK, None)
     }
 
+    #[cfg(feature = "https")]
+    fn handle_read_ca_certificate(&self) -> Result<Response<Bytes>, Error> {
+        let status_code = self
+            .ca_certificate
+            .as_ref()
+            .map_or(StatusCode::NOT_FOUND, |_| StatusCode::OK);
+        response(status_code, self.ca_certificate.as_ref())
+    }
+
     fn handle_reset(&self) -> Result<Response<Bytes>, Error> {
         self.state.reset();
         response::<()>(StatusCode::NO_CONTENT, None)
@@ -235,6 +347,10 @@ impl Handler {
         response(status_code, active_mock)
     }
 
+    fn handle_read_mocks(&self) -> Result<Response<Bytes>, Error> {
+        response(StatusCode::OK, Some(self.state.read_mocks()))
+    }
+
     fn handle_delete_mock(&self, params: Path) -> Result<Response<Bytes>, Error> {
         let deleted = self.state.delete_mock(param("id", params)?)?;
         let status_code = if deleted {
@@ -264,7 +380,7 @@ impl Handler {
 
     fn handle_add_forwarding_rule(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
         let config: ForwardingRuleConfig = parse_json_body(req)?;
-        let active_forwarding_rule = self.state.create_forwarding_rule(config);
+        let active_forwarding_rule = self.state.create_forwarding_rule(config)?;
         response(StatusCode::CREATED, Some(active_forwarding_rule))
     }
 
@@ -285,7 +401,7 @@ impl Handler {
 
     fn handle_add_proxy_rule(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
         let config: ProxyRuleConfig = parse_json_body(req)?;
-        let active_proxy_rule = self.state.create_proxy_rule(config);
+        let active_proxy_rule = self.state.create_proxy_rule(config)?;
         response(StatusCode::CREATED, Some(active_proxy_rule))
     }
 
@@ -306,8 +422,14 @@ impl Handler {
 
     #[cfg(feature = "record")]
     fn handle_add_recording_matcher(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
-        let req_req: RecordingRuleConfig = parse_json_body(req)?;
-        let active_recording = self.state.create_recording(req_req);
+        let mut req_req: RecordingRuleConfig = parse_json_body(req)?;
+        if let Some(path) = &req_req.stream_to {
+            let path = recording::resolve_stream_path(self.recording_stream_dir.as_deref(), path)
+                .map_err(state::Error::ValidationError)?;
+            req_req.stream_to = Some(path);
+        }
+
+        let active_recording = self.state.create_recording(req_req)?;
         response(StatusCode::CREATED, Some(active_recording))
     }
 
@@ -353,46 +475,48 @@ impl Handler {
         let start = Instant::now();
 
         #[cfg(feature = "proxy")]
-        let (res, is_proxied) = if let Some(rule) = self.state.find_forward_rule(&internal_request)? {
-            (self.forward(rule, req).await?, false)
+        let (res, is_proxied, from_upstream) = if let Some(rule) = self.state.find_forward_rule(&internal_request)? {
+            let forwarded = self.forward(&rule, req, &internal_request);
+            (
+                inject_faults(&rule.config.faults, &rule.rng, forwarded).await?,
+                false,
+                true,
+            )
         } else if let Some(rule) = self.state.find_proxy_rule(&internal_request)? {
-            (self.proxy(rule, req).await?, true)
+            let proxied = self.proxy(&rule, req, &internal_request);
+            (
+                inject_faults(&rule.config.faults, &rule.rng, proxied).await?,
+                true,
+                true,
+            )
         } else {
-            (self.serve_mock(&internal_request).await?, false)
+            (self.serve_mock(&internal_request).await?, false, false)
         };
 
         #[cfg(not(feature = "proxy"))]
-        let (res, is_proxied) = (self.serve_mock(&internal_request).await?, false);
+        let (res, is_proxied, from_upstream) = (self.serve_mock(&internal_request).await?, false, false);
 
         #[cfg(feature = "record")]
-        self.state.record(is_proxied, start.elapsed(), internal_request, &res)?;
+        self.state
+            .record(is_proxied, from_upstream, start.elapsed(), internal_request, &res)
+            .await?;
 
         Ok(res)
     }
 
     #[cfg(feature = "proxy")]
-    async fn forward(&self, rule: ActiveForwardingRule, req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
-        let to_base_uri: Uri = rule.config.target_base_url.parse().unwrap();
-
+    async fn forward(
+        &self,
+        rule: &ActiveForwardingRule,
+        req: Request<Bytes>,
+        internal_request: &HttpMockRequest,
+    ) -> Result<Response<Bytes>, Error> {
         let (mut req_parts, body) = req.into_parts();
 
         // We need to remove the host header, because it contains the host of this mock server.
         req_parts.headers.remove(http::header::HOST);
 
-        let mut uri_parts = req_parts.uri.into_parts();
-        uri_parts.authority = Some(to_base_uri.authority().unwrap().clone());
-        uri_parts.scheme = to_base_uri.scheme().cloned().or(uri_parts.scheme);
-        req_parts.uri = Uri::from_parts(uri_parts).unwrap();
-
-        // Record the upstream scheme (http/https) so the HttpClient can reconstruct
-        // an absolute target URI after converting to origin-form.
-        let upstream_scheme: &'static str = match to_base_uri.scheme_str() {
-            Some("https") => "https",
-            _ => "http",
-        };
-        req_parts
-            .extensions
-            .insert(crate::server::RequestMetadata::new(upstream_scheme));
+        let body = transform_request(&rule.config.request_transform, &mut req_parts, body)?;
 
         if !rule.config.request_header.is_empty() {
             for (key, value) in &rule.config.request_header {
@@ -406,17 +530,61 @@ impl Handler {
             }
         }
 
-        let req = Request::from_parts(req_parts, body);
-        // Requests are normalized to absolute-form inside this server for internal uniformity
-        // (matchers/recorders can read scheme/host/port from req.uri()). Before talking to an
-        // upstream origin server we MUST convert to origin-form (path + query only) and provide
-        // the authority via the Host header, as expected by HTTP/1.1 and HTTP/2 origin servers.
-        let req = to_origin_form(req)?;
-        Ok(self.http_client.send(req).await?)
+        let http_client = self.upstream_client(rule.config.upstream_proxy.as_deref())?;
+
+        let targets = rule.targets_in_order();
+        for (attempt, target) in targets.iter().enumerate() {
+            // Target base URLs are validated when the rule is created.
+            let to_base_uri: Uri = target.base_url.parse().unwrap();
+
+            let mut target_parts = req_parts.clone();
+
+            let mut uri_parts = target_parts.uri.into_parts();
+            uri_parts.authority = Some(to_base_uri.authority().unwrap().clone());
+            uri_parts.scheme = to_base_uri.scheme().cloned().or(uri_parts.scheme);
+            target_parts.uri = Uri::from_parts(uri_parts).unwrap();
+
+            // Record the upstream scheme (http/https) so the HttpClient can reconstruct
+            // an absolute target URI after converting to origin-form.
+            let upstream_scheme: &'static str = match to_base_uri.scheme_str() {
+                Some("https") => "https",
+                _ => "http",
+            };
+            target_parts
+                .extensions
+                .insert(crate::server::RequestMetadata::new(upstream_scheme));
+
+            let req = Request::from_parts(target_parts, body.clone());
+            // Requests are normalized to absolute-form inside this server for internal uniformity
+            // (matchers/recorders can read scheme/host/port from req.uri()). Before talking to an
+            // upstream origin server we MUST convert to origin-form (path + query only) and provide
+            // the authority via the Host header, as expected by HTTP/1.1 and HTTP/2 origin servers.
+            let req = to_origin_form(req)?;
+
+            match http_client.send(req).await {
+                Err(HttpClientError::RequestSend(err))
+                    if rule.config.failover && err.is_connect() && attempt + 1 < targets.len() =>
+                {
+                    tracing::warn!(
+                        "Cannot connect to forwarding target {} ({}), failing over to the next target",
+                        target.base_url,
+                        err
+                    );
+                }
+                res => return transform_response(&rule.config.response_transform, internal_request, res?),
+            }
+        }
+
+        unreachable!("forwarding rules have at least one target")
     }
 
     #[cfg(feature = "proxy")]
-    async fn proxy(&self, rule: ActiveProxyRule, mut req: Request<Bytes>) -> Result<Response<Bytes>, Error> {
+    async fn proxy(
+        &self,
+        rule: &ActiveProxyRule,
+        mut req: Request<Bytes>,
+        internal_request: &HttpMockRequest,
+    ) -> Result<Response<Bytes>, Error> {
         if !rule.config.request_header.is_empty() {
             let headers = req.headers_mut();
 
@@ -436,7 +604,11 @@ impl Handler {
         // upstream origin server we MUST convert to origin-form (path + query only) and provide
         // the authority via the Host header, as expected by HTTP/1.1 and HTTP/2 origin servers.
         let req = to_origin_form(req)?;
-        Ok(self.http_client.send(req).await?)
+        let res = self
+            .upstream_client(rule.config.upstream_proxy.as_deref())?
+            .send(req)
+            .await?;
+        transform_response(&rule.config.response_transform, internal_request, res)
     }
 
     async fn serve_mock(&self, req: &HttpMockRequest) -> Result<http::Response<bytes::Bytes>, Error> {
@@ -451,18 +623,36 @@ impl Handler {
             runtime::sleep(std::time::Duration::from_millis(duration)).await;
         }
 
-        // Resolve dynamic vs. static response into HttpMockResponse
-        let resp_def: HttpMockResponse = definition
-            .respond_with
-            .map(|f| f(req))
-            .unwrap_or_else(|| HttpMockResponse {
-                status: definition.status.or(Some(StatusCode::OK.as_u16())),
-                headers: definition.headers,
-                body: definition.body,
-            });
+        #[cfg(feature = "proxy")]
+        let callbacks = definition.callbacks.clone();
+
+        let released_with = match &definition.wait_for {
+            Some(gate) => gate.pass().await,
+            None => None,
+        };
+
+        // Resolve gate override vs. dynamic vs. static response into HttpMockResponse
+        let resp_def: HttpMockResponse = released_with.unwrap_or_else(|| {
+            definition
+                .respond_with
+                .map(|f| f(req))
+                .unwrap_or_else(|| HttpMockResponse {
+                    status: definition.status.or(Some(StatusCode::OK.as_u16())),
+                    headers: definition.headers,
+                    body: definition.body,
+                })
+        });
 
         // Convert via your TryFrom<HttpMockResponse> impl
-        let http_resp: http::Response<bytes::Bytes> = resp_def.try_into().map_err(ResponseDataConversion)?;
+        #[cfg_attr(not(feature = "proxy"), allow(unused_mut))]
+        let mut http_resp: http::Response<bytes::Bytes> = resp_def.try_into().map_err(ResponseDataConversion)?;
+
+        // Callbacks are held back until the transport has written the response.
+        #[cfg(feature = "proxy")]
+        if let Some(callbacks) = callbacks {
+            let trigger = callback::spawn_all(&self.http_client, callbacks, req);
+            http_resp.extensions_mut().insert(trigger);
+        }
 
         Ok(http_resp)
     }
@@ -0,0 +1,436 @@
+use std::{
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use http::{StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    common::{
+        data,
+        data::{MockDefinition, MockServerHttpResponse, RequestRequirements},
+        util::HttpMockBytes,
+    },
+    server::persistence::{self, deserialize_mock_defs_from_yaml},
+};
+
+/// The HAR version that is written on export.
+const HAR_VERSION: &str = "1.2";
+
+/// Response headers that describe the encoding of the original message rather than the
+/// content stored in the HAR file. The mock server computes them itself.
+const SKIPPED_RESPONSE_HEADERS: [&str; 5] = [
+    "content-length",
+    "content-encoding",
+    "transfer-encoding",
+    "connection",
+    "keep-alive",
+];
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("cannot process HAR document: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("cannot process recording: {0}")]
+    Recording(#[from] persistence::Error),
+    #[error("cannot convert data structures: {0}")]
+    DataConversion(#[from] data::Error),
+    #[error("invalid URL '{0}' in HAR entry")]
+    Url(String),
+    #[error("cannot decode base64 encoded response content: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("no entries could be found in the provided HAR document")]
+    NoEntries,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Har {
+    log: Log,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Log {
+    version: String,
+    creator: Creator,
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Creator {
+    name: String,
+    version: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Entry {
+    started_date_time: String,
+    time: f64,
+    request: Request,
+    response: Response,
+    cache: Cache,
+    timings: Timings,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Request {
+    method: String,
+    url: String,
+    http_version: String,
+    cookies: Vec<NameValue>,
+    headers: Vec<NameValue>,
+    query_string: Vec<NameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Response {
+    status: u16,
+    status_text: String,
+    http_version: String,
+    cookies: Vec<NameValue>,
+    headers: Vec<NameValue>,
+    content: Content,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct NameValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct PostData {
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Content {
+    size: i64,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Cache {}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Timings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+/// Converts a recording that has been exported as YAML into an HTTP Archive (HAR 1.2) document.
+///
+/// Recorded requests only contain the parts of the URL that were used for matching. Missing
+/// parts (e.g., the host of requests that were recorded from forwarded requests) are taken
+/// from `base_url`.
+pub(crate) fn recording_to_har(yaml: &str, base_url: &str) -> Result<Bytes, Error> {
+    let base_url: Uri = base_url.parse().map_err(|_| Error::Url(base_url.to_string()))?;
+
+    let mut entries = Vec::new();
+    for static_definition in deserialize_mock_defs_from_yaml(yaml)? {
+        let definition: MockDefinition = static_definition.try_into()?;
+        // Response sequences are archived as one entry per response, in the order they are served.
+        match &definition.response.sequence {
+            Some(sequence) => entries.extend(
+                sequence
+                    .iter()
+                    .map(|response| to_entry(&definition.request, response, &base_url)),
+            ),
+            None => entries.push(to_entry(&definition.request, &definition.response, &base_url)),
+        }
+    }
+
+    let har = Har {
+        log: Log {
+            version: HAR_VERSION.to_string(),
+            creator: Creator {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries,
+        },
+    };
+
+    Ok(Bytes::from(serde_json::to_vec_pretty(&har)?))
+}
+
+/// Creates one mock definition per entry of an HTTP Archive (HAR 1.2) document.
+///
+/// Mocks match the method, path, query parameters and body of the archived request. Request
+/// headers are not matched, because archives created by browsers contain many headers that
+/// differ between runs. Entries without a response (status `0`) are skipped.
+pub(crate) fn mock_definitions_from_har(content: &str) -> Result<Vec<MockDefinition>, Error> {
+    let har: Har = serde_json::from_str(content)?;
+
+    let definitions = har
+        .log
+        .entries
+        .iter()
+        .filter(|entry| entry.response.status != 0)
+        .map(to_mock_definition)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if definitions.is_empty() {
+        return Err(Error::NoEntries);
+    }
+
+    Ok(definitions)
+}
+
+fn to_entry(request: &RequestRequirements, response: &MockServerHttpResponse, base_url: &Uri) -> Entry {
+    let scheme = request
+        .scheme
+        .clone()
+        .or_else(|| base_url.scheme_str().map(str::to_string))
+        .unwrap_or_else(|| "http".to_string());
+    let host = request
+        .host
+        .clone()
+        .or_else(|| base_url.host().map(str::to_string))
+        .unwrap_or_else(|| "localhost".to_string());
+    let port = request
+        .port
+        .or_else(|| request.host.is_none().then(|| base_url.port_u16()).flatten());
+
+    let mut url = format!("{}://{}", scheme, host);
+    if let Some(port) = port {
+        url.push_str(&format!(":{}", port));
+    }
+    url.push_str(request.path.as_deref().unwrap_or("/"));
+
+    let query_params = request.query_param.clone().unwrap_or_default();
+    if !query_params.is_empty() {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&query_params)
+            .finish();
+        url.push_str(&format!("?{}", query));
+    }
+
+    let request_headers = request.header.clone().unwrap_or_default();
+    let response_headers = response.headers.clone().unwrap_or_default();
+
+    let post_data = request_body(request).map(|(text, is_json)| {
+        let mut mime_type = content_type(&request_headers);
+        if is_json && mime_type.is_empty() {
+            mime_type = "application/json".to_string();
+        }
+        PostData { mime_type, text }
+    });
+
+    let status = response.status.unwrap_or(200);
+    let body = response.body.as_ref().map(|body| body.to_bytes()).unwrap_or_default();
+    let (text, encoding) = match std::str::from_utf8(&body) {
+        Ok(text) => (text.to_string(), None),
+        Err(_) => (BASE64.encode(&body), Some("base64".to_string())),
+    };
+
+    let delay = response.delay.unwrap_or(0) as f64;
+
+    Entry {
+        started_date_time: now_rfc3339(),
+        time: delay,
+        request: Request {
+            method: request.method.clone().unwrap_or_else(|| "GET".to_string()),
+            url,
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: name_values(request_headers),
+            query_string: name_values(query_params),
+            body_size: post_data.as_ref().map_or(0, |data| data.text.len() as i64),
+            post_data,
+            headers_size: -1,
+        },
+        response: Response {
+            status,
+            status_text: StatusCode::from_u16(status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+                .to_string(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            content: Content {
+                size: body.len() as i64,
+                mime_type: content_type(&response_headers),
+                text: Some(text),
+                encoding,
+            },
+            headers: name_values(response_headers),
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: body.len() as i64,
+        },
+        cache: Cache {},
+        timings: Timings {
+            send: 0.0,
+            wait: delay,
+            receive: 0.0,
+        },
+    }
+}
+
+fn to_mock_definition(entry: &Entry) -> Result<MockDefinition, Error> {
+    let uri: Uri = entry
+        .request
+        .url
+        .parse()
+        .map_err(|_| Error::Url(entry.request.url.clone()))?;
+
+    let query_params: Vec<(String, String)> = uri
+        .query()
+        .map(|query| form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+        .unwrap_or_default();
+
+    let mut request = RequestRequirements::new();
+    request.method = Some(entry.request.method.to_uppercase());
+    request.path = Some(uri.path().to_string());
+    if !query_params.is_empty() {
+        request.query_param = Some(query_params);
+    }
+    if let Some(post_data) = entry.request.post_data.as_ref().filter(|data| !data.text.is_empty()) {
+        request.body = Some(HttpMockBytes::from(Bytes::from(post_data.text.clone())));
+    }
+
+    let headers: Vec<(String, String)> = entry
+        .response
+        .headers
+        .iter()
+        .filter(|header| !SKIPPED_RESPONSE_HEADERS.contains(&header.name.to_lowercase().as_str()))
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    let content = &entry.response.content;
+    let body = match (&content.text, content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => Some(Bytes::from(BASE64.decode(text)?)),
+        (Some(text), _) => Some(Bytes::from(text.clone())),
+        (None, _) => None,
+    };
+
+    let mut response = MockServerHttpResponse::new();
+    response.status = Some(entry.response.status);
+    if !headers.is_empty() {
+        response.headers = Some(headers);
+    }
+    response.body = body.filter(|body| !body.is_empty()).map(HttpMockBytes::from);
+
+    Ok(MockDefinition { request, response })
+}
+
+/// Returns the request body that was recorded for a mock and whether it is JSON.
+///
+/// Recordings that match JSON bodies only store the expected JSON value, either as a whole
+/// (`json_body`) or as one or more partial values (`json_body_includes`), which are merged
+/// into a single document.
+fn request_body(request: &RequestRequirements) -> Option<(String, bool)> {
+    if let Some(body) = &request.body {
+        return Some((String::from_utf8_lossy(body.as_ref()).to_string(), false));
+    }
+
+    if let Some(value) = &request.json_body {
+        return Some((value.to_string(), true));
+    }
+
+    let mut merged: Option<Value> = None;
+    for included in request.json_body_includes.iter().flatten() {
+        match &mut merged {
+            Some(value) => merge_json(value, included),
+            None => merged = Some(included.clone()),
+        }
+    }
+    merged.map(|value| (value.to_string(), true))
+}
+
+/// Merges `other` into `target`. Objects are merged key by key, all other values are replaced.
+fn merge_json(target: &mut Value, other: &Value) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            for (key, value) in other {
+                match target.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, other) => *target = other.clone(),
+    }
+}
+
+fn content_type(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default()
+}
+
+fn name_values(values: Vec<(String, String)>) -> Vec<NameValue> {
+    values
+        .into_iter()
+        .map(|(name, value)| NameValue { name, value })
+        .collect()
+}
+
+/// Formats the current time as an RFC 3339 timestamp in UTC (e.g., `2024-05-01T12:30:00.000Z`),
+/// as required for the `startedDateTime` field of HAR entries.
+fn now_rfc3339() -> String {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let (hours, minutes, secs) = ((seconds / 3600) % 24, (seconds / 60) % 60, seconds % 60);
+
+    // Converts days since the epoch to a civil date (see Howard Hinnant's `civil_from_days`).
+    let days = (seconds / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        hours,
+        minutes,
+        secs,
+        elapsed.subsec_millis()
+    )
+}
@@ -5,15 +5,16 @@ use serde_json::Value;
 
 use crate::{
     common::{
-        data::{HttpMockRegex, HttpMockRequest},
+        data::{HttpMockRegex, HttpMockRequest, TlsVersion},
+        json_path::JsonPath,
         util::HttpMockBytes,
     },
     server::matchers::{
         comparison,
         comparison::{
-            distance_for, distance_for_prefix, distance_for_substring, distance_for_suffix, equal_weight_distance_for,
-            hostname_equals, regex_unmatched_length, string_contains, string_distance, string_equals,
-            string_has_prefix, string_has_suffix,
+            distance_for, distance_for_prefix, distance_for_substring, distance_for_suffix,
+            distinguished_name_includes, equal_weight_distance_for, hostname_equals, regex_unmatched_length,
+            string_contains, string_distance, string_equals, string_has_prefix, string_has_suffix,
         },
     },
 };
@@ -102,6 +103,40 @@ impl ValueComparator<Value, Value> for JSONContainsMatchComparator {
     }
 }
 
+// ************************************************************************************************
+// JSONPathExistsComparator
+// ************************************************************************************************
+#[derive(Default)]
+pub struct JSONPathExistsComparator {}
+
+impl JSONPathExistsComparator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ValueComparator<JsonPath, Value> for JSONPathExistsComparator {
+    fn matches(&self, mock_value: &Option<&JsonPath>, req_value: &Option<&Value>) -> bool {
+        match (mock_value, req_value) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(path), Some(rv)) => path.exists(rv),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "has path"
+    }
+
+    fn distance(&self, mock_value: &Option<&JsonPath>, req_value: &Option<&Value>) -> usize {
+        if self.matches(mock_value, req_value) {
+            0
+        } else {
+            mock_value.map_or(0, |path| path.to_string().len())
+        }
+    }
+}
+
 // ************************************************************************************************
 // StringExactMatchComparator
 // ************************************************************************************************
@@ -446,6 +481,92 @@ impl ValueComparator<u16, u16> for U16ExactMatchComparator {
     }
 }
 
+// ************************************************************************************************
+// BoolExactMatchComparator
+// ************************************************************************************************
+#[derive(Default)]
+pub struct BoolExactMatchComparator {}
+
+impl BoolExactMatchComparator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ValueComparator<bool, bool> for BoolExactMatchComparator {
+    fn matches(&self, mock_value: &Option<&bool>, req_value: &Option<&bool>) -> bool {
+        comparison::integer_equals(false, mock_value, req_value)
+    }
+
+    fn name(&self) -> &str {
+        "equals"
+    }
+
+    fn distance(&self, mock_value: &Option<&bool>, req_value: &Option<&bool>) -> usize {
+        if self.matches(mock_value, req_value) { 0 } else { 1 }
+    }
+}
+
+// ************************************************************************************************
+// TlsVersionAtLeastComparator
+// ************************************************************************************************
+#[derive(Default)]
+pub struct TlsVersionAtLeastComparator {}
+
+impl TlsVersionAtLeastComparator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ValueComparator<TlsVersion, TlsVersion> for TlsVersionAtLeastComparator {
+    fn matches(&self, mock_value: &Option<&TlsVersion>, req_value: &Option<&TlsVersion>) -> bool {
+        match (mock_value, req_value) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(mv), Some(rv)) => rv >= mv,
+        }
+    }
+
+    fn name(&self) -> &str {
+        "at least"
+    }
+
+    fn distance(&self, mock_value: &Option<&TlsVersion>, req_value: &Option<&TlsVersion>) -> usize {
+        if self.matches(mock_value, req_value) { 0 } else { 1 }
+    }
+}
+
+// ************************************************************************************************
+// DistinguishedNameIncludesComparator
+// ************************************************************************************************
+#[derive(Default)]
+pub struct DistinguishedNameIncludesComparator {}
+
+impl DistinguishedNameIncludesComparator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ValueComparator<String, String> for DistinguishedNameIncludesComparator {
+    fn matches(&self, mock_value: &Option<&String>, req_value: &Option<&String>) -> bool {
+        distinguished_name_includes(mock_value, req_value)
+    }
+
+    fn name(&self) -> &str {
+        "includes"
+    }
+
+    fn distance(&self, mock_value: &Option<&String>, req_value: &Option<&String>) -> usize {
+        if self.matches(mock_value, req_value) {
+            return 0;
+        }
+
+        string_distance(true, false, mock_value, req_value)
+    }
+}
+
 // ************************************************************************************************
 // BytesExactMatchComparator
 // ************************************************************************************************
@@ -3,7 +3,10 @@ use std::{convert::TryInto, ops::Deref};
 use regex::Regex;
 use stringmetrics::LevWeights;
 
-use crate::common::{data::HttpMockRegex, util::HttpMockBytes};
+use crate::common::{
+    data::{HttpMockRegex, parse_distinguished_name},
+    util::HttpMockBytes,
+};
 
 pub fn string_has_prefix(
     case_sensitive: bool,
@@ -772,6 +775,64 @@ mod hostname_equals_tests {
     }
 }
 
+/// Checks whether every attribute of the distinguished name `mock_value` (e.g. `CN=my-client`)
+/// is also an attribute of the distinguished name `req_value` (e.g. `CN=my-client, O=Acme`).
+/// Attribute types are compared case-insensitively, attribute values case-sensitively.
+pub fn distinguished_name_includes(mock_value: &Option<&String>, req_value: &Option<&String>) -> bool {
+    match (mock_value, req_value) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(mv), Some(rv)) => {
+            let req_attributes = parse_distinguished_name(rv);
+            let mock_attributes = parse_distinguished_name(mv);
+            !mock_attributes.is_empty() && mock_attributes.iter().all(|a| req_attributes.contains(a))
+        }
+    }
+}
+
+#[cfg(test)]
+mod distinguished_name_includes_tests {
+    use super::*;
+
+    #[test]
+    fn test_distinguished_name_includes_attributes() {
+        let subject = "C=DE, O=Acme, CN=my-client".to_string();
+
+        assert!(distinguished_name_includes(
+            &Some(&"CN=my-client".to_string()),
+            &Some(&subject)
+        ));
+        assert!(distinguished_name_includes(
+            &Some(&"cn=my-client,o=Acme".to_string()),
+            &Some(&subject)
+        ));
+        assert!(distinguished_name_includes(&Some(&subject), &Some(&subject)));
+    }
+
+    #[test]
+    fn test_distinguished_name_does_not_include_attributes() {
+        let subject = "O=Acme, CN=my-client".to_string();
+
+        assert!(!distinguished_name_includes(
+            &Some(&"CN=my".to_string()),
+            &Some(&subject)
+        ));
+        assert!(!distinguished_name_includes(
+            &Some(&"CN=other".to_string()),
+            &Some(&subject)
+        ));
+        assert!(!distinguished_name_includes(
+            &Some(&"CN=my-client, OU=IT".to_string()),
+            &Some(&subject)
+        ));
+        assert!(!distinguished_name_includes(
+            &Some(&"my-client".to_string()),
+            &Some(&subject)
+        ));
+        assert!(!distinguished_name_includes(&Some(&"CN=my-client".to_string()), &None));
+    }
+}
+
 /// Computes the distance between two optional strings (`mock_value` and `req_value`),
 /// with optional case sensitivity and negation.
 ///
@@ -2,11 +2,12 @@ use crate::{
     common::data::{HttpMockRequest, Mismatch, RequestRequirements, Tokenizer},
     server::matchers::{
         comparators::{
-            AnyValueComparator, BytesExactMatchComparator, BytesIncludesComparator, BytesPrefixComparator,
-            BytesSuffixComparator, FunctionMatchesRequestComparator, HostEqualsComparator,
-            HttpMockBytesPatternComparator, JSONContainsMatchComparator, JSONExactMatchComparator,
-            StringContainsComparator, StringEqualsComparator, StringPatternMatchComparator,
-            StringPrefixMatchComparator, StringRegexMatchComparator, StringSuffixMatchComparator,
+            AnyValueComparator, BoolExactMatchComparator, BytesExactMatchComparator, BytesIncludesComparator,
+            BytesPrefixComparator, BytesSuffixComparator, DistinguishedNameIncludesComparator,
+            FunctionMatchesRequestComparator, HostEqualsComparator, HttpMockBytesPatternComparator,
+            JSONContainsMatchComparator, JSONExactMatchComparator, JSONPathExistsComparator, StringContainsComparator,
+            StringEqualsComparator, StringPatternMatchComparator, StringPrefixMatchComparator,
+            StringRegexMatchComparator, StringSuffixMatchComparator, TlsVersionAtLeastComparator,
             U16ExactMatchComparator,
         },
         generic::{
@@ -863,6 +864,97 @@ pub fn all() -> Vec<Box<dyn Matcher + Sync + Send>> {
             diff_with: Some(Tokenizer::Line),
             weight: 1,
         }),
+        Box::new(SingleValueMatcher {
+            entity_name: "JSON body",
+            matcher_method: "json_body_path",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(JSONPathExistsComparator::new()),
+            expectation: readers::expectations::json_body_path,
+            request_value: readers::request_value::json_body,
+            diff_with: None,
+            weight: 1,
+        }),
+        //*************************************************************************************
+        // Client certificate matchers
+        //*************************************************************************************
+        Box::new(SingleValueMatcher {
+            entity_name: "client certificate",
+            matcher_method: "client_cert_present",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(BoolExactMatchComparator::new()),
+            expectation: readers::expectations::client_cert_present,
+            request_value: readers::request_value::client_cert_present,
+            diff_with: None,
+            weight: 3,
+        }),
+        Box::new(SingleValueMatcher {
+            entity_name: "client certificate subject",
+            matcher_method: "client_cert_subject",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(DistinguishedNameIncludesComparator::new()),
+            expectation: readers::expectations::client_cert_subject,
+            request_value: readers::request_value::client_cert_subject,
+            diff_with: None,
+            weight: 3,
+        }),
+        Box::new(MultiValueMatcher {
+            entity_name: "client certificate subject alternative name",
+            matcher_method: "client_cert_san",
+            operator: KeyValueOperator::AND,
+            expectation: readers::expectations::client_cert_san,
+            request_value: readers::request_value::client_cert_sans,
+            matching_strategy: MatchingStrategy::Presence,
+            key_required: true,
+            key_comparator: Box::new(StringEqualsComparator::new(false, false)),
+            value_comparator: Box::new(AnyValueComparator::new()),
+            weight: 3,
+        }),
+        //*************************************************************************************
+        // TLS connection matchers
+        //*************************************************************************************
+        Box::new(SingleValueMatcher {
+            entity_name: "TLS server name (SNI)",
+            matcher_method: "tls_sni",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(StringEqualsComparator::new(false, false)),
+            expectation: readers::expectations::tls_sni,
+            request_value: readers::request_value::tls_sni,
+            diff_with: None,
+            weight: 3,
+        }),
+        Box::new(SingleValueMatcher {
+            entity_name: "TLS version",
+            matcher_method: "tls_version_at_least",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(TlsVersionAtLeastComparator::new()),
+            expectation: readers::expectations::tls_version_at_least,
+            request_value: readers::request_value::tls_version,
+            diff_with: None,
+            weight: 3,
+        }),
+        Box::new(SingleValueMatcher {
+            entity_name: "ALPN protocol",
+            matcher_method: "alpn",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(StringEqualsComparator::new(true, false)),
+            expectation: readers::expectations::alpn,
+            request_value: readers::request_value::alpn,
+            diff_with: None,
+            weight: 3,
+        }),
+        //*************************************************************************************
+        // Protocol matchers
+        //*************************************************************************************
+        Box::new(SingleValueMatcher {
+            entity_name: "HTTP version",
+            matcher_method: "http_version",
+            matching_strategy: MatchingStrategy::Presence,
+            comparator: Box::new(StringEqualsComparator::new(true, false)),
+            expectation: readers::expectations::http_version,
+            request_value: readers::request_value::http_version,
+            diff_with: None,
+            weight: 3,
+        }),
         Box::new(FunctionValueMatcher {
             entity_name: "custom matcher function",
             matcher_function: "is_true",
@@ -879,6 +971,30 @@ pub fn all() -> Vec<Box<dyn Matcher + Sync + Send>> {
             request_value: readers::request_value::full_request,
             weight: 1,
         }),
+        Box::new(FunctionValueMatcher {
+            entity_name: "named predicate",
+            matcher_function: "predicate",
+            comparator: Box::new(FunctionMatchesRequestComparator::new(false)),
+            expectation: readers::expectations::predicate,
+            request_value: readers::request_value::full_request,
+            weight: 1,
+        }),
+        Box::new(FunctionValueMatcher {
+            entity_name: "named predicate",
+            matcher_function: "predicate_not",
+            comparator: Box::new(FunctionMatchesRequestComparator::new(true)),
+            expectation: readers::expectations::predicate_not,
+            request_value: readers::request_value::full_request,
+            weight: 1,
+        }),
+        Box::new(FunctionValueMatcher {
+            entity_name: "script predicate",
+            matcher_function: "script_predicate",
+            comparator: Box::new(FunctionMatchesRequestComparator::new(false)),
+            expectation: readers::expectations::script_predicate,
+            request_value: readers::request_value::full_request,
+            weight: 1,
+        }),
         //*************************************************************************************
         // x-www-form-urlencoded body
         //*************************************************************************************
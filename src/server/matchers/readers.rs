@@ -10,7 +10,8 @@ pub mod expectations {
 
     use crate::{
         common::{
-            data::{HttpMockRegex, RequestRequirements},
+            data::{HttpMockRegex, RequestRequirements, TlsVersion},
+            json_path::JsonPath,
             util::HttpMockBytes,
         },
         prelude::HttpMockRequest,
@@ -448,6 +449,48 @@ pub mod expectations {
         mock.json_body_excludes.as_ref().map(|b| b.iter().collect())
     }
 
+    #[inline]
+    pub(crate) fn json_body_path(mock: &RequestRequirements) -> Option<Vec<&JsonPath>> {
+        mock.resolved_json_body_path.as_ref().map(|b| b.iter().collect())
+    }
+
+    #[inline]
+    pub fn client_cert_present(mock: &RequestRequirements) -> Option<Vec<&bool>> {
+        mock.client_cert_present.as_ref().map(|b| vec![b])
+    }
+
+    #[inline]
+    pub fn client_cert_subject(mock: &RequestRequirements) -> Option<Vec<&String>> {
+        mock.client_cert_subject.as_ref().map(|b| b.iter().collect())
+    }
+
+    #[inline]
+    pub fn client_cert_san(mock: &RequestRequirements) -> Option<Vec<(&String, Option<&String>)>> {
+        mock.client_cert_san
+            .as_ref()
+            .map(|v| v.iter().map(|v| (v, None)).collect())
+    }
+
+    #[inline]
+    pub fn tls_sni(mock: &RequestRequirements) -> Option<Vec<&String>> {
+        mock.tls_sni.as_ref().map(|b| vec![b])
+    }
+
+    #[inline]
+    pub fn tls_version_at_least(mock: &RequestRequirements) -> Option<Vec<&TlsVersion>> {
+        mock.tls_version_at_least.as_ref().map(|b| vec![b])
+    }
+
+    #[inline]
+    pub fn alpn(mock: &RequestRequirements) -> Option<Vec<&String>> {
+        mock.alpn.as_ref().map(|b| vec![b])
+    }
+
+    #[inline]
+    pub fn http_version(mock: &RequestRequirements) -> Option<Vec<&String>> {
+        mock.http_version.as_ref().map(|b| vec![b])
+    }
+
     #[inline]
     pub fn is_true(mock: &RequestRequirements) -> Option<Vec<&RequestPredicate>> {
         mock.is_true.as_ref().map(|b| b.iter().collect())
@@ -458,6 +501,21 @@ pub mod expectations {
         mock.is_false.as_ref().map(|b| b.iter().collect())
     }
 
+    #[inline]
+    pub fn predicate(mock: &RequestRequirements) -> Option<Vec<&RequestPredicate>> {
+        mock.resolved_predicate.as_ref().map(|b| b.iter().collect())
+    }
+
+    #[inline]
+    pub fn predicate_not(mock: &RequestRequirements) -> Option<Vec<&RequestPredicate>> {
+        mock.resolved_predicate_not.as_ref().map(|b| b.iter().collect())
+    }
+
+    #[inline]
+    pub fn script_predicate(mock: &RequestRequirements) -> Option<Vec<&RequestPredicate>> {
+        mock.resolved_script_predicate.as_ref().map(|b| b.iter().collect())
+    }
+
     pub fn form_urlencoded_tuple(mock: &RequestRequirements) -> Option<Vec<(&String, Option<&String>)>> {
         mock.form_urlencoded_tuple
             .as_ref()
@@ -542,7 +600,10 @@ pub mod expectations {
 }
 
 pub mod request_value {
-    use crate::{common::util::HttpMockBytes, prelude::HttpMockRequest};
+    use crate::{
+        common::{data::TlsVersion, util::HttpMockBytes},
+        prelude::HttpMockRequest,
+    };
 
     #[inline]
     pub fn scheme(req: &HttpMockRequest) -> Option<String> {
@@ -627,6 +688,42 @@ pub mod request_value {
         )
     }
 
+    #[inline]
+    pub fn client_cert_present(req: &HttpMockRequest) -> Option<bool> {
+        Some(req.client_certificate().is_some())
+    }
+
+    #[inline]
+    pub fn client_cert_subject(req: &HttpMockRequest) -> Option<String> {
+        req.client_certificate().map(|cert| cert.subject.clone())
+    }
+
+    #[inline]
+    pub fn client_cert_sans(req: &HttpMockRequest) -> Option<Vec<(String, Option<String>)>> {
+        req.client_certificate()
+            .map(|cert| cert.subject_alt_names.iter().map(|san| (san.clone(), None)).collect())
+    }
+
+    #[inline]
+    pub fn tls_sni(req: &HttpMockRequest) -> Option<String> {
+        req.tls().and_then(|tls| tls.sni.clone())
+    }
+
+    #[inline]
+    pub fn tls_version(req: &HttpMockRequest) -> Option<TlsVersion> {
+        req.tls().and_then(|tls| tls.version)
+    }
+
+    #[inline]
+    pub fn alpn(req: &HttpMockRequest) -> Option<String> {
+        req.tls().and_then(|tls| tls.alpn.clone())
+    }
+
+    #[inline]
+    pub fn http_version(req: &HttpMockRequest) -> Option<String> {
+        Some(req.version_ref().to_string())
+    }
+
     #[inline]
     pub fn full_request(req: &HttpMockRequest) -> Option<&HttpMockRequest> {
         Some(req)
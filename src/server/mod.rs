@@ -1,31 +1,75 @@
 mod builder;
+#[cfg(feature = "proxy")]
+mod callback;
+#[cfg(feature = "proxy")]
+mod fault;
+mod functions;
+#[cfg(feature = "http2")]
+mod h2c;
 mod handler;
+#[cfg(feature = "record")]
+pub(crate) mod har;
 pub mod matchers;
+pub(crate) mod pact;
 pub mod state;
 mod transport;
 
+#[cfg(feature = "openapi")]
+pub(crate) mod openapi;
+
 #[cfg(feature = "record")]
 mod persistence;
 
+pub(crate) mod recording;
+
+#[cfg(feature = "scripting")]
+mod scripting;
+
+#[cfg(feature = "record")]
+pub(crate) mod wiremock;
+
 #[cfg(feature = "https")]
 mod tls;
 
+#[cfg(feature = "proxy")]
+mod transform;
+
+#[cfg(feature = "https")]
+pub use builder::ClientAuth;
 pub use builder::HttpMockServerBuilder;
 #[cfg(feature = "https")]
+#[allow(deprecated)]
 pub use builder::{DEFAULT_CA_CERTIFICATE, DEFAULT_CA_PRIVATE_KEY};
+#[cfg(feature = "https")]
+pub use tls::{Error as TlsError, ca_certificate_pem, generate_ca_certificate};
 pub use transport::{Error, HttpMockServer};
 
+use crate::common::data::TlsInfo;
+
 /// Per-request metadata propagated through Hyper services.
 #[derive(Clone)]
 pub struct RequestMetadata {
     /// The scheme ("http" or "https") associated with this request, used by the
     /// upstream client to reconstruct the absolute target when needed.
     pub scheme: &'static str,
+    /// Information about the TLS connection the request was received on, if any.
+    pub(crate) tls: Option<TlsInfo>,
 }
 
 impl RequestMetadata {
     /// Create new RequestMetadata for a request with the given scheme.
     pub fn new(scheme: &'static str) -> Self {
-        Self { scheme }
+        Self { scheme, tls: None }
+    }
+
+    /// Attaches information about the TLS connection the request was received on.
+    pub fn with_tls(mut self, tls: TlsInfo) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Returns information about the TLS connection the request was received on, if any.
+    pub fn tls(&self) -> Option<&TlsInfo> {
+        self.tls.as_ref()
     }
 }
@@ -0,0 +1,566 @@
+use std::{
+    cmp::Ordering,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::common::{
+    data::{HttpMockRegex, MockDefinition, MockServerHttpResponse, RequestRequirements},
+    util::HttpMockBytes,
+};
+
+/// The HTTP methods an OpenAPI path item can define operations for.
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Header parameters with these names are ignored according to the OpenAPI specification.
+const IGNORED_HEADERS: [&str; 3] = ["accept", "content-type", "authorization"];
+
+/// Upper bound for the nesting depth when generating examples from schemas. This keeps
+/// recursive schemas from generating infinite documents.
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("cannot read OpenAPI document '{}': {source}", path.display())]
+    FileRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("cannot parse OpenAPI document: {0}")]
+    Parse(#[from] serde_yaml::Error),
+    #[error("unsupported OpenAPI document: {0}")]
+    Unsupported(String),
+    #[error("cannot create a matcher from pattern '{0}': {1}")]
+    Regex(String, regex::Error),
+}
+
+/// Reads an OpenAPI 3.x document (YAML or JSON) from a file and creates one mock definition
+/// per operation. See [parse_mock_definitions] for details.
+pub(crate) fn read_mock_definitions(path: &Path, validate_requests: bool) -> Result<Vec<MockDefinition>, Error> {
+    let content = read_to_string(path).map_err(|source| Error::FileRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    parse_mock_definitions(&content, validate_requests)
+}
+
+/// Creates one mock definition per operation in an OpenAPI 3.x document (YAML or JSON).
+///
+/// Each mock matches the method and path template of the operation and responds with the
+/// documented example of its success response. If no example is documented, one is
+/// generated from the response schema.
+///
+/// If `validate_requests` is `true`, the mocks additionally require the documented path
+/// parameter types, required query parameters, required headers and a required request body.
+/// Requests that target an operation but violate these requirements are answered with
+/// `400 Bad Request`.
+pub(crate) fn parse_mock_definitions(content: &str, validate_requests: bool) -> Result<Vec<MockDefinition>, Error> {
+    let document: Value = serde_yaml::from_str(content)?;
+
+    let version = document.get("openapi").and_then(Value::as_str).unwrap_or_default();
+    if !version.starts_with("3.") {
+        return Err(Error::Unsupported(format!(
+            "expected an OpenAPI 3.x document, but found version '{}'",
+            version
+        )));
+    }
+
+    let paths = document
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| Error::Unsupported("the document does not contain any paths".to_string()))?;
+
+    let base_path = base_path(&document);
+
+    let mut operations = Vec::new();
+    for (template, item) in paths {
+        let item = resolve(&document, item);
+        for method in METHODS {
+            if let Some(operation) = item.get(method) {
+                operations.push(Operation {
+                    document: &document,
+                    method,
+                    template,
+                    parameters: parameters(&document, item, operation),
+                    operation,
+                });
+            }
+        }
+    }
+
+    // Literal paths must take precedence over templated ones (e.g., "/users/me" over
+    // "/users/{id}"), so they are registered first.
+    operations.sort_by(|a, b| match (a.template.contains('{'), b.template.contains('{')) {
+        (false, true) => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        _ => Ordering::Equal,
+    });
+
+    let mut definitions = Vec::new();
+    for operation in &operations {
+        definitions.push(MockDefinition {
+            request: operation.request_requirements(&base_path, validate_requests)?,
+            response: operation.response(),
+        });
+    }
+
+    // The strict mocks above are matched first, so these only serve requests that target an
+    // operation but do not conform to its specification.
+    if validate_requests {
+        for operation in &operations {
+            definitions.push(MockDefinition {
+                request: operation.request_requirements(&base_path, false)?,
+                response: operation.validation_error_response(),
+            });
+        }
+    }
+
+    Ok(definitions)
+}
+
+struct Operation<'a> {
+    document: &'a Value,
+    method: &'static str,
+    template: &'a str,
+    parameters: Vec<&'a Value>,
+    operation: &'a Value,
+}
+
+impl<'a> Operation<'a> {
+    fn request_requirements(&self, base_path: &str, strict: bool) -> Result<RequestRequirements, Error> {
+        let mut requirements = RequestRequirements::new();
+        requirements.method = Some(self.method.to_uppercase());
+        requirements.path_matches = Some(vec![self.path_regex(base_path, strict)?]);
+
+        if !strict {
+            return Ok(requirements);
+        }
+
+        for parameter in self.parameters.iter().filter(|p| is_required(p)) {
+            let name = parameter.get("name").and_then(Value::as_str).unwrap_or_default();
+
+            match parameter.get("in").and_then(Value::as_str) {
+                Some("query") => match parameter.get("schema").and_then(value_pattern) {
+                    Some(pattern) => requirements.query_param_matches.get_or_insert_with(Vec::new).push((
+                        regex(&format!("^{}$", regex::escape(name)))?,
+                        regex(&format!("^(?:{})$", pattern))?,
+                    )),
+                    None => requirements
+                        .query_param_exists
+                        .get_or_insert_with(Vec::new)
+                        .push(name.to_string()),
+                },
+                Some("header") if !IGNORED_HEADERS.contains(&name.to_lowercase().as_str()) => requirements
+                    .header_exists
+                    .get_or_insert_with(Vec::new)
+                    .push(name.to_string()),
+                _ => {}
+            }
+        }
+
+        if let Some(body) = self
+            .operation
+            .get("requestBody")
+            .map(|body| resolve(self.document, body))
+            .filter(|body| is_required(body))
+        {
+            requirements.body_matches = Some(vec![regex("(?s).+")?]);
+
+            let content_types: Vec<String> = body
+                .get("content")
+                .and_then(Value::as_object)
+                .map(|content| content.keys().map(|t| media_type_pattern(t)).collect())
+                .unwrap_or_default();
+
+            if !content_types.is_empty() && !content_types.iter().any(|t| t == ".+") {
+                requirements.header_matches = Some(vec![(
+                    regex("(?i)^content-type$")?,
+                    regex(&format!("^({})(;.*)?$", content_types.join("|")))?,
+                )]);
+            }
+        }
+
+        Ok(requirements)
+    }
+
+    fn path_regex(&self, base_path: &str, strict: bool) -> Result<HttpMockRegex, Error> {
+        let mut pattern = format!("^{}", regex::escape(base_path));
+
+        let mut rest = self.template;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+
+            pattern.push_str(&regex::escape(&rest[..start]));
+
+            let name = &rest[start + 1..end];
+            let segment = strict
+                .then(|| {
+                    self.parameters
+                        .iter()
+                        .find(|p| {
+                            p.get("in").and_then(Value::as_str) == Some("path")
+                                && p.get("name").and_then(Value::as_str) == Some(name)
+                        })
+                        .and_then(|p| p.get("schema"))
+                        .and_then(value_pattern)
+                })
+                .flatten()
+                .unwrap_or_else(|| "[^/]+".to_string());
+            pattern.push_str(&format!("(?:{})", segment));
+
+            rest = &rest[end + 1..];
+        }
+        pattern.push_str(&regex::escape(rest));
+        pattern.push('$');
+
+        regex(&pattern)
+    }
+
+    fn response(&self) -> MockServerHttpResponse {
+        let document = self.document;
+        let mut response = MockServerHttpResponse::new();
+        response.status = Some(200);
+
+        let Some((status, spec)) = self.success_response() else {
+            return response;
+        };
+        response.status = Some(status);
+
+        let mut headers = Vec::new();
+
+        for (name, header) in spec.get("headers").and_then(Value::as_object).into_iter().flatten() {
+            let header = resolve(document, header);
+            let value = header.get("example").cloned().or_else(|| {
+                header
+                    .get("schema")
+                    .map(|schema| example_from_schema(document, schema, 0))
+            });
+
+            match value {
+                Some(Value::String(value)) => headers.push((name.to_string(), value)),
+                Some(Value::Null) | None => {}
+                Some(value) => headers.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        if let Some((content_type, media)) = preferred_media_type(spec)
+            && let Some(example) = media_example(document, media)
+        {
+            if !content_type.contains('*') {
+                headers.push(("content-type".to_string(), content_type.to_string()));
+            }
+
+            let body = match example {
+                Value::String(text) if !is_json(content_type) => text,
+                other => other.to_string(),
+            };
+            response.body = Some(HttpMockBytes::from(Bytes::from(body)));
+        }
+
+        if !headers.is_empty() {
+            response.headers = Some(headers);
+        }
+
+        response
+    }
+
+    /// Selects the response that is returned by the mock: the lowest documented `2xx` status
+    /// code, then the `2XX` range, then `default` and finally the lowest documented status code.
+    fn success_response(&self) -> Option<(u16, &'a Value)> {
+        let responses = self.operation.get("responses")?.as_object()?;
+
+        let mut codes: Vec<(u16, &Value)> = responses
+            .iter()
+            .filter_map(|(code, spec)| code.parse::<u16>().ok().map(|code| (code, spec)))
+            .collect();
+        codes.sort_by_key(|(code, _)| *code);
+
+        let selected = codes
+            .iter()
+            .find(|(code, _)| (200..300).contains(code))
+            .copied()
+            .or_else(|| responses.get("2XX").map(|spec| (200, spec)))
+            .or_else(|| responses.get("default").map(|spec| (200, spec)))
+            .or_else(|| codes.first().copied());
+
+        selected.map(|(code, spec)| (code, resolve(self.document, spec)))
+    }
+
+    fn validation_error_response(&self) -> MockServerHttpResponse {
+        let mut response = MockServerHttpResponse::new();
+        response.status = Some(400);
+        response.headers = Some(vec![("content-type".to_string(), "application/json".to_string())]);
+
+        let mut body = Map::new();
+        body.insert(
+            "error".to_string(),
+            Value::from("the request does not conform to the OpenAPI specification"),
+        );
+        body.insert(
+            "operation".to_string(),
+            Value::from(format!("{} {}", self.method.to_uppercase(), self.template)),
+        );
+        if let Some(id) = self.operation.get("operationId") {
+            body.insert("operationId".to_string(), id.clone());
+        }
+
+        response.body = Some(HttpMockBytes::from(Bytes::from(Value::Object(body).to_string())));
+        response
+    }
+}
+
+/// Returns the path prefix of the first server URL, e.g. `/v1` for `https://example.com/v1/`.
+/// Server variables are replaced with their default values.
+fn base_path(document: &Value) -> String {
+    let Some(server) = document.pointer("/servers/0") else {
+        return String::new();
+    };
+
+    let mut url = server
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    for (name, variable) in server.get("variables").and_then(Value::as_object).into_iter().flatten() {
+        let default = variable.get("default").and_then(Value::as_str).unwrap_or_default();
+        url = url.replace(&format!("{{{}}}", name), default);
+    }
+
+    let path = match url.find("://") {
+        Some(index) => url[index + 3..].find('/').map(|i| &url[index + 3 + i..]).unwrap_or(""),
+        None => url.as_str(),
+    };
+
+    path.trim_end_matches('/').to_string()
+}
+
+/// Merges path-level and operation-level parameters. Operation-level parameters override
+/// path-level parameters with the same name and location.
+fn parameters<'a>(document: &'a Value, item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+    let key = |p: &Value| {
+        (
+            p.get("name").and_then(Value::as_str).map(str::to_string),
+            p.get("in").and_then(Value::as_str).map(str::to_string),
+        )
+    };
+
+    let operation_parameters: Vec<&Value> = operation
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|p| resolve(document, p))
+        .collect();
+
+    let mut result: Vec<&Value> = item
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|p| resolve(document, p))
+        .filter(|p| !operation_parameters.iter().any(|o| key(o) == key(p)))
+        .collect();
+
+    result.extend(operation_parameters);
+    result
+}
+
+/// Follows a local reference (`$ref: '#/components/...'`). Values that are not references
+/// or references that cannot be resolved are returned as is.
+fn resolve<'a>(document: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+
+    // Bounded to avoid looping forever on cyclic references.
+    for _ in 0..MAX_SCHEMA_DEPTH {
+        let Some(reference) = current.get("$ref").and_then(Value::as_str) else {
+            break;
+        };
+
+        match reference
+            .strip_prefix('#')
+            .and_then(|pointer| document.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => {
+                tracing::warn!("Cannot resolve OpenAPI reference '{}'", reference);
+                break;
+            }
+        }
+    }
+
+    current
+}
+
+fn is_required(value: &Value) -> bool {
+    value.get("required").and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn is_json(content_type: &str) -> bool {
+    content_type.contains("json")
+}
+
+/// Prefers JSON over other media types and otherwise returns the first documented one.
+fn preferred_media_type(response: &Value) -> Option<(&str, &Value)> {
+    let content = response.get("content")?.as_object()?;
+
+    content
+        .iter()
+        .find(|(content_type, _)| is_json(content_type))
+        .or_else(|| content.iter().next())
+        .map(|(content_type, media)| (content_type.as_str(), media))
+}
+
+fn media_example(document: &Value, media: &Value) -> Option<Value> {
+    if let Some(example) = media.get("example") {
+        return Some(example.clone());
+    }
+
+    if let Some(example) = media
+        .get("examples")
+        .and_then(Value::as_object)
+        .and_then(|examples| examples.values().next())
+    {
+        return resolve(document, example).get("value").cloned();
+    }
+
+    media
+        .get("schema")
+        .map(|schema| example_from_schema(document, schema, 0))
+}
+
+/// Generates an example value that conforms to a JSON schema. Documented examples, defaults
+/// and enum values are preferred over generated values.
+fn example_from_schema(document: &Value, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Value::Null;
+    }
+
+    let schema = resolve(document, schema);
+
+    if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+        return example.clone();
+    }
+
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+
+    if let Some(value) = schema.get("enum").and_then(Value::as_array).and_then(|v| v.first()) {
+        return value.clone();
+    }
+
+    if let Some(examples) = schema.get("examples").and_then(Value::as_array).and_then(|v| v.first()) {
+        return examples.clone();
+    }
+
+    if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for schema in schemas {
+            if let Value::Object(values) = example_from_schema(document, schema, depth + 1) {
+                merged.extend(values);
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    if let Some(schema) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+        .and_then(|schemas| schemas.first())
+    {
+        return example_from_schema(document, schema, depth + 1);
+    }
+
+    match schema_type(schema) {
+        Some("object") => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .map(|(name, property)| (name.clone(), example_from_schema(document, property, depth + 1)))
+                .collect(),
+        ),
+        Some("array") => Value::Array(
+            schema
+                .get("items")
+                .map(|items| vec![example_from_schema(document, items, depth + 1)])
+                .unwrap_or_default(),
+        ),
+        Some("string") => Value::from(match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => "1970-01-01T00:00:00Z",
+            Some("date") => "1970-01-01",
+            Some("time") => "00:00:00Z",
+            Some("uuid") => "00000000-0000-0000-0000-000000000000",
+            Some("email") => "user@example.com",
+            Some("uri") | Some("url") => "https://example.com",
+            Some("hostname") => "example.com",
+            Some("ipv4") => "127.0.0.1",
+            Some("ipv6") => "::1",
+            _ => "string",
+        }),
+        Some("integer") => schema.get("minimum").cloned().unwrap_or_else(|| Value::from(0)),
+        Some("number") => schema.get("minimum").cloned().unwrap_or_else(|| Value::from(0.0)),
+        Some("boolean") => Value::Bool(true),
+        _ => Value::Null,
+    }
+}
+
+/// Returns the type of a schema. OpenAPI 3.1 allows a list of types, in which case the first
+/// type other than `null` is used. Schemas without a type but with properties are objects.
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => Some(t.as_str()),
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+        _ if schema.get("properties").is_some() => Some("object"),
+        _ if schema.get("items").is_some() => Some("array"),
+        _ => None,
+    }
+}
+
+/// Returns a regex pattern that accepts all textual representations of a parameter value
+/// with the given schema, or `None` if any value is accepted.
+fn value_pattern(schema: &Value) -> Option<String> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let alternatives: Vec<String> = values
+            .iter()
+            .map(|value| match value {
+                Value::String(s) => regex::escape(s),
+                other => regex::escape(&other.to_string()),
+            })
+            .collect();
+        return Some(alternatives.join("|"));
+    }
+
+    match schema_type(schema)? {
+        "integer" => Some("-?[0-9]+".to_string()),
+        "number" => Some(r"-?[0-9]+(\.[0-9]+)?([eE][-+]?[0-9]+)?".to_string()),
+        "boolean" => Some("true|false".to_string()),
+        _ => None,
+    }
+}
+
+/// Converts a media type range from a request body definition (e.g. `application/*`) into a
+/// regex pattern.
+fn media_type_pattern(media_type: &str) -> String {
+    match media_type.split_once('/') {
+        Some(("*", _)) => ".+".to_string(),
+        Some((main, "*")) => format!("{}/[^;]+", regex::escape(main)),
+        _ => regex::escape(media_type),
+    }
+}
+
+fn regex(pattern: &str) -> Result<HttpMockRegex, Error> {
+    regex::Regex::new(pattern)
+        .map(HttpMockRegex)
+        .map_err(|err| Error::Regex(pattern.to_string(), err))
+}
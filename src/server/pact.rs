@@ -0,0 +1,359 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value, json};
+
+use crate::common::data::{ActiveMock, HttpMockRegex, HttpMockRequest, MockServerHttpResponse, RequestRequirements};
+
+/// The Pact specification version of the generated contracts.
+const PACT_SPECIFICATION_VERSION: &str = "3.0.0";
+
+/// Builds a Pact (specification v3) contract from all mocks that have been called at least once.
+///
+/// The example request of each interaction is the first request that matched the mock. Only the
+/// request headers that the mock has requirements for (plus `content-type`, if the request has a
+/// body) are included. Requirements that are more relaxed than an exact comparison (e.g.,
+/// `path_matches`, `header_exists` or `json_body_includes`) are expressed as matching rules.
+/// The example response is the static response of the mock. Mocks that compute their response
+/// dynamically (e.g., using `respond_with`) are skipped.
+pub(crate) fn build_pact(consumer: &str, provider: &str, mocks: &[ActiveMock]) -> Value {
+    let mut descriptions = HashSet::new();
+    let mut interactions = Vec::new();
+
+    for mock in mocks.iter().filter(|mock| mock.call_counter > 0) {
+        let request = match &mock.first_request {
+            Some(request) => request,
+            None => continue,
+        };
+
+        // The example response of a response sequence is the response to the first request.
+        let response = &mock.definition.response;
+        let response = response
+            .sequence
+            .as_ref()
+            .and_then(|sequence| sequence.first())
+            .unwrap_or(response);
+        if response.respond_with.is_some() || response.responder.is_some() || response.script.is_some() {
+            tracing::warn!(
+                "Mock with ID={} computes its response dynamically and is not included in the Pact file",
+                mock.id
+            );
+            continue;
+        }
+
+        let mut description = format!("{} {}", request.method_str(), request.uri().path());
+        let mut counter = 1;
+        while !descriptions.insert(description.clone()) {
+            counter += 1;
+            description = format!("{} {} ({})", request.method_str(), request.uri().path(), counter);
+        }
+
+        interactions.push(json!({
+            "description": description,
+            "request": to_pact_request(request, &mock.definition.request),
+            "response": to_pact_response(response),
+        }));
+    }
+
+    json!({
+        "consumer": { "name": consumer },
+        "provider": { "name": provider },
+        "interactions": interactions,
+        "metadata": {
+            "pactSpecification": { "version": PACT_SPECIFICATION_VERSION },
+            "httpmock": { "version": env!("CARGO_PKG_VERSION") },
+        },
+    })
+}
+
+fn to_pact_request(request: &HttpMockRequest, requirements: &RequestRequirements) -> Value {
+    let uri = request.uri();
+    let mut pact_request = Map::new();
+
+    pact_request.insert("method".to_string(), json!(request.method_str().to_uppercase()));
+    pact_request.insert("path".to_string(), json!(uri.path()));
+
+    let query_params = request.query_params();
+    if !query_params.is_empty() {
+        let mut query = Map::new();
+        for (name, value) in &query_params {
+            query
+                .entry(name.clone())
+                .or_insert_with(|| json!([]))
+                .as_array_mut()
+                .unwrap()
+                .push(json!(value));
+        }
+        pact_request.insert("query".to_string(), Value::Object(query));
+    }
+
+    let body = body_value(request.body_ref());
+    let header_names = required_header_names(request, requirements);
+    let headers: Vec<(String, String)> = request
+        .headers_vec()
+        .iter()
+        .filter(|(name, _)| {
+            header_names.contains(&name.to_lowercase()) || (body.is_some() && name.eq_ignore_ascii_case("content-type"))
+        })
+        .cloned()
+        .collect();
+    if !headers.is_empty() {
+        pact_request.insert("headers".to_string(), headers_value(&headers));
+    }
+
+    if let Some(body) = body {
+        pact_request.insert("body".to_string(), body);
+    }
+
+    let matching_rules = matching_rules(request, requirements);
+    if !matching_rules.is_empty() {
+        pact_request.insert("matchingRules".to_string(), Value::Object(matching_rules));
+    }
+
+    Value::Object(pact_request)
+}
+
+fn to_pact_response(response: &MockServerHttpResponse) -> Value {
+    let mut pact_response = Map::new();
+
+    pact_response.insert("status".to_string(), json!(response.status.unwrap_or(200)));
+
+    if let Some(headers) = response.headers.as_ref().filter(|headers| !headers.is_empty()) {
+        pact_response.insert("headers".to_string(), headers_value(headers));
+    }
+
+    if let Some(body) = response.body.as_ref().and_then(|body| body_value(body.as_ref())) {
+        pact_response.insert("body".to_string(), body);
+    }
+
+    Value::Object(pact_response)
+}
+
+/// Returns the lowercase names of all request headers the mock has requirements for.
+fn required_header_names(request: &HttpMockRequest, requirements: &RequestRequirements) -> HashSet<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    names.extend(requirements.header_exists.iter().flatten().cloned());
+    for pairs in [
+        &requirements.header,
+        &requirements.header_includes,
+        &requirements.header_prefix,
+        &requirements.header_suffix,
+    ] {
+        names.extend(pairs.iter().flatten().map(|(name, _)| name.clone()));
+    }
+
+    for (name_regex, _) in requirements.header_matches.iter().flatten() {
+        names.extend(
+            request
+                .headers_vec()
+                .iter()
+                .filter(|(name, _)| name_regex.0.is_match(name))
+                .map(|(name, _)| name.clone()),
+        );
+    }
+
+    names.into_iter().map(|name| name.to_lowercase()).collect()
+}
+
+/// Translates requirements that are not exact comparisons into Pact matching rules.
+fn matching_rules(request: &HttpMockRequest, requirements: &RequestRequirements) -> Map<String, Value> {
+    let mut rules = Map::new();
+
+    if requirements.path.is_none() {
+        let regexes: Vec<String> = requirements
+            .path_matches
+            .iter()
+            .flatten()
+            .map(full_match)
+            .chain(requirements.path_prefix.iter().flatten().map(|v| prefix_regex(v)))
+            .chain(requirements.path_suffix.iter().flatten().map(|v| suffix_regex(v)))
+            .chain(requirements.path_includes.iter().flatten().map(|v| includes_regex(v)))
+            .collect();
+        if !regexes.is_empty() {
+            rules.insert("path".to_string(), regex_matchers(regexes));
+        }
+    }
+
+    let query_params = request.query_params();
+    let query_rules = value_rules(
+        &query_params,
+        &requirements.query_param_exists,
+        [
+            (&requirements.query_param_includes, includes_regex as fn(&str) -> String),
+            (&requirements.query_param_prefix, prefix_regex),
+            (&requirements.query_param_suffix, suffix_regex),
+        ],
+        &requirements.query_param_matches,
+        false,
+    );
+    if !query_rules.is_empty() {
+        rules.insert("query".to_string(), Value::Object(query_rules));
+    }
+
+    let header_rules = value_rules(
+        request.headers_vec(),
+        &requirements.header_exists,
+        [
+            (&requirements.header_includes, includes_regex as fn(&str) -> String),
+            (&requirements.header_prefix, prefix_regex),
+            (&requirements.header_suffix, suffix_regex),
+        ],
+        &requirements.header_matches,
+        true,
+    );
+    if !header_rules.is_empty() {
+        rules.insert("header".to_string(), Value::Object(header_rules));
+    }
+
+    let mut body_rules = Map::new();
+    for included in requirements.json_body_includes.iter().flatten() {
+        body_rules.insert("$".to_string(), matchers(vec![json!({ "match": "type" })]));
+        for path in leaf_paths(included, "$".to_string()) {
+            body_rules.insert(path, matchers(vec![json!({ "match": "equality" })]));
+        }
+    }
+    for path in requirements.json_body_path.iter().flatten() {
+        body_rules.insert(path.clone(), matchers(vec![json!({ "match": "type" })]));
+    }
+    let body_regexes: Vec<String> = requirements.body_matches.iter().flatten().map(full_match).collect();
+    if !body_regexes.is_empty() {
+        body_rules.insert("$".to_string(), regex_matchers(body_regexes));
+    }
+    if !body_rules.is_empty() {
+        rules.insert("body".to_string(), Value::Object(body_rules));
+    }
+
+    rules
+}
+
+/// Requirements on named values, each paired with a function that turns the expected value into
+/// a regex.
+type NamedValuePatterns<'a> = [(&'a Option<Vec<(String, String)>>, fn(&str) -> String); 3];
+
+/// Creates matching rules for named values (query parameters or headers).
+fn value_rules(
+    values: &[(String, String)],
+    exists: &Option<Vec<String>>,
+    patterns: NamedValuePatterns,
+    matches: &Option<Vec<(HttpMockRegex, HttpMockRegex)>>,
+    case_insensitive: bool,
+) -> Map<String, Value> {
+    let same_name = |a: &str, b: &str| {
+        if case_insensitive {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    };
+
+    let mut regexes: Vec<(String, String)> = Vec::new();
+
+    for name in exists.iter().flatten() {
+        regexes.push((name.clone(), ".*".to_string()));
+    }
+
+    for (pairs, to_regex) in patterns {
+        for (name, value) in pairs.iter().flatten() {
+            regexes.push((name.clone(), to_regex(value)));
+        }
+    }
+
+    for (name_regex, value_regex) in matches.iter().flatten() {
+        for (name, _) in values.iter().filter(|(name, _)| name_regex.0.is_match(name)) {
+            regexes.push((name.clone(), full_match(value_regex)));
+        }
+    }
+
+    let mut rules = Map::new();
+    for (name, regex) in regexes {
+        // Use the spelling of the name as it appears in the example request.
+        let name = values
+            .iter()
+            .find(|(value_name, _)| same_name(value_name, &name))
+            .map_or(name, |(value_name, _)| value_name.clone());
+
+        let rule = rules.entry(name).or_insert_with(|| matchers(Vec::new()));
+        rule["matchers"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "match": "regex", "regex": regex }));
+    }
+
+    rules
+}
+
+fn matchers(matchers: Vec<Value>) -> Value {
+    json!({ "matchers": matchers, "combine": "AND" })
+}
+
+fn regex_matchers(regexes: Vec<String>) -> Value {
+    matchers(
+        regexes
+            .into_iter()
+            .map(|regex| json!({ "match": "regex", "regex": regex }))
+            .collect(),
+    )
+}
+
+/// Pact regex matchers must match the whole value, whereas httpmock regexes match anywhere
+/// within a value unless they are anchored.
+fn full_match(regex: &HttpMockRegex) -> String {
+    let regex = regex.0.as_str();
+    if regex.starts_with('^') && regex.ends_with('$') {
+        regex.to_string()
+    } else {
+        format!("^.*(?:{}).*$", regex)
+    }
+}
+
+fn prefix_regex(value: &str) -> String {
+    format!("^{}.*$", regex::escape(value))
+}
+
+fn suffix_regex(value: &str) -> String {
+    format!("^.*{}$", regex::escape(value))
+}
+
+fn includes_regex(value: &str) -> String {
+    format!("^.*{}.*$", regex::escape(value))
+}
+
+/// Returns the JSONPath of every non-container value in `value`.
+fn leaf_paths(value: &Value, prefix: String) -> Vec<String> {
+    match value {
+        Value::Object(map) if !map.is_empty() => map
+            .iter()
+            .flat_map(|(key, value)| leaf_paths(value, format!("{}['{}']", prefix, key)))
+            .collect(),
+        Value::Array(values) if !values.is_empty() => values
+            .iter()
+            .enumerate()
+            .flat_map(|(index, value)| leaf_paths(value, format!("{}[{}]", prefix, index)))
+            .collect(),
+        _ => vec![prefix],
+    }
+}
+
+fn headers_value(headers: &[(String, String)]) -> Value {
+    let mut map: Map<String, Value> = Map::new();
+    for (name, value) in headers {
+        match map.get_mut(name) {
+            Some(Value::String(existing)) => existing.push_str(&format!(", {}", value)),
+            _ => {
+                map.insert(name.clone(), json!(value));
+            }
+        }
+    }
+    Value::Object(map)
+}
+
+/// Bodies that contain valid JSON are embedded as JSON, all other bodies as strings.
+fn body_value(body: &[u8]) -> Option<Value> {
+    if body.is_empty() {
+        return None;
+    }
+
+    serde_json::from_slice(body)
+        .ok()
+        .or_else(|| Some(json!(String::from_utf8_lossy(body))))
+}
@@ -14,7 +14,7 @@ use crate::{
         data,
         data::{MockDefinition, StaticMockDefinition},
     },
-    server::state,
+    server::{state, wiremock},
 };
 
 #[derive(Error, Debug)]
@@ -37,13 +37,25 @@ pub enum Error {
     Yaml(#[from] serde_yaml::Error),
     #[error("cannot convert data structures: {0}")]
     DataConversion(#[from] data::Error),
+    #[error("{0}")]
+    WireMock(#[from] wiremock::Error),
 }
 
+/// Loads all static mocks from a directory. Static mocks are read from httpmock's YAML files
+/// in the directory and from WireMock JSON stub mappings in its `mappings` subdirectory
+/// (WireMock's directory layout). Other JSON files in the directory are ignored.
 pub fn read_static_mock_definitions(path_opt: PathBuf, state: &state::Manager) -> Result<(), Error> {
-    for def in read_static_mocks(path_opt)? {
+    for def in read_static_mocks(path_opt.clone())? {
         state.add_mock(def.try_into()?, true)?;
     }
 
+    let mappings_dir = path_opt.join(wiremock::MAPPINGS_DIR);
+    if mappings_dir.is_dir() {
+        for def in wiremock::read_mappings(&mappings_dir)? {
+            state.add_mock(def, true)?;
+        }
+    }
+
     Ok(())
 }
 
@@ -61,6 +73,9 @@ fn read_static_mocks(path: PathBuf) -> Result<Vec<StaticMockDefinition>, Error>
                 source,
             })?
             .path();
+        if file_path.is_dir() {
+            continue;
+        }
         if let Some(ext) = file_path.extension()
             && !"yaml".eq(ext)
             && !"yml".eq(ext)
@@ -0,0 +1,475 @@
+use std::sync::LazyLock;
+#[cfg(feature = "record")]
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::common::{
+    data::{
+        ActiveRecording, BodyMatchMode, HttpMockRegex, MockDefinition, MockServerHttpResponse, RecordingRuleConfig,
+        RepeatedRequests, RequestRequirements,
+    },
+    json_path::JsonPath,
+    util::HttpMockBytes,
+};
+
+/// The placeholder that replaces redacted header values, query parameter values and JSON fields.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Response headers that typically change on every response, with the stable values that
+/// replace them (see `RecordingRuleBuilder::normalize_volatile_response_headers`).
+pub(crate) const VOLATILE_RESPONSE_HEADERS: [(&str, &str); 9] = [
+    ("date", "Thu, 01 Jan 1970 00:00:00 GMT"),
+    ("expires", "Thu, 01 Jan 1970 00:00:00 GMT"),
+    ("last-modified", "Thu, 01 Jan 1970 00:00:00 GMT"),
+    ("etag", "\"normalized\""),
+    ("x-request-id", "00000000-0000-0000-0000-000000000000"),
+    ("x-correlation-id", "00000000-0000-0000-0000-000000000000"),
+    ("request-id", "00000000-0000-0000-0000-000000000000"),
+    ("x-amzn-requestid", "00000000-0000-0000-0000-000000000000"),
+    ("x-github-request-id", "00000000-0000-0000-0000-000000000000"),
+];
+
+/// Matches ISO 8601 date-time strings, such as `2024-05-01T12:30:00Z` or
+/// `2024-05-01 12:30:00.123+02:00`.
+static TIMESTAMP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?$").unwrap());
+
+/// The file of a streaming recording (see `RecordingRuleBuilder::stream_to`). Clones share the
+/// same file, so that documents are appended one after another even if they are written from
+/// different threads.
+#[cfg(feature = "record")]
+#[derive(Clone)]
+pub(crate) struct RecordingStream {
+    path: PathBuf,
+    file: Arc<Mutex<StreamFile>>,
+}
+
+#[cfg(feature = "record")]
+struct StreamFile {
+    file: File,
+    documents: usize,
+}
+
+#[cfg(feature = "record")]
+impl RecordingStream {
+    /// Creates the file, replacing any existing file at `path`.
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let file = File::create(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Arc::new(Mutex::new(StreamFile { file, documents: 0 })),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a YAML document to the file, separated from the previous document.
+    pub(crate) fn append(&self, document: &[u8]) -> io::Result<()> {
+        let mut stream = self.file.lock().unwrap();
+
+        if stream.documents > 0 {
+            stream.file.write_all(b"---\n")?;
+        }
+        stream.file.write_all(document)?;
+        stream.documents += 1;
+
+        Ok(())
+    }
+
+    /// Reads the content of the file. Documents that are being appended concurrently are either
+    /// fully contained or not at all.
+    pub(crate) fn read(&self) -> io::Result<Vec<u8>> {
+        let _stream = self.file.lock().unwrap();
+        std::fs::read(&self.path)
+    }
+}
+
+/// Resolves the file that a recording created through the management API streams to.
+///
+/// Clients of the management API must not be able to write arbitrary files on the host of the
+/// mock server. Therefore, the file must be located inside the recording stream directory of the
+/// server (see `HttpMockServerBuilder::recording_stream_dir`). Relative paths are resolved
+/// against this directory.
+///
+/// # Returns
+/// The path of the file, or a description of why the path is not allowed.
+#[cfg(feature = "record")]
+pub(crate) fn resolve_stream_path(dir: Option<&Path>, path: &Path) -> Result<PathBuf, String> {
+    let Some(dir) = dir else {
+        return Err(
+            "this mock server has no recording stream directory, so recordings cannot be streamed to a file"
+                .to_string(),
+        );
+    };
+
+    let outside = || format!("'{}' is not inside the recording stream directory", path.display());
+
+    let relative = if path.is_absolute() {
+        path.strip_prefix(dir).map_err(|_| outside())?
+    } else {
+        path
+    };
+
+    let mut components = relative
+        .components()
+        .filter(|component| *component != Component::CurDir);
+    let has_file_name = components.clone().next().is_some();
+    if !has_file_name || !components.all(|component| matches!(component, Component::Normal(_))) {
+        return Err(outside());
+    }
+
+    Ok(dir.join(relative))
+}
+
+/// Adds a mock that was created from a recorded interaction to the mocks of a recording,
+/// according to how the recording handles repeated requests (see `RepeatedRequests`).
+pub(crate) fn add_recorded_mock(rec: &mut ActiveRecording, definition: MockDefinition) {
+    let mode = rec.config.repeated_requests;
+    let key = match mode {
+        RepeatedRequests::Keep => None,
+        RepeatedRequests::Collapse | RepeatedRequests::Sequence => serde_json::to_string(&definition.request).ok(),
+    };
+
+    let existing = key
+        .as_ref()
+        .and_then(|key| rec.mock_indices.get(key))
+        .and_then(|&index| rec.mocks.get_mut(index));
+
+    let Some(existing) = existing else {
+        if let Some(key) = key {
+            rec.mock_indices.insert(key, rec.mocks.len());
+        }
+        rec.mocks.push(definition);
+        return;
+    };
+
+    if mode == RepeatedRequests::Sequence {
+        if existing.response.sequence.is_none() {
+            let first = std::mem::take(&mut existing.response);
+            existing.response.sequence = Some(vec![first]);
+        }
+        existing
+            .response
+            .sequence
+            .get_or_insert_with(Vec::new)
+            .push(definition.response);
+    }
+}
+
+/// Relaxes the request requirements of a recorded mock according to the matching options of the
+/// recording rule (see `body_match_mode`, `ignore_query_params` and `exact_query`).
+///
+/// This must be applied after redaction: request bodies that have already been relaxed by a
+/// redaction rule are left untouched, unless the body is ignored altogether.
+pub(crate) fn apply_match_options(request: &mut RequestRequirements, config: &RecordingRuleConfig) {
+    if let Some(params) = request.query_param.as_mut() {
+        params.retain(|(name, _)| !config.ignore_query_params.contains(name));
+        if params.is_empty() {
+            request.query_param = None;
+        }
+    }
+
+    if config.exact_query && config.ignore_query_params.is_empty() {
+        let count =
+            request.query_param.as_ref().map_or(0, Vec::len) + request.query_param_exists.as_ref().map_or(0, Vec::len);
+        let any = || HttpMockRegex(Regex::new(".*").unwrap());
+        request.query_param_count = Some(vec![(any(), any(), count)]);
+    }
+
+    match config.body_match_mode {
+        BodyMatchMode::Exact => {}
+        BodyMatchMode::Json | BodyMatchMode::JsonIncludes => {
+            if let Some(value) = request
+                .body
+                .as_ref()
+                .and_then(|body| serde_json::from_slice::<Value>(body.as_ref()).ok())
+            {
+                request.body = None;
+                if config.body_match_mode == BodyMatchMode::Json {
+                    request.json_body = Some(value);
+                } else {
+                    request.json_body_includes.get_or_insert_with(Vec::new).push(value);
+                }
+            }
+        }
+        BodyMatchMode::None => {
+            request.body = None;
+            request.body_matches = None;
+            request.json_body_includes = None;
+            request.json_body_path = None;
+        }
+    }
+}
+
+/// Removes sensitive values from the request requirements of a recorded mock.
+///
+/// Redacted values are not stored, but the mock must still match the same requests on playback.
+/// Therefore, redacted headers and query parameters are only required to be present, redacted
+/// JSON fields are only required to exist, and text that matches a body redaction regex may be
+/// anything.
+pub(crate) fn redact_request(request: &mut RequestRequirements, config: &RecordingRuleConfig) {
+    if let Some(headers) = request.header.take() {
+        let (redacted, kept): (Vec<_>, Vec<_>) = headers
+            .into_iter()
+            .partition(|(name, _)| contains_ignore_case(&config.redact_headers, name));
+        request.header = (!kept.is_empty()).then_some(kept);
+        for (name, _) in redacted {
+            push_unique(&mut request.header_exists, name);
+        }
+    }
+
+    if let Some(params) = request.query_param.take() {
+        let (redacted, kept): (Vec<_>, Vec<_>) = params
+            .into_iter()
+            .partition(|(name, _)| config.redact_query_params.contains(name));
+        request.query_param = (!kept.is_empty()).then_some(kept);
+        for (name, _) in redacted {
+            push_unique(&mut request.query_param_exists, name);
+        }
+    }
+
+    if let Some(body) = &request.body {
+        let text = String::from_utf8_lossy(body.as_ref()).to_string();
+
+        match serde_json::from_str::<Value>(&text) {
+            Ok(mut value) if value.is_object() || value.is_array() => {
+                let mut redacted_paths = Vec::new();
+                for path in &config.redact_json_fields {
+                    if JsonPath::parse(path).is_ok_and(|json_path| json_path.remove(&mut value) > 0) {
+                        redacted_paths.push(path.clone());
+                    }
+                }
+                let strings_removed = remove_matching_strings(&mut value, &config.redact_body_regex);
+
+                if !redacted_paths.is_empty() || strings_removed {
+                    request.body = None;
+                    request.json_body_includes.get_or_insert_with(Vec::new).push(value);
+                    request
+                        .json_body_path
+                        .get_or_insert_with(Vec::new)
+                        .extend(redacted_paths);
+                }
+            }
+            _ => {
+                if let Some(pattern) = redacted_text_pattern(&text, &config.redact_body_regex) {
+                    request.body = None;
+                    request.body_matches.get_or_insert_with(Vec::new).push(HttpMockRegex(
+                        regex::Regex::new(&pattern).expect("escaped text must be a valid regex"),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Replaces sensitive values in the response of a recorded mock with placeholders.
+pub(crate) fn redact_response(response: &mut MockServerHttpResponse, config: &RecordingRuleConfig) {
+    for (name, value) in response.headers.iter_mut().flatten() {
+        if contains_ignore_case(&config.redact_headers, name) {
+            *value = REDACTED.to_string();
+        }
+    }
+
+    let body = match &response.body {
+        Some(body) => body.to_bytes(),
+        None => return,
+    };
+
+    let mut text = match String::from_utf8(body.to_vec()) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    if !config.redact_json_fields.is_empty()
+        && let Ok(mut value) = serde_json::from_str::<Value>(&text)
+    {
+        let mut redacted = false;
+        for path in config
+            .redact_json_fields
+            .iter()
+            .filter_map(|path| JsonPath::parse(path).ok())
+        {
+            path.for_each_mut(&mut value, &mut |selected| {
+                *selected = Value::String(REDACTED.to_string());
+                redacted = true;
+            });
+        }
+
+        if redacted {
+            text = value.to_string();
+        }
+    }
+
+    for (regex, replacement) in &config.redact_body_regex {
+        text = regex.0.replace_all(&text, replacement.as_str()).into_owned();
+    }
+
+    if text.as_bytes() != body.as_ref() {
+        set_body(response, text);
+    }
+}
+
+/// Removes response headers that should not be recorded (see `record_response_headers` and
+/// `drop_response_headers`).
+pub(crate) fn filter_response_headers(response: &mut MockServerHttpResponse, config: &RecordingRuleConfig) {
+    if let Some(headers) = response.headers.as_mut() {
+        headers.retain(|(name, _)| {
+            let allowed = config
+                .record_response_headers
+                .as_ref()
+                .is_none_or(|allowed| contains_ignore_case(allowed, name));
+            allowed && !contains_ignore_case(&config.drop_response_headers, name)
+        });
+    }
+}
+
+/// Replaces volatile values in the response of a recorded mock (e.g., dates or request IDs)
+/// with stable values, so that re-recording the same interaction produces the same file.
+pub(crate) fn normalize_response(response: &mut MockServerHttpResponse, config: &RecordingRuleConfig) {
+    for (name, value) in response.headers.iter_mut().flatten() {
+        if let Some((_, normalized)) = config
+            .normalize_response_headers
+            .iter()
+            .find(|(normalized_name, _)| normalized_name.eq_ignore_ascii_case(name))
+        {
+            *value = normalized.clone();
+        }
+    }
+
+    if config.normalize_json_fields.is_empty() && config.normalize_json_timestamps.is_none() {
+        return;
+    }
+
+    let mut value = match response
+        .body
+        .as_ref()
+        .and_then(|body| serde_json::from_slice::<Value>(body.as_ref()).ok())
+    {
+        Some(value) => value,
+        None => return,
+    };
+
+    let original = value.clone();
+
+    for (path, normalized) in &config.normalize_json_fields {
+        if let Ok(path) = JsonPath::parse(path) {
+            path.for_each_mut(&mut value, &mut |selected| *selected = normalized.clone());
+        }
+    }
+
+    if let Some(placeholder) = &config.normalize_json_timestamps {
+        replace_timestamps(&mut value, placeholder);
+    }
+
+    if value != original {
+        set_body(response, value.to_string());
+    }
+}
+
+fn replace_timestamps(value: &mut Value, placeholder: &str) {
+    match value {
+        Value::String(text) if TIMESTAMP.is_match(text) => *text = placeholder.to_string(),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|child| replace_timestamps(child, placeholder)),
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|child| replace_timestamps(child, placeholder)),
+        _ => {}
+    }
+}
+
+/// Replaces the response body and updates a recorded `content-length` header accordingly.
+fn set_body(response: &mut MockServerHttpResponse, body: String) {
+    for (name, value) in response.headers.iter_mut().flatten() {
+        if name.eq_ignore_ascii_case("content-length") {
+            *value = body.len().to_string();
+        }
+    }
+    response.body = Some(HttpMockBytes::from(Bytes::from(body)));
+}
+
+/// Removes all string values that match one of the regexes from `value`. Returns `true` if
+/// any value was removed.
+fn remove_matching_strings(value: &mut Value, regexes: &[(HttpMockRegex, String)]) -> bool {
+    let is_match = |value: &Value| {
+        value
+            .as_str()
+            .is_some_and(|text| regexes.iter().any(|(regex, _)| regex.0.is_match(text)))
+    };
+
+    let mut removed = false;
+    match value {
+        Value::Object(map) => {
+            let len = map.len();
+            map.retain(|_, value| !is_match(value));
+            removed |= map.len() != len;
+            for child in map.values_mut() {
+                removed |= remove_matching_strings(child, regexes);
+            }
+        }
+        Value::Array(values) => {
+            let len = values.len();
+            values.retain(|value| !is_match(value));
+            removed |= values.len() != len;
+            for child in values.iter_mut() {
+                removed |= remove_matching_strings(child, regexes);
+            }
+        }
+        _ => {}
+    }
+
+    removed
+}
+
+/// Creates a regex that matches `text`, where all parts that match one of the redaction regexes
+/// may be replaced by any other text. Returns `None` if no part of `text` matches.
+fn redacted_text_pattern(text: &str, regexes: &[(HttpMockRegex, String)]) -> Option<String> {
+    let mut ranges: Vec<(usize, usize)> = regexes
+        .iter()
+        .flat_map(|(regex, _)| regex.0.find_iter(text).map(|m| (m.start(), m.end())))
+        .filter(|(start, end)| start < end)
+        .collect();
+
+    if ranges.is_empty() {
+        return None;
+    }
+
+    ranges.sort_unstable();
+
+    let mut pattern = String::from("(?s)^");
+    let mut position = 0;
+    for (start, end) in ranges {
+        if start >= position {
+            pattern.push_str(&regex::escape(&text[position..start]));
+            pattern.push_str(".*");
+        }
+        position = position.max(end);
+    }
+    pattern.push_str(&regex::escape(&text[position..]));
+    pattern.push('$');
+
+    Some(pattern)
+}
+
+fn contains_ignore_case(names: &[String], name: &str) -> bool {
+    names.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn push_unique(values: &mut Option<Vec<String>>, value: String) {
+    let values = values.get_or_insert_with(Vec::new);
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
@@ -0,0 +1,235 @@
+use std::sync::{Arc, LazyLock};
+
+use rhai::{AST, Dynamic, Engine, EvalAltResult, ImmutableString, Map, Scope, module_resolvers::DummyModuleResolver};
+
+use crate::{
+    common::data::{HttpMockRequest, HttpMockResponse, HttpMockResponseBuilder, RequestPredicate, ResponseCallback},
+    server::state::{Error, Error::ScriptError},
+};
+
+/// Upper bound for the number of operations a single responder script evaluation may perform.
+/// This prevents scripts with infinite loops from blocking the server.
+const MAX_RESPONDER_OPERATIONS: u64 = 1_000_000;
+
+/// Upper bound for the number of operations a single predicate script evaluation may perform.
+/// Predicates are evaluated while the server state is locked, so this budget is much smaller
+/// than the one for responders to keep the server responsive for other requests.
+const MAX_PREDICATE_OPERATIONS: u64 = 10_000;
+
+/// Upper bounds for the size of strings, arrays and object maps a script may create. These
+/// prevent scripts from exhausting the server's memory within the operation budget.
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 10_000;
+const MAX_MAP_SIZE: usize = 10_000;
+
+/// The name of the variable that holds the request inside a script.
+const REQUEST_VARIABLE: &str = "request";
+
+static PREDICATE_ENGINE: LazyLock<Engine> = LazyLock::new(|| new_engine(MAX_PREDICATE_OPERATIONS));
+static RESPONDER_ENGINE: LazyLock<Engine> = LazyLock::new(|| new_engine(MAX_RESPONDER_OPERATIONS));
+
+fn new_engine(max_operations: u64) -> Engine {
+    let mut engine = Engine::new();
+
+    // Scripts may come from remote clients or static mock files, so they must not be able to
+    // access the server's filesystem (through `import`) or write to its output.
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {});
+
+    engine
+        .set_max_operations(max_operations)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE);
+
+    // Read-only view of the request. Only getters are registered, so scripts cannot
+    // modify the request.
+    engine
+        .register_type_with_name::<HttpMockRequest>("HttpMockRequest")
+        .register_get("scheme", |req: &mut HttpMockRequest| req.scheme())
+        .register_get("method", |req: &mut HttpMockRequest| req.method_str().to_string())
+        .register_get("uri", |req: &mut HttpMockRequest| req.uri_str().to_string())
+        .register_get("path", |req: &mut HttpMockRequest| req.uri().path().to_string())
+        .register_get("host", |req: &mut HttpMockRequest| {
+            req.host().map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+        })
+        .register_get("port", |req: &mut HttpMockRequest| req.port() as i64)
+        .register_get("headers", |req: &mut HttpMockRequest| {
+            to_map(req.headers_vec().iter().map(|(k, v)| (k.to_lowercase(), v.clone())))
+        })
+        .register_get("query", |req: &mut HttpMockRequest| to_map(req.query_params()))
+        .register_get("body", |req: &mut HttpMockRequest| req.body_string())
+        .register_get("json", |req: &mut HttpMockRequest| {
+            serde_json::from_slice::<serde_json::Value>(req.body_ref())
+                .ok()
+                .and_then(|value| rhai::serde::to_dynamic(value).ok())
+                .unwrap_or(Dynamic::UNIT)
+        })
+        .register_fn("header", |req: &mut HttpMockRequest, name: &str| {
+            req.headers_vec()
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| Dynamic::from(v.clone()))
+                .unwrap_or(Dynamic::UNIT)
+        })
+        .register_fn("query_param", |req: &mut HttpMockRequest, name: &str| {
+            req.query_params()
+                .into_iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| Dynamic::from(v))
+                .unwrap_or(Dynamic::UNIT)
+        });
+
+    // Response builder, e.g. `response().status(201).header("x", "y").body("...")`.
+    engine
+        .register_type_with_name::<HttpMockResponseBuilder>("HttpMockResponseBuilder")
+        .register_fn("response", HttpMockResponseBuilder::new)
+        .register_fn(
+            "status",
+            |builder: HttpMockResponseBuilder, status: i64| -> Result<HttpMockResponseBuilder, Box<EvalAltResult>> {
+                Ok(builder.status(to_status(status)?))
+            },
+        )
+        .register_fn("header", |builder: HttpMockResponseBuilder, name: &str, value: &str| {
+            builder.header(name, value)
+        })
+        .register_fn("body", |builder: HttpMockResponseBuilder, body: &str| {
+            builder.body(body.to_string())
+        })
+        .register_fn("json_body", |builder: HttpMockResponseBuilder, body: Dynamic| {
+            let json = rhai::serde::from_dynamic::<serde_json::Value>(&body).unwrap_or(serde_json::Value::Null);
+            builder
+                .header("content-type", "application/json")
+                .body(json.to_string())
+        });
+
+    engine
+}
+
+/// Compiles a script that decides whether a request matches. The script must evaluate to
+/// a boolean. Scripts that fail at runtime are treated as not matching.
+pub(crate) fn compile_predicate(script: &str) -> Result<RequestPredicate, Error> {
+    let ast = compile(&PREDICATE_ENGINE, script)?;
+
+    Ok(Arc::new(move |req: &HttpMockRequest| {
+        match evaluate(&PREDICATE_ENGINE, &ast, req) {
+            Ok(value) => value.as_bool().unwrap_or_else(|_| {
+                tracing::warn!("Script predicate returned a non-boolean value: {}", value.type_name());
+                false
+            }),
+            Err(err) => {
+                tracing::warn!("Script predicate failed: {}", err);
+                false
+            }
+        }
+    }))
+}
+
+/// Compiles a script that creates the response for a request. The script may evaluate to a
+/// response builder (`response()...`), an object map with the keys `status`, `headers`
+/// and `body`, or a string that is used as the body of a `200 OK` response. Scripts that
+/// fail at runtime result in a `500 Internal Server Error` response.
+pub(crate) fn compile_responder(script: &str) -> Result<ResponseCallback, Error> {
+    let ast = compile(&RESPONDER_ENGINE, script)?;
+
+    Ok(Arc::new(move |req: &HttpMockRequest| {
+        evaluate(&RESPONDER_ENGINE, &ast, req)
+            .and_then(to_response)
+            .unwrap_or_else(|err| {
+                tracing::warn!("Script responder failed: {}", err);
+                HttpMockResponse::builder()
+                    .status(500)
+                    .body(format!("script error: {}", err))
+                    .build()
+            })
+    }))
+}
+
+fn compile(engine: &Engine, script: &str) -> Result<AST, Error> {
+    engine.compile(script).map_err(|err| ScriptError(err.to_string()))
+}
+
+fn evaluate(engine: &Engine, ast: &AST, req: &HttpMockRequest) -> Result<Dynamic, String> {
+    let mut scope = Scope::new();
+    scope.push_constant(REQUEST_VARIABLE, req.clone());
+
+    engine
+        .eval_ast_with_scope::<Dynamic>(&mut scope, ast)
+        .map_err(|err| err.to_string())
+}
+
+/// Converts a status code returned by a script, rejecting values that are not valid status codes
+/// instead of truncating them.
+fn to_status(status: i64) -> Result<u16, String> {
+    u16::try_from(status)
+        .ok()
+        .filter(|status| (100..=999).contains(status))
+        .ok_or_else(|| format!("status must be between 100 and 999, got {}", status))
+}
+
+fn to_response(value: Dynamic) -> Result<HttpMockResponse, String> {
+    if value.is::<HttpMockResponseBuilder>() {
+        let builder = value.cast::<HttpMockResponseBuilder>();
+        let response = builder.build();
+        return Ok(HttpMockResponse {
+            status: response.status.or(Some(200)),
+            ..response
+        });
+    }
+
+    if value.is_string() {
+        let body = value.into_immutable_string().map_err(|t| t.to_string())?;
+        return Ok(HttpMockResponse::builder().status(200).body(body.to_string()).build());
+    }
+
+    if value.is_map() {
+        let map = value.cast::<Map>();
+        let mut builder = HttpMockResponse::builder().status(200);
+
+        if let Some(status) = map.get("status") {
+            let status = status
+                .as_int()
+                .map_err(|t| format!("status must be an integer, got {}", t))?;
+            builder = builder.status(to_status(status)?);
+        }
+
+        if let Some(headers) = map.get("headers") {
+            let headers = headers
+                .clone()
+                .try_cast::<Map>()
+                .ok_or_else(|| "headers must be an object map".to_string())?;
+            for (name, value) in headers {
+                builder = builder.header(name.to_string(), value.to_string());
+            }
+        }
+
+        if let Some(body) = map.get("body") {
+            builder = if body.is_string() {
+                builder.body(body.to_string())
+            } else {
+                let json = rhai::serde::from_dynamic::<serde_json::Value>(body).map_err(|err| err.to_string())?;
+                builder
+                    .header("content-type", "application/json")
+                    .body(json.to_string())
+            };
+        }
+
+        return Ok(builder.build());
+    }
+
+    Err(format!(
+        "script must return a response, an object map or a string, but returned {}",
+        value.type_name()
+    ))
+}
+
+fn to_map<I: IntoIterator<Item = (String, String)>>(values: I) -> Map {
+    values
+        .into_iter()
+        .map(|(k, v)| (k.into(), Dynamic::from(ImmutableString::from(v))))
+        .collect()
+}
@@ -8,24 +8,30 @@ use std::{
 use bytes::Bytes;
 use thiserror::Error;
 
+#[cfg(feature = "proxy")]
+use crate::common::http::parse_upstream_proxy;
+
 #[cfg(feature = "record")]
 use crate::{
     common::data,
-    server::{
-        persistence::{deserialize_mock_defs_from_yaml, serialize_mock_defs_to_yaml},
-        state::Error::ValidationError,
-    },
+    common::data::RepeatedRequests,
+    server::persistence::{deserialize_mock_defs_from_yaml, serialize_mock_defs_to_yaml},
+    server::recording::RecordingStream,
+    server::state::Error::RecordingWriteError,
 };
 use crate::{
     common::data::{
         ActiveForwardingRule, ActiveMock, ActiveProxyRule, ActiveRecording, ClosestMatch, ForwardingRuleConfig,
         Mismatch, MockDefinition, MockServerHttpResponse, ProxyRuleConfig, RecordingRuleConfig, RequestRequirements,
     },
+    common::json_path::JsonPath,
     prelude::HttpMockRequest,
     server::{
+        functions::FunctionRegistry,
         matchers,
         matchers::Matcher,
-        state::Error::{BodyMethodInvalid, DataConversionError, StaticMockError},
+        recording,
+        state::Error::{BodyMethodInvalid, DataConversionError, StaticMockError, ValidationError},
     },
 };
 
@@ -39,6 +45,12 @@ pub enum Error {
     DataConversionError(String),
     #[error("validation error: {0}")]
     ValidationError(String),
+    #[error("no {0} named '{1}' has been registered on the mock server")]
+    UnregisteredFunction(&'static str, String),
+    #[error("invalid script: {0}")]
+    ScriptError(String),
+    #[error("cannot write recording: {0}")]
+    RecordingWriteError(String),
     #[error("unknown error")]
     Unknown,
 }
@@ -61,6 +73,10 @@ pub(crate) struct Inner {
     pub forwarding_rules: BTreeMap<usize, ActiveForwardingRule>,
     pub proxy_rules: BTreeMap<usize, ActiveProxyRule>,
     pub recordings: BTreeMap<usize, ActiveRecording>,
+    /// The files of streaming recordings, by recording ID.
+    #[cfg(feature = "record")]
+    pub recording_streams: BTreeMap<usize, RecordingStream>,
+    pub functions: FunctionRegistry,
 }
 
 impl Inner {
@@ -70,6 +86,8 @@ impl Inner {
             forwarding_rules: BTreeMap::new(),
             proxy_rules: BTreeMap::new(),
             recordings: BTreeMap::new(),
+            #[cfg(feature = "record")]
+            recording_streams: BTreeMap::new(),
             history: Vec::new(),
             history_limit,
             next_mock_id: 0,
@@ -77,6 +95,7 @@ impl Inner {
             next_proxy_rule_id: 0,
             next_recording_id: 0,
             matchers: matchers::all(),
+            functions: FunctionRegistry::default(),
         }
     }
 }
@@ -101,11 +120,21 @@ impl Manager {
         self.delete_all_recordings();
     }
 
-    pub(crate) fn add_mock(&self, definition: MockDefinition, is_static: bool) -> Result<ActiveMock, Error> {
+    /// Registers named predicates and responders that mocks and rules can refer to.
+    pub(crate) fn register_functions(&self, functions: FunctionRegistry) {
+        let mut state = self.state.lock().unwrap();
+        state.functions.extend(functions);
+    }
+
+    pub(crate) fn add_mock(&self, mut definition: MockDefinition, is_static: bool) -> Result<ActiveMock, Error> {
         validate_request_requirements(&definition.request)?;
+        validate_response(&definition.response)?;
 
         let mut state = self.state.lock().unwrap();
 
+        state.functions.resolve_request_requirements(&mut definition.request)?;
+        state.functions.resolve_response(&mut definition.response)?;
+
         let id = state.next_mock_id;
         let active_mock = ActiveMock::new(id, definition, 0, is_static);
 
@@ -128,6 +157,11 @@ impl Manager {
         }
     }
 
+    pub(crate) fn read_mocks(&self) -> Vec<ActiveMock> {
+        let state = self.state.lock().unwrap();
+        state.mocks.values().cloned().collect()
+    }
+
     pub(crate) fn delete_mock(&self, id: usize) -> Result<bool, Error> {
         let mut state = self.state.lock().unwrap();
 
@@ -168,6 +202,10 @@ impl Manager {
     pub(crate) fn verify(&self, requirements: &RequestRequirements) -> Result<Option<ClosestMatch>, Error> {
         let state = self.state.lock().unwrap();
 
+        let mut requirements = requirements.clone();
+        state.functions.resolve_request_requirements(&mut requirements)?;
+        let requirements = &requirements;
+
         let non_matching_requests: Vec<&Arc<HttpMockRequest>> = state
             .history
             .iter()
@@ -214,8 +252,11 @@ impl Manager {
 
             let mock = state.mocks.get_mut(&found_id).unwrap();
             mock.call_counter += 1;
+            if mock.first_request.is_none() {
+                mock.first_request = Some(HttpMockRequest::clone(&req));
+            }
 
-            return Ok(Some(mock.definition.response.clone()));
+            return Ok(Some(mock.definition.response.for_call(mock.call_counter)));
         }
 
         tracing::debug!("Could not match any mock to the following request: {:#?}", req);
@@ -223,19 +264,54 @@ impl Manager {
         Ok(None)
     }
 
-    pub(crate) fn create_forwarding_rule(&self, config: ForwardingRuleConfig) -> ActiveForwardingRule {
+    pub(crate) fn create_forwarding_rule(
+        &self,
+        mut config: ForwardingRuleConfig,
+    ) -> Result<ActiveForwardingRule, Error> {
         let mut state = self.state.lock().unwrap();
 
-        let rule = ActiveForwardingRule {
-            id: state.next_forwarding_rule_id,
-            config,
-        };
+        state
+            .functions
+            .resolve_request_requirements(&mut config.request_requirements)?;
+
+        config.sync_target_base_url();
+        if config.targets.is_empty() {
+            return Err(ValidationError(
+                "a forwarding rule needs at least one target".to_string(),
+            ));
+        }
+
+        for target in &config.targets {
+            let valid = target
+                .base_url
+                .parse::<http::Uri>()
+                .is_ok_and(|uri| uri.scheme().is_some() && uri.authority().is_some());
+            if !valid {
+                return Err(ValidationError(format!(
+                    "invalid forwarding target base URL '{}'",
+                    target.base_url
+                )));
+            }
+        }
+
+        if !config.faults.has_valid_probabilities() {
+            return Err(ValidationError(
+                "fault injection probabilities must be between 0 and 1".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "proxy")]
+        if let Some(url) = &config.upstream_proxy {
+            parse_upstream_proxy(url).map_err(|err| ValidationError(err.to_string()))?;
+        }
+
+        let rule = ActiveForwardingRule::new(state.next_forwarding_rule_id, config);
 
         state.forwarding_rules.insert(rule.id, rule.clone());
 
         state.next_forwarding_rule_id += 1;
 
-        rule
+        Ok(rule)
     }
 
     pub(crate) fn delete_forwarding_rule(&self, id: usize) -> Option<ActiveForwardingRule> {
@@ -262,19 +338,31 @@ impl Manager {
         tracing::debug!("Deleted all forwarding rules");
     }
 
-    pub(crate) fn create_proxy_rule(&self, config: ProxyRuleConfig) -> ActiveProxyRule {
+    pub(crate) fn create_proxy_rule(&self, mut config: ProxyRuleConfig) -> Result<ActiveProxyRule, Error> {
         let mut state = self.state.lock().unwrap();
 
-        let rule = ActiveProxyRule {
-            id: state.next_proxy_rule_id,
-            config,
-        };
+        state
+            .functions
+            .resolve_request_requirements(&mut config.request_requirements)?;
+
+        if !config.faults.has_valid_probabilities() {
+            return Err(ValidationError(
+                "fault injection probabilities must be between 0 and 1".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "proxy")]
+        if let Some(url) = &config.upstream_proxy {
+            parse_upstream_proxy(url).map_err(|err| ValidationError(err.to_string()))?;
+        }
+
+        let rule = ActiveProxyRule::new(state.next_proxy_rule_id, config);
 
         state.proxy_rules.insert(rule.id, rule.clone());
 
         state.next_proxy_rule_id += 1;
 
-        rule
+        Ok(rule)
     }
 
     pub(crate) fn delete_proxy_rule(&self, id: usize) -> Option<ActiveProxyRule> {
@@ -301,26 +389,59 @@ impl Manager {
         tracing::debug!("Deleted all proxy rules");
     }
 
-    pub(crate) fn create_recording(&self, config: RecordingRuleConfig) -> ActiveRecording {
-        let mut state = self.state.lock().unwrap();
+    pub(crate) fn create_recording(&self, mut config: RecordingRuleConfig) -> Result<ActiveRecording, Error> {
+        let normalized_paths = config.normalize_json_fields.iter().map(|(path, _)| path);
+        for path in config.redact_json_fields.iter().chain(normalized_paths) {
+            JsonPath::parse(path).map_err(ValidationError)?;
+        }
 
-        let rec = ActiveRecording {
-            id: state.next_recording_id,
-            config,
-            mocks: Vec::new(),
+        // The file is created before the state is locked, so that file system access does not
+        // block other requests.
+        #[cfg(feature = "record")]
+        let stream = match &config.stream_to {
+            Some(path) => {
+                if config.repeated_requests != RepeatedRequests::Keep {
+                    return Err(ValidationError(
+                        "repeated requests cannot be collapsed or combined into sequences when the recording is streamed to a file"
+                            .to_string(),
+                    ));
+                }
+
+                Some(
+                    RecordingStream::create(path).map_err(|err| {
+                        RecordingWriteError(format!("cannot create file '{}': {}", path.display(), err))
+                    })?,
+                )
+            }
+            None => None,
         };
 
+        let mut state = self.state.lock().unwrap();
+
+        state
+            .functions
+            .resolve_request_requirements(&mut config.request_requirements)?;
+
+        let rec = ActiveRecording::new(state.next_recording_id, config);
+
         state.recordings.insert(rec.id, rec.clone());
 
+        #[cfg(feature = "record")]
+        if let Some(stream) = stream {
+            state.recording_streams.insert(rec.id, stream);
+        }
+
         state.next_recording_id += 1;
 
-        rec
+        Ok(rec)
     }
 
     pub(crate) fn delete_recording(&self, id: usize) -> Option<ActiveRecording> {
         let mut state = self.state.lock().unwrap();
 
         let result = state.recordings.remove(&id);
+        #[cfg(feature = "record")]
+        state.recording_streams.remove(&id);
 
         if result.is_some() {
             tracing::debug!("Deleting proxy rule with id={}", id);
@@ -337,21 +458,37 @@ impl Manager {
     pub(crate) fn delete_all_recordings(&self) {
         let mut state = self.state.lock().unwrap();
         state.recordings.clear();
+        #[cfg(feature = "record")]
+        state.recording_streams.clear();
 
         tracing::debug!("Deleted all recorders");
     }
 
     #[cfg(feature = "record")]
     pub(crate) fn export_recording(&self, id: usize) -> Result<Option<Bytes>, Error> {
-        let state = self.state.lock().unwrap();
+        let stream = {
+            let state = self.state.lock().unwrap();
+
+            let Some(rec) = state.recordings.get(&id) else {
+                return Ok(None);
+            };
+
+            match state.recording_streams.get(&id) {
+                Some(stream) => stream.clone(),
+                None => {
+                    return Ok(Some(
+                        serialize_mock_defs_to_yaml(&rec.mocks).map_err(|err| DataConversionError(err.to_string()))?,
+                    ));
+                }
+            }
+        };
 
-        if let Some(rec) = state.recordings.get(&id) {
-            return Ok(Some(
-                serialize_mock_defs_to_yaml(&rec.mocks).map_err(|err| DataConversionError(err.to_string()))?,
-            ));
-        }
+        // The file of a streaming recording is read after the state was unlocked.
+        let content = stream
+            .read()
+            .map_err(|err| RecordingWriteError(format!("cannot read file '{}': {}", stream.path().display(), err)))?;
 
-        Ok(None)
+        Ok(Some(Bytes::from(content)))
     }
 
     #[cfg(feature = "record")]
@@ -383,13 +520,27 @@ impl Manager {
         &'a self,
         req: &'a HttpMockRequest,
     ) -> Result<Option<ActiveForwardingRule>, Error> {
-        let state = self.state.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
 
-        let result = state
+        let found_id = state
             .forwarding_rules
             .values()
-            .find(|&rule| request_matches(&state.matchers, req, &rule.config.request_requirements))
-            .cloned();
+            .find(|&rule| {
+                request_matches(&state.matchers, req, &rule.config.request_requirements)
+                    && !(rule.config.fallback
+                        && state
+                            .mocks
+                            .values()
+                            .any(|mock| request_matches(&state.matchers, req, &mock.definition.request)))
+            })
+            .map(|rule| rule.id);
+
+        let result = found_id.map(|id| {
+            let rule = state.forwarding_rules.get_mut(&id).unwrap();
+            let result = rule.clone();
+            rule.forwarded_requests += 1;
+            result
+        });
 
         Ok(result)
     }
@@ -400,40 +551,92 @@ impl Manager {
         let result = state
             .proxy_rules
             .values()
+            .filter(|rule| !rule.config.passthrough_tunnel.enabled)
             .find(|&rule| request_matches(&state.matchers, req, &rule.config.request_requirements))
             .cloned();
 
         Ok(result)
     }
 
-    pub(crate) fn record<
+    /// Returns the proxy rule that passes a `CONNECT` tunnel to `host` through to the host
+    /// instead of intercepting it, if any.
+    pub(crate) fn find_passthrough_tunnel_rule(&self, host: &str) -> Option<ActiveProxyRule> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .proxy_rules
+            .values()
+            .find(|rule| rule.config.passthrough_tunnel.applies_to(host))
+            .cloned()
+    }
+
+    /// Returns true if any mock sends callbacks, either itself or in one of its sequence responses.
+    pub(crate) fn has_callbacks(&self) -> bool {
+        let state = self.state.lock().unwrap();
+
+        state.mocks.values().any(|mock| {
+            let response = &mock.definition.response;
+            response.callbacks.is_some() || response.sequence.iter().flatten().any(|r| r.callbacks.is_some())
+        })
+    }
+
+    pub(crate) async fn record<
         IntoResponse: TryInto<MockServerHttpResponse, Error = impl std::fmt::Display + std::fmt::Debug + 'static>,
     >(
         &self,
         is_proxied: bool,
+        from_upstream: bool,
         time_taken: Duration,
         req: HttpMockRequest,
         res: IntoResponse,
     ) -> Result<(), Error> {
-        let mut state = self.state.lock().unwrap();
+        #[cfg(feature = "record")]
+        let mut stream_writes = Vec::new();
 
-        let recording_ids: Vec<usize> = state
-            .recordings
-            .values()
-            .filter(|rec| request_matches(&state.matchers, &req, &rec.config.request_requirements))
-            .map(|r| r.id)
-            .collect();
+        {
+            let state = &mut *self.state.lock().unwrap();
+
+            let recording_ids: Vec<usize> = state
+                .recordings
+                .values()
+                .filter(|rec| from_upstream || !rec.config.upstream_only)
+                .filter(|rec| request_matches(&state.matchers, &req, &rec.config.request_requirements))
+                .map(|r| r.id)
+                .collect();
+
+            if recording_ids.is_empty() {
+                return Ok(());
+            }
 
-        if recording_ids.is_empty() {
-            return Ok(());
-        }
+            let res = res.try_into().map_err(|err| DataConversionError(err.to_string()))?;
 
-        let res = res.try_into().map_err(|err| DataConversionError(err.to_string()))?;
+            for id in recording_ids {
+                let rec = state.recordings.get_mut(&id).unwrap();
+                let definition = build_mock_definition(is_proxied, time_taken, &req, &res, &rec.config)?;
 
-        for id in recording_ids {
-            let rec = state.recordings.get_mut(&id).unwrap();
-            let definition = build_mock_definition(is_proxied, time_taken, &req, &res, &rec.config)?;
-            rec.mocks.push(definition);
+                #[cfg(feature = "record")]
+                if let Some(stream) = state.recording_streams.get(&id) {
+                    if let Some(document) = streamed_document(rec, &definition)? {
+                        stream_writes.push((stream.clone(), document));
+                    }
+                    continue;
+                }
+
+                recording::add_recorded_mock(rec, definition);
+            }
+        }
+
+        // Files are written after the state was unlocked and outside the async runtime's worker
+        // threads, so that slow file systems do not block other requests.
+        #[cfg(feature = "record")]
+        for (stream, document) in stream_writes {
+            tokio::task::spawn_blocking(move || {
+                stream.append(&document).map_err(|err| {
+                    RecordingWriteError(format!("cannot write to file '{}': {}", stream.path().display(), err))
+                })
+            })
+            .await
+            .map_err(|err| RecordingWriteError(err.to_string()))??;
         }
 
         Ok(())
@@ -446,6 +649,52 @@ impl Default for Manager {
     }
 }
 
+/// Serializes a recorded mock for the file of a streaming recording, so that it is not kept in
+/// memory and is not lost if the process terminates unexpectedly. Mocks that would exceed one of
+/// the caps of the recording are not recorded.
+///
+/// # Returns
+/// The YAML document to append to the file, or `None` if the mock must not be recorded.
+#[cfg(feature = "record")]
+fn streamed_document(rec: &mut ActiveRecording, definition: &MockDefinition) -> Result<Option<Vec<u8>>, Error> {
+    if rec
+        .config
+        .stream_max_interactions
+        .is_some_and(|max| rec.streamed_interactions >= max)
+    {
+        tracing::warn!(
+            "Recording with ID={} reached its maximum number of interactions. The interaction is not recorded.",
+            rec.id
+        );
+        return Ok(None);
+    }
+
+    let document = serialize_mock_defs_to_yaml(std::slice::from_ref(definition))
+        .map_err(|err| DataConversionError(err.to_string()))?
+        .to_vec();
+
+    // Documents are separated by a "---" line (see RecordingStream::append).
+    let separator_len = if rec.streamed_interactions > 0 { 4 } else { 0 };
+    let len = (separator_len + document.len()) as u64;
+
+    if rec
+        .config
+        .stream_max_bytes
+        .is_some_and(|max| rec.streamed_bytes + len > max)
+    {
+        tracing::warn!(
+            "Recording with ID={} reached its maximum file size. The interaction is not recorded.",
+            rec.id
+        );
+        return Ok(None);
+    }
+
+    rec.streamed_interactions += 1;
+    rec.streamed_bytes += len;
+
+    Ok(Some(document))
+}
+
 fn build_mock_definition(
     is_proxied: bool,
     time_taken: Duration,
@@ -468,7 +717,7 @@ fn build_mock_definition(
         }
     }
 
-    let request = RequestRequirements {
+    let mut request = RequestRequirements {
         /* Authority and scheme are assumed to always exist for proxies requests for the
         following reasons:
 
@@ -519,10 +768,17 @@ fn build_mock_definition(
         ..Default::default()
     };
 
+    recording::redact_request(&mut request, config);
+    recording::apply_match_options(&mut request, config);
+
     // ************************************************************************************
     // Response
     let mut response = response.clone();
 
+    recording::filter_response_headers(&mut response, config);
+    recording::redact_response(&mut response, config);
+    recording::normalize_response(&mut response, config);
+
     if config.record_response_delays {
         response.delay = Some(time_taken.as_millis() as u64)
     }
@@ -539,6 +795,22 @@ fn validate_request_requirements(req: &RequestRequirements) -> Result<(), Error>
     {
         return Err(BodyMethodInvalid);
     }
+
+    Ok(())
+}
+
+fn validate_response(res: &MockServerHttpResponse) -> Result<(), Error> {
+    #[cfg(not(feature = "proxy"))]
+    if res.callbacks.is_some() {
+        return Err(Error::ValidationError(
+            "callbacks are not supported because the mock server was built without the `proxy` feature".to_string(),
+        ));
+    }
+
+    for response in res.sequence.iter().flatten() {
+        validate_response(response)?;
+    }
+
     Ok(())
 }
 
@@ -1,29 +1,39 @@
 use std::{
     collections::HashMap,
+    env,
+    ffi::OsStr,
     fmt::Debug,
     io::Cursor,
-    sync::{Arc, Mutex, RwLock},
+    sync::{Arc, LazyLock, Mutex, RwLock},
 };
 
 use async_trait::async_trait;
-use rcgen::{CertificateParams, Issuer, KeyPair, SanType};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose, SanType};
 use rustls::{
+    ProtocolVersion, RootCertStore,
     crypto::ring::sign::any_supported_type,
     pki_types::{CertificateDer, PrivateKeyDer},
-    server::{ClientHello, ResolvesServerCert},
+    server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier, danger::ClientCertVerifier},
     sign::CertifiedKey,
 };
 use rustls_pki_types::pem::PemObject;
 use thiserror::Error;
+use x509_parser::extensions::GeneralName;
 
-use crate::server::tls::Error::{CaCertificateError, GenerateCertificateError};
+use crate::{
+    common::data::{ClientCertificate, TlsVersion},
+    server::tls::Error::{CaCertificateError, GenerateCertificateError, StaticCertificateError},
+};
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error("CA certificate error: {0}")]
     CaCertificateError(String),
     #[error("cannot generate certificate: {0}")]
     GenerateCertificateError(String),
+    #[error("cannot use static certificate: {0}")]
+    StaticCertificateError(String),
 }
 
 pub trait CertificateResolverFactory {
@@ -66,6 +76,88 @@ impl GeneratingCertificateResolverFactory {
     }
 }
 
+/// The CA that servers use to sign generated certificates if no CA was configured. It is
+/// generated once per process, so that all mock servers of a process share the same CA.
+///
+/// If the environment variables `HTTPMOCK_CA_CERT_FILE` and `HTTPMOCK_CA_KEY_FILE` are both set,
+/// the CA is read from these files instead. This allows clients that cannot be configured per
+/// test (e.g., clients that only use the system trust store) to trust a fixed CA.
+///
+/// Errors are kept as messages, so that every caller can report them (see [`process_ca`]).
+static PROCESS_CA: LazyLock<Result<(String, String), String>> = LazyLock::new(|| {
+    match (
+        env::var_os("HTTPMOCK_CA_CERT_FILE"),
+        env::var_os("HTTPMOCK_CA_KEY_FILE"),
+    ) {
+        (Some(cert_file), Some(key_file)) => {
+            let read = |variable: &str, path: &OsStr| {
+                std::fs::read_to_string(path).map_err(|err| {
+                    format!(
+                        "cannot read CA file '{}' from {}: {}",
+                        path.to_string_lossy(),
+                        variable,
+                        err
+                    )
+                })
+            };
+            Ok((
+                read("HTTPMOCK_CA_CERT_FILE", &cert_file)?,
+                read("HTTPMOCK_CA_KEY_FILE", &key_file)?,
+            ))
+        }
+        (None, None) => generate_ca_certificate().map_err(|err| match err {
+            CaCertificateError(msg) => msg,
+            err => err.to_string(),
+        }),
+        _ => Err("HTTPMOCK_CA_CERT_FILE and HTTPMOCK_CA_KEY_FILE must be set together".to_string()),
+    }
+});
+
+/// Generates a new self-signed CA certificate that can be used to sign host certificates.
+///
+/// # Returns
+/// The CA certificate and its private key, both in PEM format, or an error if generation fails.
+pub fn generate_ca_certificate() -> Result<(String, String), Error> {
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(DnType::OrganizationName, "httpmock");
+    params.distinguished_name.push(DnType::CommonName, "httpmock CA");
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+
+    let key_pair = KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256)
+        .map_err(|err| CaCertificateError(format!("cannot generate CA key pair: {:?}", err)))?;
+    let cert = params
+        .self_signed(&key_pair)
+        .map_err(|err| CaCertificateError(format!("cannot generate CA certificate: {:?}", err)))?;
+
+    Ok((cert.pem(), key_pair.serialize_pem()))
+}
+
+/// Returns the CA certificate and private key (both in PEM format) that mock servers of this
+/// process use when no CA was configured, or an error if the CA files configured by environment
+/// variables cannot be read.
+pub(crate) fn process_ca() -> Result<&'static (String, String), Error> {
+    PROCESS_CA.as_ref().map_err(|err| CaCertificateError(err.clone()))
+}
+
+/// Returns the CA certificate (in PEM format) that mock servers of this process use to sign
+/// generated certificates when no CA was configured. Add it to the trusted root certificates
+/// of an HTTP client to send HTTPS requests to mock servers.
+///
+/// The CA is generated once per process, unless the environment variables
+/// `HTTPMOCK_CA_CERT_FILE` and `HTTPMOCK_CA_KEY_FILE` point to a CA certificate and private key.
+///
+/// # Returns
+/// The CA certificate in PEM format, or an error if the files that the environment variables
+/// point to cannot be read.
+pub fn ca_certificate_pem() -> Result<&'static str, Error> {
+    process_ca().map(|(cert, _)| cert.as_str())
+}
+
 impl CertificateResolverFactory for GeneratingCertificateResolverFactory {
     fn build(&self, authority: Option<String>) -> Arc<dyn ResolvesServerCert> {
         Arc::new(GeneratingCertificateResolver {
@@ -346,6 +438,158 @@ impl ResolvesServerCert for GeneratingCertificateResolver {
     }
 }
 
+/// Serves user-provided certificates instead of generating them. Certificates can be configured
+/// per server name (as sent by clients using SNI) and as a default for all other server names.
+/// If there is no matching static certificate, certificate resolution is delegated to `fallback`.
+pub struct StaticCertificateResolverFactory {
+    default: Option<Arc<CertifiedKey>>,
+    by_server_name: Arc<HashMap<String, Arc<CertifiedKey>>>,
+    fallback: Arc<dyn CertificateResolverFactory + Send + Sync>,
+}
+
+impl StaticCertificateResolverFactory {
+    pub fn new(fallback: Arc<dyn CertificateResolverFactory + Send + Sync>) -> Self {
+        Self {
+            default: None,
+            by_server_name: Arc::new(HashMap::new()),
+            fallback,
+        }
+    }
+
+    /// Adds a certificate chain and its private key (both in PEM format). The certificate is used
+    /// for `server_name` (case-insensitive) or, if `server_name` is `None`, for all server names
+    /// without a certificate of their own.
+    pub fn add(&mut self, server_name: Option<&str>, cert_chain: &str, key: &str) -> Result<(), Error> {
+        let certificates = CertificateDer::pem_slice_iter(cert_chain.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| StaticCertificateError(format!("cannot parse certificate chain: {:?}", err)))?;
+        if certificates.is_empty() {
+            return Err(StaticCertificateError(
+                "the certificate chain does not contain any certificates".to_string(),
+            ));
+        }
+
+        let private_key = PrivateKeyDer::from_pem_slice(key.as_bytes())
+            .map_err(|err| StaticCertificateError(format!("cannot parse private key: {:?}", err)))?;
+        let signing_key = any_supported_type(&private_key)
+            .map_err(|err| StaticCertificateError(format!("unsupported private key: {:?}", err)))?;
+
+        let certified_key = Arc::new(CertifiedKey::new(certificates, signing_key));
+        match server_name {
+            Some(server_name) => {
+                Arc::make_mut(&mut self.by_server_name).insert(server_name.to_lowercase(), certified_key);
+            }
+            None => self.default = Some(certified_key),
+        }
+
+        Ok(())
+    }
+}
+
+impl CertificateResolverFactory for StaticCertificateResolverFactory {
+    fn build(&self, authority: Option<String>) -> Arc<dyn ResolvesServerCert> {
+        Arc::new(StaticCertificateResolver {
+            default: self.default.clone(),
+            by_server_name: self.by_server_name.clone(),
+            fallback: self.fallback.build(authority),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct StaticCertificateResolver {
+    default: Option<Arc<CertifiedKey>>,
+    by_server_name: Arc<HashMap<String, Arc<CertifiedKey>>>,
+    fallback: Arc<dyn ResolvesServerCert>,
+}
+
+impl ResolvesServerCert for StaticCertificateResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        if let Some(server_name) = client_hello.server_name()
+            && let Some(key) = self.by_server_name.get(&server_name.to_lowercase())
+        {
+            return Some(key.clone());
+        }
+
+        match &self.default {
+            Some(key) => Some(key.clone()),
+            None => self.fallback.resolve(client_hello),
+        }
+    }
+}
+
+/// Creates a verifier that accepts client certificates issued by one of the CA certificates in
+/// `ca_cert` (PEM format). If `required` is `false`, clients may also connect without presenting
+/// a certificate.
+pub(crate) fn client_cert_verifier(ca_cert: &str, required: bool) -> Result<Arc<dyn ClientCertVerifier>, Error> {
+    let mut roots = RootCertStore::empty();
+    for cert in CertificateDer::pem_slice_iter(ca_cert.as_bytes()) {
+        let cert = cert.map_err(|err| CaCertificateError(format!("cannot parse client CA certificate: {:?}", err)))?;
+        roots
+            .add(cert)
+            .map_err(|err| CaCertificateError(format!("cannot use client CA certificate: {:?}", err)))?;
+    }
+
+    let mut builder = WebPkiClientVerifier::builder_with_provider(
+        Arc::new(roots),
+        Arc::new(rustls::crypto::ring::default_provider()),
+    );
+    if !required {
+        builder = builder.allow_unauthenticated();
+    }
+
+    builder
+        .build()
+        .map_err(|err| CaCertificateError(format!("cannot verify client certificates: {}", err)))
+}
+
+/// Reads the subject and the subject alternative names from a (verified) client certificate.
+pub(crate) fn client_certificate(cert: &CertificateDer) -> Option<ClientCertificate> {
+    let (_, cert) = match x509_parser::parse_x509_certificate(cert) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            tracing::warn!("cannot parse client certificate: {:?}", err);
+            return None;
+        }
+    };
+
+    let subject_alt_names = match cert.subject_alternative_name() {
+        Ok(Some(san)) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => {
+                    Some(name.to_string())
+                }
+                GeneralName::IPAddress(bytes) => match bytes.len() {
+                    4 => Some(std::net::IpAddr::from(<[u8; 4]>::try_from(*bytes).unwrap()).to_string()),
+                    16 => Some(std::net::IpAddr::from(<[u8; 16]>::try_from(*bytes).unwrap()).to_string()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(ClientCertificate {
+        subject: cert.subject().to_string(),
+        subject_alt_names,
+    })
+}
+
+/// Converts a negotiated rustls protocol version into a [TlsVersion].
+pub(crate) fn tls_version(version: ProtocolVersion) -> Option<TlsVersion> {
+    match version {
+        ProtocolVersion::TLSv1_0 => Some(TlsVersion::Tls10),
+        ProtocolVersion::TLSv1_1 => Some(TlsVersion::Tls11),
+        ProtocolVersion::TLSv1_2 => Some(TlsVersion::Tls12),
+        ProtocolVersion::TLSv1_3 => Some(TlsVersion::Tls13),
+        _ => None,
+    }
+}
+
 pub struct TcpStreamPeekBuffer<'a> {
     stream: &'a tokio::net::TcpStream,
     buffer: Vec<u8>,
@@ -0,0 +1,224 @@
+use std::str::FromStr;
+
+use bytes::Bytes;
+use http::{
+    HeaderMap, HeaderName, HeaderValue, Response, StatusCode, Uri, header::CONTENT_LENGTH, request, uri::PathAndQuery,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    common::{
+        data::{Error as DataError, HttpMockRequest, HttpMockResponse, RequestTransform, ResponseTransform},
+        util::HttpMockBytes,
+    },
+    server::handler::Error::{self, InvalidHeader, RequestConversion, ResponseDataConversion},
+};
+
+/// Applies the request modifications of a forwarding rule to a request before it is sent to the
+/// target server. Returns the (possibly modified) request body.
+///
+/// The request URI must be in absolute form. JSON body transforms are only applied to JSON
+/// bodies. A `content-length` header is updated to match the modified body.
+pub(crate) fn transform_request(
+    transform: &RequestTransform,
+    parts: &mut request::Parts,
+    mut body: Bytes,
+) -> Result<Bytes, Error> {
+    if transform.is_empty() {
+        return Ok(body);
+    }
+
+    for name in &transform.remove_header {
+        parts.headers.remove(name.as_str());
+    }
+
+    if transform.strip_path_prefix.is_some()
+        || !transform.rewrite_path.is_empty()
+        || !transform.set_query_param.is_empty()
+    {
+        let path_and_query = transform_path_and_query(transform, parts.uri.path(), parts.uri.query());
+        let path_and_query = PathAndQuery::from_str(&path_and_query)
+            .map_err(|err| RequestConversion(format!("invalid rewritten path '{}': {}", path_and_query, err)))?;
+
+        let mut uri_parts = std::mem::take(&mut parts.uri).into_parts();
+        uri_parts.path_and_query = Some(path_and_query);
+        parts.uri = Uri::from_parts(uri_parts).map_err(|err| RequestConversion(err.to_string()))?;
+    }
+
+    if let Some(transform_json_body) = &transform.transform_json_body {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(value) => {
+                body = Bytes::from(transform_json_body(value).to_string());
+                update_content_length(&mut parts.headers, body.len());
+            }
+            Err(_) => tracing::warn!("Cannot transform a request body that is not JSON"),
+        }
+    }
+
+    Ok(body)
+}
+
+fn transform_path_and_query(transform: &RequestTransform, path: &str, query: Option<&str>) -> String {
+    let mut path = path.to_string();
+
+    if let Some(rest) = transform
+        .strip_path_prefix
+        .as_ref()
+        .and_then(|prefix| path.strip_prefix(prefix.as_str()))
+    {
+        path = if rest.starts_with('/') {
+            rest.to_string()
+        } else {
+            format!("/{}", rest)
+        };
+    }
+
+    for (regex, replacement) in &transform.rewrite_path {
+        path = regex.0.replace_all(&path, replacement.as_str()).into_owned();
+    }
+
+    // The query is only re-encoded if it needs to be modified.
+    let query = if transform.set_query_param.is_empty() {
+        query.map(str::to_string)
+    } else {
+        let mut params: Vec<(String, String)> = form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+        for (name, value) in &transform.set_query_param {
+            params.retain(|(existing, _)| existing != name);
+            params.push((name.clone(), value.clone()));
+        }
+        Some(
+            form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&params)
+                .finish(),
+        )
+    };
+
+    match query {
+        Some(query) if !query.is_empty() => format!("{}?{}", path, query),
+        _ => path,
+    }
+}
+
+/// Applies the response modifications of a proxy or forwarding rule to a response that was
+/// received from the upstream server.
+///
+/// Body modifications are only applied to UTF-8 bodies and JSON merge patches only to JSON
+/// bodies. A `content-length` header is updated to match the final body.
+pub(crate) fn transform_response(
+    transform: &ResponseTransform,
+    request: &HttpMockRequest,
+    response: Response<Bytes>,
+) -> Result<Response<Bytes>, Error> {
+    if transform.is_empty() {
+        return Ok(response);
+    }
+
+    let (mut parts, mut body) = response.into_parts();
+
+    for name in &transform.remove_header {
+        parts.headers.remove(name.as_str());
+    }
+
+    for (name, value) in &transform.add_header {
+        let name = HeaderName::from_str(name).map_err(|err| InvalidHeader(format!("invalid header key: {}", err)))?;
+        let value =
+            HeaderValue::from_str(value).map_err(|err| InvalidHeader(format!("invalid header value: {}", err)))?;
+        parts.headers.append(name, value);
+    }
+
+    if let Some(status) = transform.status {
+        parts.status = StatusCode::from_u16(status).map_err(|_| {
+            ResponseDataConversion(DataError::ResponseConversion(format!("invalid status: {}", status)))
+        })?;
+    }
+
+    if !transform.replace_body_regex.is_empty() || !transform.json_merge_patch.is_empty() {
+        match std::str::from_utf8(&body) {
+            Ok(text) => body = Bytes::from(transform_body(transform, text.to_string())),
+            Err(_) => tracing::warn!("Cannot modify a response body that is not valid UTF-8"),
+        }
+    }
+
+    let mut response = Response::from_parts(parts, body);
+
+    if let Some(map_response) = &transform.map_response {
+        let mapped = map_response(request, to_mock_response(&response));
+        response = (&mapped).try_into().map_err(ResponseDataConversion)?;
+    }
+
+    let length = response.body().len();
+    update_content_length(response.headers_mut(), length);
+
+    Ok(response)
+}
+
+fn transform_body(transform: &ResponseTransform, mut text: String) -> String {
+    for (regex, replacement) in &transform.replace_body_regex {
+        text = regex.0.replace_all(&text, replacement.as_str()).into_owned();
+    }
+
+    if transform.json_merge_patch.is_empty() {
+        return text;
+    }
+
+    match serde_json::from_str::<Value>(&text) {
+        Ok(mut value) => {
+            for patch in &transform.json_merge_patch {
+                merge_patch(&mut value, patch);
+            }
+            value.to_string()
+        }
+        Err(_) => {
+            tracing::warn!("Cannot apply a JSON merge patch to a response body that is not JSON");
+            text
+        }
+    }
+}
+
+/// Applies a JSON merge patch (RFC 7396) to `target`.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    let target = target.as_object_mut().unwrap();
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+fn to_mock_response(response: &Response<Bytes>) -> HttpMockResponse {
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+
+    HttpMockResponse {
+        status: Some(response.status().as_u16()),
+        headers: Some(headers),
+        body: Some(HttpMockBytes::from(response.body().clone())),
+    }
+}
+
+pub(crate) fn update_content_length(headers: &mut HeaderMap, length: usize) {
+    if headers.contains_key(CONTENT_LENGTH) {
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(length));
+    }
+}
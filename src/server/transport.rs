@@ -4,10 +4,20 @@ use std::{
     net::SocketAddr,
     sync::Arc,
 };
+#[cfg(feature = "proxy")]
+use std::{
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, ready},
+};
 
 use futures_util::{FutureExt, future::BoxFuture};
+#[cfg(feature = "proxy")]
+use http::uri::Authority;
 use http::{Request, StatusCode};
 use http_body_util::{BodyExt, Empty, Full, combinators::BoxBody};
+#[cfg(feature = "proxy")]
+use hyper::body::{Body, Frame, SizeHint};
 use hyper::{
     Method, Response,
     body::{Bytes, Incoming},
@@ -16,8 +26,10 @@ use hyper::{
 };
 use hyper_util::{rt::tokio::TokioIo, server::conn::auto::Builder as ServerBuilder};
 #[cfg(feature = "https")]
-use rustls::ServerConfig;
+use rustls::{ServerConfig, server::danger::ClientCertVerifier};
 use thiserror::Error;
+#[cfg(feature = "proxy")]
+use tokio::io::copy_bidirectional;
 use tokio::{
     net::{TcpListener, TcpStream},
     sync::oneshot::Sender,
@@ -66,6 +78,9 @@ pub enum Error {
 #[cfg(feature = "https")]
 pub struct MockServerHttpsConfig {
     pub cert_resolver_factory: Arc<dyn CertificateResolverFactory + Send + Sync>,
+    pub client_cert_verifier: Option<Arc<dyn ClientCertVerifier>>,
+    /// The CA certificate (in PEM format) that signs generated certificates, if any.
+    pub ca_certificate: Option<String>,
 }
 
 pub struct MockServerConfig {
@@ -163,6 +178,18 @@ impl HttpMockServer {
         tracing::trace!("New HTTP request received: {}", req.uri());
 
         if req.method() == Method::CONNECT {
+            #[cfg(feature = "proxy")]
+            if let Some(authority) = req.uri().authority().cloned()
+                && let Some(rule) = self.handler.find_passthrough_tunnel_rule(authority.host())
+            {
+                // Connect before confirming the tunnel, so that the client learns about
+                // unreachable hosts.
+                return match self.handler.connect_passthrough_tunnel(&rule, &authority).await {
+                    Ok(target) => passthrough_tunnel(req, authority, target),
+                    Err(err) => error_response(StatusCode::BAD_GATEWAY, RouterError(err)),
+                };
+            }
+
             #[cfg(feature = "https")]
             {
                 // CONNECT handling (HTTP proxy tunneling):
@@ -212,6 +239,37 @@ impl HttpMockServer {
             return Ok(Response::builder().status(StatusCode::OK).body(empty())?);
         }
 
+        #[cfg(feature = "http2")]
+        if let Some(metadata) = req.extensions().get::<RequestMetadata>().cloned()
+            && metadata.scheme == "http"
+            && let Some(upgrade) = h2c::upgrade_request(&req)
+        {
+            // h2c upgrade: Like for CONNECT requests above, the upgrade only happens after the
+            // 101 response has been returned, so it must be handled in a background task. The
+            // request itself is answered on stream 1 of the upgraded HTTP/2 connection.
+            let on_upgrade = hyper::upgrade::on(req);
+            let server = self.clone();
+
+            spawn(async move {
+                let result = match on_upgrade.await {
+                    Ok(upgraded) => match h2c::replay_upgrade_request(TokioIo::new(upgraded), upgrade).await {
+                        Ok(stream) => serve_connection(server, stream, metadata).await,
+                        Err(err) => Err(Error::IOError(err)),
+                    },
+                    Err(err) => Err(Error::ServerConnectionError(Box::new(err))),
+                };
+                if let Err(err) = result {
+                    tracing::warn!("h2c upgraded handling failed: {:?}", err);
+                }
+            });
+
+            return Ok(Response::builder()
+                .status(StatusCode::SWITCHING_PROTOCOLS)
+                .header(http::header::CONNECTION, "Upgrade")
+                .header(http::header::UPGRADE, "h2c")
+                .body(empty())?);
+        }
+
         let mut req = match buffer_request(req).await {
             Ok(req) => req,
             Err(err) => {
@@ -245,8 +303,18 @@ impl HttpMockServer {
             .print_access_log
             .then_some((req.method().clone(), req.uri().clone()));
 
+        #[cfg(feature = "proxy")]
+        let pending_callbacks = req.extensions().get::<PendingCallbacks>().cloned();
+
         let resp = match self.handler.handle(req).await {
-            Ok(response) => to_service_response(response),
+            Ok(response) => to_service_response(
+                response,
+                #[cfg(feature = "proxy")]
+                pending_callbacks,
+            ),
+            // Returning an error makes hyper close the connection without sending a response.
+            #[cfg(feature = "proxy")]
+            Err(err @ handler::Error::ConnectionClosedByFault) => return Err(RouterError(err)),
             Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, RouterError(err)),
         };
 
@@ -291,7 +359,7 @@ impl HttpMockServer {
         }
 
         tracing::trace!("TCP connection is not TLS encrypted");
-        serve_connection(self.clone(), tcp_stream, "http").await
+        serve_connection(self.clone(), tcp_stream, RequestMetadata::new("http")).await
     }
 }
 
@@ -311,11 +379,14 @@ where
     // (e.g. reqwest) can enable rustls' `aws-lc-rs` feature alongside our `ring`
     // feature, which makes auto-detection ambiguous and panics.
     // See https://github.com/rustls/rustls/issues/1938
-    let mut server_config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+    let server_config_builder = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
         .with_safe_default_protocol_versions()
-        .map_err(|e| Error::TlsError(format!("cannot build TLS server config: {:?}", e)))?
-        .with_no_client_auth()
-        .with_cert_resolver(cert_resolver);
+        .map_err(|e| Error::TlsError(format!("cannot build TLS server config: {:?}", e)))?;
+    let server_config_builder = match &server.config.https.client_cert_verifier {
+        Some(verifier) => server_config_builder.with_client_cert_verifier(verifier.clone()),
+        None => server_config_builder.with_no_client_auth(),
+    };
+    let mut server_config = server_config_builder.with_cert_resolver(cert_resolver);
 
     server_config.alpn_protocols = vec![
         #[cfg(feature = "http2")]
@@ -330,7 +401,24 @@ where
         .await
         .map_err(|e| Error::TlsError(format!("TLS accept failed: {:?}", e)))?;
 
-    serve_connection(server, tls_stream, "https").await
+    let (_, connection) = tls_stream.get_ref();
+    let tls = TlsInfo {
+        sni: connection.server_name().map(str::to_string),
+        version: connection.protocol_version().and_then(tls_version),
+        alpn: connection
+            .alpn_protocol()
+            .map(|protocol| String::from_utf8_lossy(protocol).to_string()),
+        cipher_suite: connection
+            .negotiated_cipher_suite()
+            .and_then(|suite| suite.suite().as_str())
+            .map(str::to_string),
+        client_certificate: connection
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(client_certificate),
+    };
+
+    serve_connection(server, tls_stream, RequestMetadata::new("https").with_tls(tls)).await
 }
 
 // `serve_connection` cannot be a plain `async fn` (nor return an implicit/explicit
@@ -344,34 +432,99 @@ where
 fn serve_connection<S>(
     server: Arc<HttpMockServer>,
     stream: S,
-    scheme: &'static str,
+    metadata: RequestMetadata,
 ) -> BoxFuture<'static, Result<(), Error>>
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
     Box::pin(async move {
-        let mut server_builder = ServerBuilder::new(TokioExecutor::new());
-
-        server_builder.http1().preserve_header_case(true);
-        server_builder.http2();
-        //.enable_connect_protocol();
-
-        server_builder
-            .serve_connection_with_upgrades(
-                TokioIo::new(stream),
-                service_fn(|mut req| {
-                    // We pass authority None here since we don't know it for non-CONNECT requests
-                    // yet. We only know it when the full request has been buffered in `service()`.
-                    // Here, we only the scheme is known from the connection type.
-                    req.extensions_mut().insert(RequestMetadata::new(scheme));
-                    server.clone().service(req)
-                }),
-            )
-            .await
-            .map_err(ServerConnectionError)
+        // Callbacks of mocks are held back until their responses have been written to the
+        // connection. This requires watching the connection, which is only done if a mock sends
+        // callbacks. On connections that were opened before, callbacks are sent once hyper is
+        // done with the response body.
+        #[cfg(feature = "proxy")]
+        if server.handler.has_callbacks() {
+            let pending_callbacks = PendingCallbacks::default();
+            let stream = CallbackConnection {
+                inner: stream,
+                pending: pending_callbacks.clone(),
+            };
+            return serve_http(server, stream, metadata, Some(pending_callbacks)).await;
+        }
+
+        serve_http(
+            server,
+            stream,
+            metadata,
+            #[cfg(feature = "proxy")]
+            None,
+        )
+        .await
     })
 }
 
+async fn serve_http<S>(
+    server: Arc<HttpMockServer>,
+    stream: S,
+    metadata: RequestMetadata,
+    #[cfg(feature = "proxy")] pending_callbacks: Option<PendingCallbacks>,
+) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut server_builder = ServerBuilder::new(TokioExecutor::new());
+
+    server_builder.http1().preserve_header_case(true);
+    server_builder.http2();
+    //.enable_connect_protocol();
+
+    server_builder
+        .serve_connection_with_upgrades(
+            TokioIo::new(stream),
+            service_fn(|mut req| {
+                // We pass authority None here since we don't know it for non-CONNECT requests
+                // yet. We only know it when the full request has been buffered in `service()`.
+                // Here, only the scheme (and TLS details) are known from the connection.
+                req.extensions_mut().insert(metadata.clone());
+                #[cfg(feature = "proxy")]
+                if let Some(pending_callbacks) = &pending_callbacks {
+                    req.extensions_mut().insert(pending_callbacks.clone());
+                }
+                server.clone().service(req)
+            }),
+        )
+        .await
+        .map_err(ServerConnectionError)
+}
+
+/// Splices the bytes of a `CONNECT` tunnel between the client and the target host (`target`),
+/// without intercepting the (TLS) traffic inside the tunnel.
+#[cfg(feature = "proxy")]
+fn passthrough_tunnel(
+    req: Request<Incoming>,
+    authority: Authority,
+    mut target: TcpStream,
+) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Error> {
+    tracing::debug!("Passing through CONNECT tunnel to {}", authority);
+
+    let on_upgrade = hyper::upgrade::on(req);
+    spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => {
+                if let Err(err) = copy_bidirectional(&mut TokioIo::new(upgraded), &mut target).await {
+                    tracing::debug!("CONNECT tunnel to {} closed with an error: {:?}", authority, err);
+                }
+            }
+            Err(err) => {
+                let e = Error::ServerConnectionError(Box::new(err));
+                tracing::warn!("CONNECT upgraded handling failed: {:?}", e);
+            }
+        }
+    });
+
+    Ok(Response::builder().status(StatusCode::OK).body(empty())?)
+}
+
 async fn buffer_request(req: Request<Incoming>) -> Result<Request<Bytes>, hyper::Error> {
     let (parts, body) = req.into_parts();
     let body = body.collect().await?.to_bytes();
@@ -391,8 +544,25 @@ fn error_response(code: StatusCode, err: Error) -> Result<Response<BoxBody<Bytes
     Ok(Response::builder().status(code).body(full(err.to_string()))?)
 }
 
-fn to_service_response(response: Response<Bytes>) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Error> {
-    let (parts, body) = response.into_parts();
+fn to_service_response(
+    response: Response<Bytes>,
+    #[cfg(feature = "proxy")] pending_callbacks: Option<PendingCallbacks>,
+) -> Result<Response<BoxBody<Bytes, hyper::Error>>, Error> {
+    #[cfg_attr(not(feature = "proxy"), allow(unused_mut))]
+    let (mut parts, body) = response.into_parts();
+
+    // Hyper drops the response head before it writes the body, so the callback trigger must be
+    // moved into the body to hold back the callbacks until the body has been written.
+    #[cfg(feature = "proxy")]
+    if let Some(trigger) = parts.extensions.remove::<server::callback::CallbackTrigger>() {
+        let body = CallbackBody {
+            data: Some(body),
+            trigger: Some(trigger),
+            pending: pending_callbacks,
+        };
+        return Ok(Response::from_parts(parts, body.boxed()));
+    }
+
     Ok(Response::from_parts(parts, full(body)))
 }
 
@@ -401,9 +571,14 @@ use hyper_util::rt::TokioExecutor;
 use tls_detect::is_encrypted;
 use tokio::io::{AsyncRead, AsyncWrite};
 
-#[cfg(feature = "https")]
-use crate::server::tls::{CertificateResolverFactory, TcpStreamPeekBuffer};
+#[cfg(feature = "http2")]
+use crate::server::h2c;
 use crate::server::{Error::ServerConnectionError, RequestMetadata};
+#[cfg(feature = "https")]
+use crate::{
+    common::data::TlsInfo,
+    server::tls::{CertificateResolverFactory, TcpStreamPeekBuffer, client_certificate, tls_version},
+};
 
 fn to_absolute_form_uri(req: &mut Request<Bytes>) -> Result<(), Error> {
     let default_scheme = req
@@ -435,3 +610,116 @@ fn to_absolute_form_uri(req: &mut Request<Bytes>) -> Result<(), Error> {
     *req.uri_mut() = new_uri;
     Ok(())
 }
+
+/// The callback triggers of the responses of a connection that have been passed to hyper, but
+/// might not have been written to the connection yet.
+#[cfg(feature = "proxy")]
+#[derive(Clone, Default)]
+struct PendingCallbacks(Arc<Mutex<Vec<server::callback::CallbackTrigger>>>);
+
+#[cfg(feature = "proxy")]
+impl PendingCallbacks {
+    fn push(&self, trigger: server::callback::CallbackTrigger) {
+        self.0.lock().unwrap().push(trigger);
+    }
+
+    /// Drops all pending triggers, which sends their callbacks.
+    fn release(&self) {
+        let triggers = std::mem::take(&mut *self.0.lock().unwrap());
+        drop(triggers);
+    }
+}
+
+/// A response body that hands the callback trigger of a mock over to the connection once hyper
+/// is done with the body, i.e., when the end of the body was reached or the body was dropped
+/// (e.g., for responses to `HEAD` requests). If the connection does not hold back callbacks,
+/// the trigger is released right away instead.
+#[cfg(feature = "proxy")]
+struct CallbackBody {
+    data: Option<Bytes>,
+    trigger: Option<server::callback::CallbackTrigger>,
+    pending: Option<PendingCallbacks>,
+}
+
+#[cfg(feature = "proxy")]
+impl Body for CallbackBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        match self.data.take().filter(|data| !data.is_empty()) {
+            Some(data) => Poll::Ready(Some(Ok(Frame::data(data)))),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.as_ref().is_none_or(Bytes::is_empty)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.data.as_ref().map_or(0, Bytes::len) as u64)
+    }
+}
+
+#[cfg(feature = "proxy")]
+impl Drop for CallbackBody {
+    fn drop(&mut self) {
+        if let Some(trigger) = self.trigger.take()
+            && let Some(pending) = &self.pending
+        {
+            pending.push(trigger);
+        }
+    }
+}
+
+/// A client connection that sends the pending callbacks of its responses whenever it was
+/// flushed. Hyper only flushes the connection after everything it buffered has been written,
+/// which includes the complete responses of all bodies that hyper is done with.
+#[cfg(feature = "proxy")]
+struct CallbackConnection<S> {
+    inner: S,
+    pending: PendingCallbacks,
+}
+
+#[cfg(feature = "proxy")]
+impl<S: AsyncRead + Unpin> AsyncRead for CallbackConnection<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+#[cfg(feature = "proxy")]
+impl<S: AsyncWrite + Unpin> AsyncWrite for CallbackConnection<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let result = ready!(Pin::new(&mut self.inner).poll_flush(cx));
+        if result.is_ok() {
+            self.pending.release();
+        }
+        Poll::Ready(result)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
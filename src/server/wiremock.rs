@@ -0,0 +1,474 @@
+use std::{
+    fs::{read, read_dir, read_to_string},
+    path::{Component, Path, PathBuf},
+    sync::LazyLock,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use regex::Regex;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::common::{
+    data::{HttpMockRegex, MockDefinition, MockServerHttpResponse, RequestRequirements},
+    json_path::JsonPath,
+    util::HttpMockBytes,
+};
+
+/// The priority WireMock assigns to stubs that do not define one.
+const DEFAULT_PRIORITY: i64 = 5;
+
+/// The directory WireMock resolves `bodyFileName` against.
+const FILES_DIR: &str = "__files";
+
+/// The directory WireMock reads stub mappings from.
+pub(crate) const MAPPINGS_DIR: &str = "mappings";
+
+/// Matches variables in URL path templates, such as `{id}` in `/users/{id}`.
+static PATH_TEMPLATE_VARIABLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{[^}/]+\}").unwrap());
+
+/// Top-level mapping keys that only carry metadata and do not affect matching.
+const METADATA_KEYS: [&str; 6] = ["id", "uuid", "name", "persistent", "metadata", "insertionIndex"];
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("cannot read WireMock mappings from '{}': {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("cannot load WireMock mapping file '{}': {message}", path.display())]
+    Mapping { path: PathBuf, message: String },
+}
+
+/// Reads WireMock stub mappings and converts them into mock definitions.
+///
+/// `path` may be a single mapping file or a directory. Mapping files are read from the
+/// directory and from its `mappings` subdirectory (WireMock's directory layout). Each file may contain a single stub mapping or an
+/// object with a `mappings` array. Stubs are ordered by their `priority`.
+///
+/// Response bodies referenced by `bodyFileName` are read from the `__files` directory next to
+/// the mapping file or next to its parent directory, as laid out by WireMock.
+pub(crate) fn read_mappings(path: &Path) -> Result<Vec<MockDefinition>, Error> {
+    let files = if path.is_dir() {
+        mapping_files(path)?
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut stubs = Vec::new();
+    for file in files {
+        stubs.extend(read_mapping_file(&file)?);
+    }
+
+    // WireMock serves the stub with the lowest priority value. The mock server serves the
+    // first matching mock, so stubs are added in the order of their priority.
+    stubs.sort_by_key(|(priority, _)| *priority);
+
+    Ok(stubs.into_iter().map(|(_, definition)| definition).collect())
+}
+
+/// Returns all JSON files in `dir` and in its `mappings` subdirectory, if there is one.
+fn mapping_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+
+    for dir in [dir.to_path_buf(), dir.join(MAPPINGS_DIR)] {
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let read_error = |source| Error::Read {
+            path: dir.clone(),
+            source,
+        };
+
+        let mut dir_files = Vec::new();
+        for entry in read_dir(&dir).map_err(read_error)? {
+            let path = entry.map_err(read_error)?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                dir_files.push(path);
+            }
+        }
+
+        // Keep the order deterministic for stubs with equal priority.
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+
+    Ok(files)
+}
+
+fn read_mapping_file(path: &Path) -> Result<Vec<(i64, MockDefinition)>, Error> {
+    let mapping_error = |message: String| Error::Mapping {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    tracing::info!("Loading WireMock mapping file from '{}'", path.to_string_lossy());
+
+    let content = read_to_string(path).map_err(|source| Error::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let document: Value = serde_json::from_str(&content).map_err(|err| mapping_error(err.to_string()))?;
+
+    let stubs: Vec<&Value> = match document.get("mappings") {
+        Some(Value::Array(mappings)) => mappings.iter().collect(),
+        Some(_) => return Err(mapping_error("'mappings' must be an array".to_string())),
+        None => vec![&document],
+    };
+
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    stubs
+        .into_iter()
+        .map(|stub| convert_stub(stub, base_dir))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(mapping_error)
+}
+
+fn convert_stub(stub: &Value, base_dir: &Path) -> Result<(i64, MockDefinition), String> {
+    let stub = as_object(stub, "stub mapping")?;
+
+    for key in stub.keys() {
+        match key.as_str() {
+            "request" | "response" | "priority" => {}
+            key if METADATA_KEYS.contains(&key) => {}
+            "scenarioName" | "requiredScenarioState" | "newScenarioState" => {
+                return Err("scenarios are not supported".to_string());
+            }
+            key => return Err(format!("unsupported stub mapping property '{}'", key)),
+        }
+    }
+
+    let priority = stub.get("priority").and_then(Value::as_i64).unwrap_or(DEFAULT_PRIORITY);
+
+    let request = match stub.get("request") {
+        Some(request) => convert_request(as_object(request, "request")?)?,
+        None => RequestRequirements::new(),
+    };
+
+    let response = match stub.get("response") {
+        Some(response) => convert_response(as_object(response, "response")?, base_dir)?,
+        None => MockServerHttpResponse {
+            status: Some(200),
+            ..MockServerHttpResponse::new()
+        },
+    };
+
+    Ok((priority, MockDefinition { request, response }))
+}
+
+fn convert_request(request: &Map<String, Value>) -> Result<RequestRequirements, String> {
+    let mut requirements = RequestRequirements::new();
+
+    for (key, value) in request {
+        match key.as_str() {
+            "method" => {
+                let method = as_str(value, "method")?.to_uppercase();
+                if method != "ANY" {
+                    requirements.method = Some(method);
+                }
+            }
+            "url" => {
+                // `url` matches the path and the query string exactly, so requests must not have
+                // any query parameters besides the listed ones.
+                let url = as_str(value, "url")?;
+                let (path, query) = url.split_once('?').unwrap_or((url, ""));
+                requirements.path = Some(path.to_string());
+                let query_params: Vec<(String, String)> =
+                    form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+                push(
+                    &mut requirements.query_param_count,
+                    (full_match(".*", false)?, full_match(".*", false)?, query_params.len()),
+                );
+                if !query_params.is_empty() {
+                    requirements.query_param = Some(query_params);
+                }
+            }
+            "urlPath" => requirements.path = Some(as_str(value, "urlPath")?.to_string()),
+            "urlPattern" | "urlPathPattern" => {
+                let pattern = as_str(value, key)?;
+                if key == "urlPattern" && pattern.contains("\\?") {
+                    return Err("'urlPattern' values that match the query string are not supported".to_string());
+                }
+                push(&mut requirements.path_matches, full_match(pattern, false)?);
+            }
+            "urlPathTemplate" => {
+                let template = as_str(value, key)?;
+                let pattern = PATH_TEMPLATE_VARIABLE
+                    .split(template)
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join("[^/]+");
+                push(&mut requirements.path_matches, full_match(&pattern, false)?);
+            }
+            "headers" => {
+                for (name, pattern) in as_object(value, key)? {
+                    convert_header_pattern(&mut requirements, name, pattern)?;
+                }
+            }
+            "queryParameters" => {
+                for (name, pattern) in as_object(value, key)? {
+                    convert_query_param_pattern(&mut requirements, name, pattern)?;
+                }
+            }
+            "bodyPatterns" => {
+                for pattern in value.as_array().ok_or("'bodyPatterns' must be an array")? {
+                    convert_body_pattern(&mut requirements, as_object(pattern, "body pattern")?)?;
+                }
+            }
+            "basicAuthCredentials" => {
+                let credentials = as_object(value, key)?;
+                let username = credentials.get("username").and_then(Value::as_str).unwrap_or_default();
+                let password = credentials.get("password").and_then(Value::as_str).unwrap_or_default();
+                push(
+                    &mut requirements.header,
+                    (
+                        "authorization".to_string(),
+                        format!("Basic {}", BASE64.encode(format!("{}:{}", username, password))),
+                    ),
+                );
+            }
+            key => return Err(format!("unsupported request property '{}'", key)),
+        }
+    }
+
+    Ok(requirements)
+}
+
+/// The parts of a WireMock string matcher (e.g., `{ "equalTo": "value" }`) that are supported.
+enum StringPattern {
+    EqualTo(String),
+    EqualToIgnoreCase(String),
+    Contains(String),
+    Matches(String),
+    Absent(bool),
+}
+
+fn string_pattern(name: &str, pattern: &Value) -> Result<StringPattern, String> {
+    let pattern = as_object(pattern, name)?;
+    let case_insensitive = pattern.get("caseInsensitive").and_then(Value::as_bool).unwrap_or(false);
+
+    for (operator, value) in pattern {
+        let result = match operator.as_str() {
+            "caseInsensitive" => continue,
+            "equalTo" if case_insensitive => StringPattern::EqualToIgnoreCase(as_str(value, operator)?.to_string()),
+            "equalTo" => StringPattern::EqualTo(as_str(value, operator)?.to_string()),
+            "contains" => StringPattern::Contains(as_str(value, operator)?.to_string()),
+            "matches" => StringPattern::Matches(as_str(value, operator)?.to_string()),
+            "absent" => StringPattern::Absent(value.as_bool().ok_or("'absent' must be a boolean")?),
+            operator => {
+                return Err(format!("unsupported matcher '{}' for '{}'", operator, name));
+            }
+        };
+        return Ok(result);
+    }
+
+    Err(format!("missing matcher for '{}'", name))
+}
+
+fn convert_header_pattern(requirements: &mut RequestRequirements, name: &str, pattern: &Value) -> Result<(), String> {
+    let name_pattern = full_match(&regex::escape(name), true)?;
+
+    match string_pattern(name, pattern)? {
+        StringPattern::EqualTo(value) => push(&mut requirements.header, (name.to_string(), value)),
+        StringPattern::EqualToIgnoreCase(value) => push(
+            &mut requirements.header_matches,
+            (name_pattern, full_match(&regex::escape(&value), true)?),
+        ),
+        StringPattern::Contains(value) => push(&mut requirements.header_includes, (name.to_string(), value)),
+        StringPattern::Matches(value) => push(
+            &mut requirements.header_matches,
+            (name_pattern, full_match(&value, false)?),
+        ),
+        StringPattern::Absent(true) => push(&mut requirements.header_missing, name.to_string()),
+        StringPattern::Absent(false) => push(&mut requirements.header_exists, name.to_string()),
+    }
+
+    Ok(())
+}
+
+fn convert_query_param_pattern(
+    requirements: &mut RequestRequirements,
+    name: &str,
+    pattern: &Value,
+) -> Result<(), String> {
+    let name_pattern = full_match(&regex::escape(name), false)?;
+
+    match string_pattern(name, pattern)? {
+        StringPattern::EqualTo(value) => push(&mut requirements.query_param, (name.to_string(), value)),
+        StringPattern::EqualToIgnoreCase(value) => push(
+            &mut requirements.query_param_matches,
+            (name_pattern, full_match(&regex::escape(&value), true)?),
+        ),
+        StringPattern::Contains(value) => push(&mut requirements.query_param_includes, (name.to_string(), value)),
+        StringPattern::Matches(value) => push(
+            &mut requirements.query_param_matches,
+            (name_pattern, full_match(&value, false)?),
+        ),
+        StringPattern::Absent(true) => push(&mut requirements.query_param_missing, name.to_string()),
+        StringPattern::Absent(false) => push(&mut requirements.query_param_exists, name.to_string()),
+    }
+
+    Ok(())
+}
+
+fn convert_body_pattern(requirements: &mut RequestRequirements, pattern: &Map<String, Value>) -> Result<(), String> {
+    let flag = |name: &str| pattern.get(name).and_then(Value::as_bool).unwrap_or(false);
+
+    if let Some(expected) = pattern.get("equalToJson") {
+        if flag("ignoreArrayOrder") {
+            return Err("'ignoreArrayOrder' is not supported for 'equalToJson'".to_string());
+        }
+
+        let expected = match expected {
+            Value::String(json) => {
+                serde_json::from_str(json).map_err(|err| format!("invalid 'equalToJson': {}", err))?
+            }
+            other => other.clone(),
+        };
+
+        if flag("ignoreExtraElements") {
+            push(&mut requirements.json_body_includes, expected);
+        } else {
+            requirements.json_body = Some(expected);
+        }
+        return Ok(());
+    }
+
+    if let Some(expression) = pattern.get("matchesJsonPath") {
+        let expression = match expression {
+            Value::String(expression) => expression,
+            Value::Object(expression) if expression.len() == 1 => expression
+                .get("expression")
+                .and_then(Value::as_str)
+                .ok_or("'matchesJsonPath' requires an 'expression'")?,
+            _ => return Err("'matchesJsonPath' with nested matchers is not supported".to_string()),
+        };
+
+        JsonPath::parse(expression)?;
+        push(&mut requirements.json_body_path, expression.to_string());
+        return Ok(());
+    }
+
+    match string_pattern("body", &Value::Object(pattern.clone()))? {
+        StringPattern::EqualTo(value) => requirements.body = Some(HttpMockBytes::from(value)),
+        StringPattern::EqualToIgnoreCase(value) => push(
+            &mut requirements.body_matches,
+            full_match(&regex::escape(&value), true)?,
+        ),
+        StringPattern::Contains(value) => push(&mut requirements.body_includes, HttpMockBytes::from(value)),
+        StringPattern::Matches(value) => push(&mut requirements.body_matches, full_match(&value, false)?),
+        StringPattern::Absent(_) => return Err("'absent' is not supported for body patterns".to_string()),
+    }
+
+    Ok(())
+}
+
+fn convert_response(response: &Map<String, Value>, base_dir: &Path) -> Result<MockServerHttpResponse, String> {
+    let mut result = MockServerHttpResponse::new();
+    result.status = Some(200);
+
+    for (key, value) in response {
+        match key.as_str() {
+            "status" => {
+                let status = value.as_u64().ok_or("'status' must be a number")?;
+                result.status = Some(u16::try_from(status).map_err(|_| format!("invalid status {}", status))?);
+            }
+            "statusMessage" => {}
+            "headers" => {
+                for (name, value) in as_object(value, key)? {
+                    let values: Vec<&Value> = match value {
+                        Value::Array(values) => values.iter().collect(),
+                        value => vec![value],
+                    };
+                    for value in values {
+                        let value = match value {
+                            Value::String(value) => value.clone(),
+                            other => other.to_string(),
+                        };
+                        push(&mut result.headers, (name.clone(), value));
+                    }
+                }
+            }
+            "body" => result.body = Some(HttpMockBytes::from(as_str(value, key)?.to_string())),
+            "jsonBody" => result.body = Some(HttpMockBytes::from(value.to_string())),
+            "base64Body" => {
+                let body = BASE64
+                    .decode(as_str(value, key)?)
+                    .map_err(|err| format!("invalid 'base64Body': {}", err))?;
+                result.body = Some(HttpMockBytes::from(body));
+            }
+            "bodyFileName" => {
+                let body = read_body_file(base_dir, as_str(value, key)?)?;
+                result.body = Some(HttpMockBytes::from(Bytes::from(body)));
+            }
+            "fixedDelayMilliseconds" => {
+                result.delay = Some(value.as_u64().ok_or("'fixedDelayMilliseconds' must be a number")?);
+            }
+            "transformers" | "transformerParameters" => {
+                return Err("response transformers (e.g., response templating) are not supported".to_string());
+            }
+            key => return Err(format!("unsupported response property '{}'", key)),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Reads a body file from the `__files` directory next to the mapping file or next to its
+/// parent directory (WireMock's default layout is `<root>/mappings` and `<root>/__files`).
+fn read_body_file(base_dir: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let candidates = [
+        Some(base_dir.join(FILES_DIR)),
+        base_dir.parent().map(|parent| parent.join(FILES_DIR)),
+    ];
+
+    // The name must not point outside of the `__files` directory (e.g., with `..`).
+    let mut components = Path::new(name)
+        .components()
+        .filter(|component| *component != Component::CurDir);
+    let has_file_name = components.clone().next().is_some();
+    if !has_file_name || !components.all(|component| matches!(component, Component::Normal(_))) {
+        return Err(format!(
+            "body file '{}' is not inside the '{}' directory",
+            name, FILES_DIR
+        ));
+    }
+
+    for dir in candidates.into_iter().flatten() {
+        let path = dir.join(name);
+        if path.is_file() {
+            return read(&path).map_err(|err| format!("cannot read body file '{}': {}", path.display(), err));
+        }
+    }
+
+    Err(format!(
+        "cannot find body file '{}' in a '{}' directory next to the mapping file",
+        name, FILES_DIR
+    ))
+}
+
+/// WireMock regular expressions must match the whole value.
+fn full_match(pattern: &str, case_insensitive: bool) -> Result<HttpMockRegex, String> {
+    let flags = if case_insensitive { "(?i)" } else { "" };
+    Regex::new(&format!("{}^(?:{})$", flags, pattern))
+        .map(HttpMockRegex)
+        .map_err(|err| format!("invalid regular expression '{}': {}", pattern, err))
+}
+
+fn push<T>(target: &mut Option<Vec<T>>, value: T) {
+    target.get_or_insert_with(Vec::new).push(value);
+}
+
+fn as_object<'a>(value: &'a Value, name: &str) -> Result<&'a Map<String, Value>, String> {
+    value.as_object().ok_or_else(|| format!("'{}' must be an object", name))
+}
+
+fn as_str<'a>(value: &'a Value, name: &str) -> Result<&'a str, String> {
+    value.as_str().ok_or_else(|| format!("'{}' must be a string", name))
+}
@@ -0,0 +1,206 @@
+use std::{thread, time::Duration};
+
+use httpmock::{Mock, prelude::*};
+
+/// Callbacks are sent in the background, so give them some time to arrive.
+fn wait_for_calls(mock: &Mock, count: usize) {
+    for _ in 0..100 {
+        if mock.calls() >= count {
+            return;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn callback_test() {
+    // Arrange
+    let webhook_server = MockServer::start();
+    let webhook = webhook_server.mock(|when, then| {
+        when.method(PUT)
+            .path("/orders/42/status")
+            .header("x-correlation-id", "abc")
+            .body("order 42 shipped via /orders");
+        then.status(204);
+    });
+
+    let server = MockServer::start();
+    let order = server.mock(|when, then| {
+        when.method(POST).path("/orders");
+        then.status(202).callback(|cb| {
+            cb.method(PUT)
+                .url(webhook_server.url("/orders/{{request.json.order.id}}/status"))
+                .header("x-correlation-id", "{{request.header.x-correlation-id}}")
+                .body("order {{ request.json.order.id }} {{request.query.state}} via {{request.path}}")
+        });
+    });
+
+    // Act
+    let response = reqwest::blocking::Client::new()
+        .post(server.url("/orders?state=shipped"))
+        .header("x-correlation-id", "abc")
+        .body(r#"{ "order": { "id": 42 } }"#)
+        .send()
+        .unwrap();
+
+    wait_for_calls(&webhook, 1);
+
+    // Assert
+    assert_eq!(response.status(), 202);
+    order.assert();
+    webhook.assert();
+}
+
+#[test]
+fn callback_url_encoding_test() {
+    // Arrange
+    let webhook_server = MockServer::start();
+    let webhook = webhook_server.mock(|when, then| {
+        when.method(POST)
+            .path("/customers/a%20b%2Fc%3Fd%23e")
+            .query_param("source", "x/y z");
+        then.status(204);
+    });
+
+    let server = MockServer::start();
+    let signup = server.mock(|when, then| {
+        when.method(POST).path("/signup");
+        then.status(202).callback(|cb| {
+            cb.url(webhook_server.url("/customers/{{request.header.x-customer}}?source={{request.query.source}}"))
+        });
+    });
+
+    // Act: The values contain characters that would change the structure of the URL.
+    let response = reqwest::blocking::Client::new()
+        .post(server.url("/signup?source=x%2Fy%20z"))
+        .header("x-customer", "a b/c?d#e")
+        .send()
+        .unwrap();
+
+    wait_for_calls(&webhook, 1);
+
+    // Assert
+    assert_eq!(response.status(), 202);
+    signup.assert();
+    webhook.assert();
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn callback_from_yaml_test() {
+    // Arrange
+    let webhook_server = MockServer::start();
+    let webhook = webhook_server.mock(|when, then| {
+        when.method(POST).path("/notify").body("hello from /trigger");
+        then.status(200);
+    });
+
+    let server = MockServer::start();
+    server.playback_from_yaml(format!(
+        r#"
+when:
+  path: /trigger
+then:
+  status: 200
+  callback:
+    - url: {}
+      body: hello from {{{{request.path}}}}
+"#,
+        webhook_server.url("/notify")
+    ));
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/trigger")).unwrap();
+
+    wait_for_calls(&webhook, 1);
+
+    // Assert
+    assert_eq!(response.status(), 200);
+    webhook.assert();
+}
+
+#[test]
+fn callback_json_body_escaping_test() {
+    // Arrange
+    let webhook_server = MockServer::start();
+    let webhook = webhook_server.mock(|when, then| {
+        when.method(POST)
+            .path("/notify")
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({ "note": "say \"hi\"\nthen {leave}", "ids": ["a\\b"], "ref": "r-1" }));
+        then.status(204);
+    });
+
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(POST).path("/notes");
+        then.status(202).callback(|cb| {
+            cb.url(webhook_server.url("/notify")).json_body(serde_json::json!({
+                "note": "{{request.json.text}}",
+                "ids": ["{{request.query.id}}"],
+                "ref": "{{request.json.refs/v1.~id}}"
+            }))
+        });
+    });
+
+    // Act: The placeholder values contain characters that must be escaped in JSON strings and
+    // the JSON keys contain characters that must be escaped in JSON pointers.
+    let response = reqwest::blocking::Client::new()
+        .post(server.url("/notes?id=a%5Cb"))
+        .body(r#"{ "text": "say \"hi\"\nthen {leave}", "refs/v1": { "~id": "r-1" } }"#)
+        .send()
+        .unwrap();
+
+    wait_for_calls(&webhook, 1);
+
+    // Assert
+    assert_eq!(response.status(), 202);
+    webhook.assert();
+}
+
+#[test]
+fn callback_after_response_written_test() {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+
+    // Arrange
+    let webhook_server = MockServer::start();
+    let webhook = webhook_server.mock(|when, then| {
+        when.path("/notify");
+        then.status(204);
+    });
+
+    // The body is larger than the socket buffers, so the response can only be written
+    // completely once the client reads it.
+    let body = vec![b'x'; 64 * 1024 * 1024];
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.path("/large");
+        then.status(200)
+            .body(body.clone())
+            .callback(|cb| cb.url(webhook_server.url("/notify")));
+    });
+
+    // Act: Send the request without reading the response.
+    let mut stream = TcpStream::connect(server.address()).unwrap();
+    write!(
+        stream,
+        "GET /large HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        server.address()
+    )
+    .unwrap();
+
+    thread::sleep(Duration::from_millis(300));
+    let calls_before_read = webhook.calls();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    wait_for_calls(&webhook, 1);
+
+    // Assert
+    assert_eq!(calls_before_read, 0);
+    assert!(response.ends_with(&body));
+    webhook.assert();
+}
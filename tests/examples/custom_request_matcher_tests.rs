@@ -162,3 +162,18 @@ fn dynamic_responder_http_crate_test() {
     assert_eq!(response2.status(), 202);
     assert_eq!(response3.status(), 203);
 }
+
+#[test]
+#[should_panic(expected = "InvalidMockDefinitionError(\"no predicate named 'does_not_exist' has been registered")]
+fn unregistered_named_predicate_test() {
+    use httpmock::prelude::*;
+
+    // Arrange
+    let server = MockServer::start();
+
+    // Creating this mock will panic because no predicate with this name has been registered.
+    server.mock(|when, then| {
+        when.predicate("does_not_exist");
+        then.status(200);
+    });
+}
@@ -1,5 +1,6 @@
 use httpmock::prelude::*;
 use reqwest::blocking::Client;
+use serde_json::{Value, json};
 
 // @example-start: forwarding
 #[test]
@@ -34,6 +35,317 @@ fn forwarding_test() {
 }
 // @example-end
 
+#[test]
+fn forwarding_response_transform_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.path("/orders/1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .header("x-internal", "secret")
+            .json_body(json!({ "id": 1, "state": "shipped", "carrier": "ACME" }));
+    });
+
+    let server = MockServer::start();
+    server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.path("/orders/1");
+        })
+        .override_status(503)
+        .remove_response_header("X-Internal")
+        .add_response_header("x-injected", "true")
+        .replace_body_regex("ACME", "Other Carrier")
+        .patch_json_body(json!({ "state": null, "error": "upstream timeout" }))
+        .map_response(|req, mut res| {
+            res.headers
+                .get_or_insert_with(Vec::new)
+                .push(("x-path".to_string(), req.uri().path().to_string()));
+            res
+        });
+    });
+
+    // Act
+    let response = Client::new().get(server.url("/orders/1")).send().unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 503);
+    assert!(response.headers().get("x-internal").is_none());
+    assert_eq!(response.headers()["x-injected"], "true");
+    assert_eq!(response.headers()["x-path"], "/orders/1");
+
+    let body: Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "id": 1, "carrier": "Other Carrier", "error": "upstream timeout" })
+    );
+}
+
+#[test]
+fn forwarding_request_rewrite_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    let upstream_mock = target_server.mock(|when, then| {
+        when.method(POST)
+            .path("/v2/users/7")
+            .query_param("page", "2")
+            .query_param("tenant", "test")
+            .header("authorization", "Bearer upstream-token")
+            .header_missing("x-debug")
+            .json_body(json!({ "name": "Alice", "source": "gateway" }));
+        then.status(201);
+    });
+
+    let server = MockServer::start();
+    server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.path_prefix("/api/");
+        })
+        .strip_path_prefix("/api")
+        .rewrite_path(r"^/v2/user/(\d+)$", "/v2/users/$1")
+        .set_query_param("tenant", "test")
+        .remove_request_header("authorization")
+        .remove_request_header("x-debug")
+        .add_request_header("authorization", "Bearer upstream-token")
+        .transform_json_body(|mut body| {
+            body["source"] = json!("gateway");
+            body
+        });
+    });
+
+    // Act
+    let response = Client::new()
+        .post(server.url("/api/v2/user/7?page=2&tenant=prod"))
+        .header("authorization", "Bearer client-token")
+        .header("x-debug", "1")
+        .body(r#"{"name":"Alice"}"#)
+        .send()
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 201);
+    upstream_mock.assert();
+}
+
+#[test]
+fn forwarding_load_balancing_test() {
+    use httpmock::LoadBalance;
+
+    // Arrange
+    let blue = MockServer::start();
+    let blue_mock = blue.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("blue");
+    });
+
+    let green = MockServer::start();
+    let green_mock = green.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("green");
+    });
+
+    // A target that refuses connections.
+    let unavailable_url = MockServer::start().base_url();
+
+    let round_robin_server = MockServer::start();
+    round_robin_server.forward_to(vec![blue.base_url(), green.base_url()], |rule| {
+        rule.load_balance(LoadBalance::RoundRobin);
+    });
+
+    let failover_server = MockServer::start();
+    failover_server.forward_to(vec![unavailable_url, blue.base_url()], |rule| {
+        rule.failover(true);
+    });
+
+    let client = Client::new();
+    let get = |server: &MockServer| client.get(server.url("/")).send().unwrap().text().unwrap();
+
+    // Act
+    let round_robin_bodies: Vec<String> = (0..4).map(|_| get(&round_robin_server)).collect();
+    let failover_bodies: Vec<String> = (0..2).map(|_| get(&failover_server)).collect();
+
+    // Assert
+    assert_eq!(round_robin_bodies, vec!["blue", "green", "blue", "green"]);
+    assert_eq!(failover_bodies, vec!["blue", "blue"]);
+    blue_mock.assert_calls(4);
+    green_mock.assert_calls(2);
+}
+
+#[test]
+fn forwarding_legacy_target_base_url_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    let target_mock = target_server.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("target");
+    });
+
+    let server = MockServer::start();
+
+    // Clients of earlier versions describe the target with a single `target_base_url`.
+    let client = Client::new();
+    let created = client
+        .post(server.url("/__httpmock__/forwarding_rules"))
+        .body(
+            json!({
+                "target_base_url": target_server.base_url(),
+                "request_requirements": {},
+                "request_header": []
+            })
+            .to_string(),
+        )
+        .send()
+        .unwrap();
+
+    // Act
+    let response = client.get(server.url("/hello")).send().unwrap();
+
+    // Assert
+    assert_eq!(created.status(), 201);
+    let created: serde_json::Value = serde_json::from_str(&created.text().unwrap()).unwrap();
+    assert_eq!(created["config"]["target_base_url"], target_server.base_url());
+    assert_eq!(created["config"]["targets"][0]["base_url"], target_server.base_url());
+    assert_eq!(response.text().unwrap(), "target");
+    target_mock.assert();
+}
+
+#[test]
+fn forwarding_through_upstream_proxy_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    let target_mock = target_server.mock(|when, then| {
+        when.path("/hello").header("x-via-upstream-proxy", "yes");
+        then.status(200).body("hello");
+    });
+
+    // Another mock server plays the role of the upstream proxy. It only accepts requests with
+    // the credentials from the proxy URL.
+    let upstream_proxy = MockServer::start();
+    upstream_proxy.proxy(|rule| {
+        rule.filter(|when| {
+            when.host(target_server.host())
+                .port(target_server.port())
+                .header("proxy-authorization", "Basic dXNlcjpzZWNyZXQ=");
+        })
+        .add_request_header("x-via-upstream-proxy", "yes");
+    });
+
+    // Plain HTTP, so that the upstream proxy sees the forwarded request rather than a CONNECT tunnel.
+    let server = MockServer::start();
+    server.forward_to(format!("http://{}", target_server.address()), |rule| {
+        rule.upstream_proxy(format!("http://user:secret@{}", upstream_proxy.address()));
+    });
+
+    // Act
+    let response = Client::new().get(server.url("/hello")).send().unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.text().unwrap(), "hello");
+    target_mock.assert();
+}
+
+#[test]
+fn forwarding_fault_injection_test() {
+    use httpmock::Fault;
+    use std::time::{Duration, Instant};
+
+    // Arrange
+    let target_server = MockServer::start();
+    let target_mock = target_server.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("hello");
+    });
+
+    let slow_server = MockServer::start();
+    slow_server.forward_to(target_server.base_url(), |rule| {
+        rule.delay(Duration::from_millis(200)).fault(1.0, Fault::EmptyBody);
+    });
+
+    let failing_server = MockServer::start();
+    failing_server.forward_to(target_server.base_url(), |rule| {
+        rule.error_rate(1.0, HttpMockResponse::builder().status(503).build());
+    });
+
+    let disconnecting_server = MockServer::start();
+    disconnecting_server.forward_to(target_server.base_url(), |rule| {
+        rule.fault(1.0, Fault::CloseConnection);
+    });
+
+    let client = Client::new();
+
+    // Act
+    let start = Instant::now();
+    let slow_response = client.get(slow_server.url("/")).send().unwrap();
+    let slow_elapsed = start.elapsed();
+    let slow_status = slow_response.status().as_u16();
+    let slow_body = slow_response.text().unwrap();
+
+    let failing_response = client.get(failing_server.url("/")).send().unwrap();
+    let disconnecting_result = client.get(disconnecting_server.url("/")).send();
+
+    // Assert
+    assert!(slow_elapsed >= Duration::from_millis(200));
+    assert_eq!(slow_status, 200);
+    assert_eq!(slow_body, "");
+    assert_eq!(failing_response.status().as_u16(), 503);
+    assert!(disconnecting_result.is_err());
+
+    // Only the request to the slow server reached the target server.
+    target_mock.assert_calls(1);
+}
+
+#[test]
+fn forwarding_random_seed_test() {
+    use httpmock::LoadBalance;
+
+    // Arrange
+    let blue = MockServer::start();
+    blue.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("blue");
+    });
+
+    let green = MockServer::start();
+    green.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("green");
+    });
+
+    // Rules with the same seed inject the same faults and choose the same targets.
+    let start_server = |seed: u64| {
+        let server = MockServer::start();
+        server.forward_to(vec![blue.base_url(), green.base_url()], |rule| {
+            rule.load_balance(LoadBalance::Random)
+                .error_rate(0.3, HttpMockResponse::builder().status(503).build())
+                .random_seed(seed);
+        });
+        server
+    };
+    let servers = [start_server(42), start_server(42), start_server(7)];
+
+    let client = Client::new();
+    let responses = |server: &MockServer| -> Vec<String> {
+        (0..30)
+            .map(|_| {
+                let response = client.get(server.url("/")).send().unwrap();
+                format!("{} {}", response.status().as_u16(), response.text().unwrap())
+            })
+            .collect()
+    };
+
+    // Act
+    let [first, second, other] = servers.map(|server| responses(&server));
+
+    // Assert
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+
+    for expected in ["503 ", "200 blue", "200 green"] {
+        assert!(first.iter().any(|response| response == expected));
+    }
+}
+
 #[test]
 fn forward_to_website() {
     // Let's create our mock server for the test
@@ -0,0 +1,196 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+};
+
+use http::Version;
+use httpmock::MockServer;
+
+#[test]
+fn h2c_prior_knowledge_test() {
+    use reqwest::blocking::Client;
+
+    // Arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/hello").http_version(Version::HTTP_2);
+        then.status(200).body("hi");
+    });
+
+    let url = format!("http://{}/hello", server.address());
+
+    // Act
+    let h2c_client = Client::builder().http2_prior_knowledge().build().unwrap();
+    let h2c_responses: Vec<_> = (0..3).map(|_| h2c_client.get(&url).send().unwrap()).collect();
+    let http1_response = Client::builder()
+        .http1_only()
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .unwrap();
+
+    // Assert
+    for response in h2c_responses {
+        assert_eq!(response.version(), reqwest::Version::HTTP_2);
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().unwrap(), "hi");
+    }
+    assert_eq!(http1_response.status(), 404);
+    mock.assert_calls(3);
+}
+
+#[test]
+fn h2c_upgrade_test() {
+    // Arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/hello")
+            .header("x-client", "h2c-test")
+            .http_version(Version::HTTP_2);
+        then.status(200).body("hi");
+    });
+
+    // Act: Send an upgrade request like `curl --http2` does.
+    let upgrade = h2c_upgrade(&server, "x-client: h2c-test\r\n", "AAMAAABkAAQCAAAAAAIAAAAA");
+
+    // Assert: The response to the upgrade request is replayed on stream 1.
+    assert!(upgrade.response.starts_with("HTTP/1.1 101 Switching Protocols"));
+    assert!(
+        upgrade
+            .frames
+            .iter()
+            .any(|frame| frame.frame_type == HEADERS && frame.stream_id == 1)
+    );
+    assert_eq!(upgrade.frames.last().unwrap().payload, b"hi");
+    mock.assert();
+}
+
+#[test]
+fn h2c_upgrade_applies_http2_settings_test() {
+    // Arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/hello").http_version(Version::HTTP_2);
+        then.status(200).body("hi");
+    });
+
+    // Act: The HTTP2-Settings header sets SETTINGS_INITIAL_WINDOW_SIZE to 1, so the server can
+    // only send a single byte of the response body before the client opens the window further.
+    let upgrade = h2c_upgrade(&server, "", "AAQAAAAB");
+
+    // Assert: The settings from the header and the client's SETTINGS frame are acknowledged once.
+    let settings_acks = upgrade
+        .frames
+        .iter()
+        .filter(|frame| frame.frame_type == SETTINGS && frame.flags & ACK != 0)
+        .count();
+    assert_eq!(settings_acks, 1);
+    assert_eq!(upgrade.frames.last().unwrap().payload, b"h");
+    mock.assert();
+}
+
+// Run with `cargo test -- --ignored` on a machine that has curl installed.
+#[test]
+#[ignore = "requires curl with HTTP/2 support"]
+fn h2c_upgrade_with_curl_test() {
+    use std::process::Command;
+
+    // Arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/hello").http_version(Version::HTTP_2);
+        then.status(200).body("hi");
+    });
+
+    // Act: `curl --http2` asks for an h2c upgrade on plain HTTP connections.
+    let output = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--http2",
+            "--write-out",
+            "\n%{http_version}",
+        ])
+        .arg(format!("http://{}/hello", server.address()))
+        .output()
+        .expect("cannot run curl");
+
+    // Assert
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hi\n2");
+    mock.assert();
+}
+
+const DATA: u8 = 0x0;
+const HEADERS: u8 = 0x1;
+const SETTINGS: u8 = 0x4;
+const ACK: u8 = 0x1;
+
+struct Frame {
+    frame_type: u8,
+    flags: u8,
+    stream_id: u32,
+    payload: Vec<u8>,
+}
+
+struct H2cUpgrade {
+    /// The HTTP/1.1 response to the upgrade request.
+    response: String,
+    /// The frames the server sent up to (and including) the first DATA frame on stream 1.
+    frames: Vec<Frame>,
+}
+
+/// Upgrades a connection to `/hello` to HTTP/2 over cleartext (h2c) with the given extra headers
+/// and `HTTP2-Settings` header, and reads the frames the server sends until the response to the
+/// upgrade request starts to arrive on stream 1.
+fn h2c_upgrade(server: &MockServer, headers: &str, http2_settings: &str) -> H2cUpgrade {
+    let mut stream = TcpStream::connect(server.address()).unwrap();
+    write!(
+        stream,
+        "GET /hello HTTP/1.1\r\nHost: {}\r\n{}Connection: Upgrade, HTTP2-Settings\r\n\
+         Upgrade: h2c\r\nHTTP2-Settings: {}\r\n\r\n",
+        server.address(),
+        headers,
+        http2_settings
+    )
+    .unwrap();
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        let mut byte = [0; 1];
+        stream.read_exact(&mut byte).unwrap();
+        response.push(byte[0]);
+    }
+
+    // After the upgrade, the client sends the HTTP/2 connection preface and an empty SETTINGS frame.
+    stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").unwrap();
+    stream.write_all(&[0, 0, 0, SETTINGS, 0, 0, 0, 0, 0]).unwrap();
+
+    let mut frames = Vec::new();
+    loop {
+        let mut frame_header = [0; 9];
+        stream.read_exact(&mut frame_header).unwrap();
+        let len = u32::from_be_bytes([0, frame_header[0], frame_header[1], frame_header[2]]) as usize;
+
+        let mut payload = vec![0; len];
+        stream.read_exact(&mut payload).unwrap();
+
+        let frame = Frame {
+            frame_type: frame_header[3],
+            flags: frame_header[4],
+            stream_id: u32::from_be_bytes([frame_header[5], frame_header[6], frame_header[7], frame_header[8]]),
+            payload,
+        };
+        let done = frame.frame_type == DATA && frame.stream_id == 1;
+        frames.push(frame);
+        if done {
+            break;
+        }
+    }
+
+    H2cUpgrade {
+        response: String::from_utf8_lossy(&response).into_owned(),
+        frames,
+    }
+}
@@ -14,7 +14,12 @@ async fn test_http_get_request() {
 
     let base_url = format!("https://{}", server.address());
 
-    let client = reqwest::Client::new();
+    // Local mock servers sign their certificates with a CA that is generated per process
+    let ca_cert = server.ca_certificate_pem_async().await.unwrap();
+    let client = reqwest::Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(ca_cert.as_bytes()).unwrap())
+        .build()
+        .unwrap();
     let res = client.get(&base_url).send().await.unwrap();
 
     assert_eq!(res.status(), 200, "HTTP status should be 200 OK");
@@ -23,8 +28,6 @@ async fn test_http_get_request() {
 #[cfg(feature = "remote")]
 #[tokio::test]
 async fn https_test_reqwest() {
-    use std::{fs::read, path::PathBuf};
-
     use httpmock::MockServer;
     use reqwest::{Client, tls::Certificate};
 
@@ -43,10 +46,9 @@ async fn https_test_reqwest() {
 
     let base_url = format!("https://localhost:{}", server.address().port());
 
-    // Load the CA certificate from the project path
-    let project_dir = env!("CARGO_MANIFEST_DIR");
-    let cert_path = PathBuf::from(project_dir).join("certs/ca.pem");
-    let cert = Certificate::from_pem(&read(cert_path).unwrap()).unwrap();
+    // Fetch the CA certificate from the remote server
+    let ca_cert = server.ca_certificate_pem_async().await.unwrap();
+    let cert = Certificate::from_pem(ca_cert.as_bytes()).unwrap();
 
     // Build the client with the CA certificate
     let client = Client::builder().add_root_certificate(cert).build().unwrap();
@@ -57,3 +59,237 @@ async fn https_test_reqwest() {
     assert_eq!(res.headers().get("X-Hello").unwrap().to_str().unwrap(), "test");
     assert!(base_url.starts_with("https://"));
 }
+
+/// Starts a mock server with a custom configuration in the background and returns its address.
+fn start_server(builder: httpmock::server::HttpMockServerBuilder) -> std::net::SocketAddr {
+    let server = builder.build().unwrap();
+    let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(server.start_with_signals(Some(addr_sender), std::future::pending()))
+            .unwrap();
+    });
+    addr_receiver.blocking_recv().unwrap()
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn https_client_certificate_test() {
+    use httpmock::server::{ClientAuth, HttpMockServerBuilder, ca_certificate_pem, generate_ca_certificate};
+    use rcgen::{CertificateParams, DnType, Issuer, KeyPair};
+    use reqwest::{
+        blocking::Client,
+        tls::{Certificate, Identity},
+    };
+
+    // Issue a client certificate with a CA that the mock server trusts for client certificates.
+    let (ca_cert, ca_key) = generate_ca_certificate().unwrap();
+    let issuer = Issuer::from_ca_cert_pem(&ca_cert, KeyPair::from_pem(&ca_key).unwrap()).unwrap();
+    let mut params = CertificateParams::new(vec!["billing.internal".to_string()]).unwrap();
+    params.distinguished_name.push(DnType::OrganizationName, "Acme");
+    params.distinguished_name.push(DnType::CommonName, "billing-service");
+    let client_key = KeyPair::generate().unwrap();
+    let client_cert = params.signed_by(&client_key, &issuer).unwrap();
+    let identity =
+        Identity::from_pem(format!("{}{}", client_cert.pem(), client_key.serialize_pem()).as_bytes()).unwrap();
+
+    // The mocks are loaded from a static mock directory, because remote mock server connections
+    // are pooled per process regardless of their address and this test uses its own servers.
+    let mock_dir = std::env::temp_dir().join(format!("httpmock_client_certificate_test_{}", std::process::id()));
+    std::fs::create_dir_all(&mock_dir).unwrap();
+    std::fs::write(
+        mock_dir.join("mocks.yaml"),
+        r#"when:
+  client_cert_subject:
+    - CN=billing-service
+  client_cert_san:
+    - billing.internal
+then:
+  status: 200
+  body: billing
+---
+when:
+  path: /anonymous
+then:
+  status: 200
+"#,
+    )
+    .unwrap();
+
+    let start_server = |auth: ClientAuth| {
+        start_server(
+            HttpMockServerBuilder::new()
+                .static_mock_dir(mock_dir.clone())
+                .https_client_ca(ca_cert.clone(), auth),
+        )
+    };
+
+    let client = |identity: Option<Identity>| {
+        let builder = Client::builder()
+            .add_root_certificate(Certificate::from_pem(ca_certificate_pem().unwrap().as_bytes()).unwrap());
+        match identity {
+            Some(identity) => builder.identity(identity),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+
+    // Arrange
+    let optional_addr = start_server(ClientAuth::Optional);
+    let required_addr = start_server(ClientAuth::Required);
+
+    // Act
+    let url = |addr: std::net::SocketAddr, path: &str| format!("https://localhost:{}{}", addr.port(), path);
+    let authenticated = client(Some(identity.clone()))
+        .get(url(optional_addr, "/billing"))
+        .send()
+        .unwrap();
+    let unauthenticated = client(None).get(url(optional_addr, "/billing")).send().unwrap();
+    let anonymous = client(None).get(url(optional_addr, "/anonymous")).send().unwrap();
+    let rejected = client(None).get(url(required_addr, "/billing")).send();
+
+    // Assert
+    assert_eq!(authenticated.status(), 200);
+    assert_eq!(authenticated.text().unwrap(), "billing");
+    assert_eq!(unauthenticated.status(), 404);
+    assert_eq!(anonymous.status(), 200);
+    assert!(rejected.is_err());
+
+    std::fs::remove_dir_all(mock_dir).unwrap();
+}
+
+#[test]
+fn https_tls_metadata_test() {
+    use httpmock::{HttpMockResponse, MockServer, TlsVersion};
+    use reqwest::{
+        blocking::Client,
+        tls::{Certificate, Version},
+    };
+
+    // Arrange
+    let server = MockServer::start();
+
+    let modern_mock = server.mock(|when, then| {
+        when.path("/modern")
+            .tls_sni("localhost")
+            .tls_version_at_least(TlsVersion::Tls13)
+            .alpn("http/1.1");
+        then.respond_with(|req| {
+            let tls = req.tls().unwrap();
+            HttpMockResponse::builder()
+                .status(200)
+                .body(tls.cipher_suite.clone().unwrap_or_default())
+                .build()
+        });
+    });
+
+    let ca_cert = Certificate::from_pem(server.ca_certificate_pem().unwrap().as_bytes()).unwrap();
+    let client = |max_version: Version| {
+        Client::builder()
+            .add_root_certificate(ca_cert.clone())
+            .http1_only()
+            .max_tls_version(max_version)
+            .build()
+            .unwrap()
+    };
+    let url = format!("https://localhost:{}/modern", server.port());
+
+    // Act
+    let tls13_response = client(Version::TLS_1_3).get(&url).send().unwrap();
+    let tls12_response = client(Version::TLS_1_2).get(&url).send().unwrap();
+
+    // Assert
+    assert_eq!(tls13_response.status(), 200);
+    assert!(tls13_response.text().unwrap().starts_with("TLS13_"));
+    assert_eq!(tls12_response.status(), 404);
+    modern_mock.assert_calls(1);
+}
+
+#[test]
+fn https_static_certificate_test() {
+    use httpmock::server::{HttpMockServerBuilder, generate_ca_certificate};
+    use rcgen::{CertificateParams, KeyPair};
+    use reqwest::{blocking::Client, tls::Certificate};
+
+    let self_signed = |name: &str| {
+        let key = KeyPair::generate().unwrap();
+        let cert = CertificateParams::new(vec![name.to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap();
+        (cert.pem(), key.serialize_pem())
+    };
+
+    // Arrange
+    let (default_cert, default_key) = self_signed("localhost");
+    let (api_cert, api_key) = self_signed("api.test");
+
+    let addr = start_server(
+        HttpMockServerBuilder::new()
+            .https_static_cert(default_cert.clone(), default_key)
+            .https_static_cert_for_sni("api.test", api_cert.clone(), api_key),
+    );
+
+    let client = |root_cert: &str| {
+        Client::builder()
+            .add_root_certificate(Certificate::from_pem(root_cert.as_bytes()).unwrap())
+            .resolve("api.test", addr)
+            .build()
+            .unwrap()
+    };
+    let localhost_url = format!("https://localhost:{}/", addr.port());
+    let api_url = format!("https://api.test:{}/", addr.port());
+
+    // Act
+    let pinned = client(&default_cert).get(&localhost_url).send();
+    let untrusted = client(&generate_ca_certificate().unwrap().0).get(&localhost_url).send();
+    let api = client(&api_cert).get(&api_url).send();
+    let api_with_default_cert = client(&default_cert).get(&api_url).send();
+
+    // Assert: No mocks are configured, so every request that passes the TLS handshake gets a 404.
+    assert_eq!(pinned.unwrap().status(), 404);
+    assert!(untrusted.is_err());
+    assert_eq!(api.unwrap().status(), 404);
+    assert!(api_with_default_cert.is_err());
+}
+
+#[cfg(feature = "proxy")]
+#[test]
+fn https_forwarding_trusts_process_ca_test() {
+    use httpmock::MockServer;
+    use reqwest::{blocking::Client, tls::Certificate};
+
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.path("/hello");
+        then.status(200).body("hi");
+    });
+
+    // The mock server sends forwarded requests to the HTTPS target without further
+    // configuration, because its HTTP client trusts the CA of this process.
+    let server = MockServer::start();
+    server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let ca_cert = server.ca_certificate_pem().unwrap();
+    let client = Client::builder()
+        .add_root_certificate(Certificate::from_pem(ca_cert.as_bytes()).unwrap())
+        .build()
+        .unwrap();
+
+    // Act
+    let response = client.get(server.url("/hello")).send().unwrap();
+
+    // Assert
+    assert!(target_server.base_url().starts_with("https://"));
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "hi");
+}
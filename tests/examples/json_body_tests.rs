@@ -137,3 +137,33 @@ fn partial_json_body_test() {
     m.assert();
     assert_eq!(response.status(), 201);
 }
+
+#[test]
+fn json_body_path_test() {
+    // Arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .json_body_path("$.order.items[*].id")
+            .json_body_path("$..customer");
+        then.status(200);
+    });
+
+    // Act
+    let client = Client::new();
+    let matching = client
+        .post(server.url("/orders"))
+        .body(r#"{"order":{"items":[{"id":1}],"meta":{"customer":"a"}}}"#)
+        .send()
+        .unwrap();
+    let not_matching = client
+        .post(server.url("/orders"))
+        .body(r#"{"order":{"items":[]},"customer":"a"}"#)
+        .send()
+        .unwrap();
+
+    // Assert
+    mock.assert_calls(1);
+    assert_eq!(matching.status(), 200);
+    assert_eq!(not_matching.status(), 404);
+}
@@ -1,4 +1,6 @@
 mod binary_body_tests;
+#[cfg(feature = "proxy")]
+mod callback_tests;
 mod cookie_tests;
 mod custom_request_matcher_tests;
 mod delay_tests;
@@ -8,17 +10,27 @@ mod file_body_tests;
 mod forwarding_tests;
 mod getting_started_tests;
 mod headers_tests;
+#[cfg(feature = "http2")]
+mod http2_tests;
 #[cfg(feature = "https")]
 mod https_tests;
 mod json_body_tests;
 mod multi_server_tests;
+#[cfg(feature = "openapi")]
+mod openapi_tests;
+mod pact_tests;
 #[cfg(feature = "proxy")]
 mod proxy_tests;
 mod query_param_tests;
 mod record_and_playback_tests;
 mod reset_tests;
+mod response_gate_tests;
+#[cfg(feature = "scripting")]
+mod scripting_tests;
 mod showcase_tests;
 mod standalone_tests;
 mod string_body_tests;
 mod url_matching_tests;
+#[cfg(feature = "record")]
+mod wiremock_tests;
 mod x_www_form_urlencoded_tests;
@@ -0,0 +1,151 @@
+use httpmock::prelude::*;
+use reqwest::blocking::Client;
+
+const SPEC: &str = "tests/resources/petstore_openapi.yaml";
+
+#[test]
+fn openapi_examples_test() {
+    // Arrange
+    let server = MockServer::start();
+    server.mocks_from_openapi(SPEC);
+
+    // Act
+    let client = Client::new();
+    let pet = client.get(server.url("/v1/pets/42")).send().unwrap();
+    let my_pets = client.get(server.url("/v1/pets/mine")).send().unwrap();
+    let search = client.get(server.url("/v1/search?q=dog")).send().unwrap();
+    let deleted = client.delete(server.url("/v1/pets/42")).send().unwrap();
+    let unknown = client.get(server.url("/pets/42")).send().unwrap();
+
+    // Assert
+    assert_eq!(pet.status(), 200);
+    assert_eq!(pet.headers()["content-type"], "application/json");
+    assert_eq!(pet.text().unwrap(), r#"{"id":1,"name":"Rex","tag":"dog"}"#);
+
+    // Literal paths take precedence over path templates.
+    assert_eq!(my_pets.status(), 200);
+    assert_eq!(my_pets.text().unwrap(), "Rex, Tom");
+
+    // Named examples are resolved from the components section.
+    assert_eq!(search.text().unwrap(), r#"[{"id":1,"name":"Rex"}]"#);
+
+    assert_eq!(deleted.status(), 204);
+
+    // The path of the server URL is part of every path.
+    assert_eq!(unknown.status(), 404);
+}
+
+#[test]
+fn openapi_generated_response_test() {
+    // Arrange
+    let server = MockServer::start();
+    server.mocks_from_openapi(SPEC);
+
+    // Act
+    let response = Client::new().get(server.url("/v1/pets?limit=10")).send().unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-next"], "/pets?page=2");
+    assert_eq!(response.text().unwrap(), r#"[{"id":0,"name":"string","tag":"string"}]"#);
+}
+
+#[test]
+fn openapi_request_validation_test() {
+    // Arrange
+    let server = MockServer::start();
+    server.mocks_from_openapi_with_validation(SPEC);
+
+    // Act
+    let client = Client::new();
+    let valid_pet = client.get(server.url("/v1/pets/42")).send().unwrap();
+    let invalid_pet = client.get(server.url("/v1/pets/rex")).send().unwrap();
+    let missing_query = client.get(server.url("/v1/search")).send().unwrap();
+    let created = client
+        .post(server.url("/v1/pets"))
+        .header("X-Request-Id", "1")
+        .header("Content-Type", "application/json; charset=utf-8")
+        .body(r#"{"id":7,"name":"Tom"}"#)
+        .send()
+        .unwrap();
+    let missing_header = client
+        .post(server.url("/v1/pets"))
+        .header("Content-Type", "application/json")
+        .body(r#"{"id":7,"name":"Tom"}"#)
+        .send()
+        .unwrap();
+    let wrong_content_type = client
+        .post(server.url("/v1/pets"))
+        .header("X-Request-Id", "1")
+        .header("Content-Type", "text/plain")
+        .body("Tom")
+        .send()
+        .unwrap();
+    let missing_body = client
+        .post(server.url("/v1/pets"))
+        .header("X-Request-Id", "1")
+        .header("Content-Type", "application/json")
+        .send()
+        .unwrap();
+
+    // Assert
+    assert_eq!(valid_pet.status(), 200);
+    assert_eq!(created.status(), 201);
+
+    assert_eq!(invalid_pet.status(), 400);
+    assert_eq!(
+        invalid_pet.text().unwrap(),
+        r#"{"error":"the request does not conform to the OpenAPI specification","operation":"GET /pets/{petId}","operationId":"showPetById"}"#
+    );
+
+    assert_eq!(missing_query.status(), 400);
+    assert_eq!(missing_header.status(), 400);
+    assert_eq!(wrong_content_type.status(), 400);
+    assert_eq!(missing_body.status(), 400);
+}
+
+#[test]
+fn openapi_query_parameter_validation_test() {
+    // Arrange
+    let server = MockServer::start();
+    server.mocks_from_openapi_with_validation(SPEC);
+
+    // Act
+    let client = Client::new();
+    let search = |query: &str| {
+        client
+            .get(server.url(format!("/v1/search?q=dog&{}", query)))
+            .send()
+            .unwrap()
+    };
+    let valid = search("exact=true&sort=name");
+    let valid_alternative = search("exact=false&sort=id");
+    let invalid_boolean_prefix = search("exact=truex&sort=name");
+    let invalid_boolean_suffix = search("exact=notfalse&sort=name");
+    let invalid_enum_prefix = search("exact=true&sort=names");
+    let invalid_enum_suffix = search("exact=true&sort=uuid");
+
+    // Assert
+    assert_eq!(valid.status(), 200);
+    assert_eq!(valid_alternative.status(), 200);
+
+    // Alternatives must match the whole value, not just its start or end.
+    assert_eq!(invalid_boolean_prefix.status(), 400);
+    assert_eq!(invalid_boolean_suffix.status(), 400);
+    assert_eq!(invalid_enum_prefix.status(), 400);
+    assert_eq!(invalid_enum_suffix.status(), 400);
+}
+
+#[test]
+fn openapi_mock_set_delete_test() {
+    // Arrange
+    let server = MockServer::start();
+    let mut mocks = server.mocks_from_openapi(SPEC);
+
+    // Act
+    mocks.delete();
+    let response = Client::new().get(server.url("/v1/pets/42")).send().unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 404);
+}
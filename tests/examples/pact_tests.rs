@@ -0,0 +1,98 @@
+use httpmock::prelude::*;
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+
+#[test]
+fn write_pact_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path_matches(r"^/users/\d+$")
+            .query_param_exists("fields")
+            .header("accept", "application/json");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": 42, "name": "Alice" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/users")
+            .json_body_includes(r#"{ "name": "Bob" }"#);
+        then.status(201);
+    });
+
+    // This mock is never called and must not be part of the contract.
+    server.mock(|when, then| {
+        when.method(DELETE).path("/users/1");
+        then.status(204);
+    });
+
+    let client = Client::new();
+    client
+        .get(server.url("/users/42?fields=name"))
+        .header("accept", "application/json")
+        .header("user-agent", "test")
+        .send()
+        .unwrap();
+    client
+        .post(server.url("/users"))
+        .header("content-type", "application/json")
+        .body(r#"{ "name": "Bob", "age": 30 }"#)
+        .send()
+        .unwrap();
+
+    // Act
+    let dir = std::env::temp_dir().join("httpmock_pact_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let provider = String::from("user-service");
+    let path = server.write_pact("frontend", provider, &dir).unwrap();
+
+    // Assert
+    assert_eq!(path, dir.join("frontend-user-service.json"));
+
+    let pact: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(pact["consumer"]["name"], "frontend");
+    assert_eq!(pact["provider"]["name"], "user-service");
+    assert_eq!(pact["metadata"]["pactSpecification"]["version"], "3.0.0");
+
+    let interactions = pact["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 2);
+
+    let get_user = &interactions[0];
+    assert_eq!(get_user["description"], "GET /users/42");
+    assert_eq!(
+        get_user["request"],
+        json!({
+            "method": "GET",
+            "path": "/users/42",
+            "query": { "fields": ["name"] },
+            "headers": { "accept": "application/json" },
+            "matchingRules": {
+                "path": { "matchers": [{ "match": "regex", "regex": r"^/users/\d+$" }], "combine": "AND" },
+                "query": { "fields": { "matchers": [{ "match": "regex", "regex": ".*" }], "combine": "AND" } }
+            }
+        })
+    );
+    assert_eq!(
+        get_user["response"],
+        json!({
+            "status": 200,
+            "headers": { "content-type": "application/json" },
+            "body": { "id": 42, "name": "Alice" }
+        })
+    );
+
+    let create_user = &interactions[1];
+    assert_eq!(create_user["request"]["body"], json!({ "name": "Bob", "age": 30 }));
+    assert_eq!(
+        create_user["request"]["matchingRules"]["body"],
+        json!({
+            "$": { "matchers": [{ "match": "type" }], "combine": "AND" },
+            "$['name']": { "matchers": [{ "match": "equality" }], "combine": "AND" }
+        })
+    );
+    assert_eq!(create_user["response"], json!({ "status": 201 }));
+}
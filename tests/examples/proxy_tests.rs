@@ -44,6 +44,37 @@ fn proxy_test() {
     assert_eq!(status_code, 200); // Now compare the status code
 }
 
+#[test]
+fn proxy_response_transform_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("Hi from fake GitHub!");
+    });
+
+    let proxy_server = MockServer::start();
+    proxy_server.proxy(|rule| {
+        rule.filter(|when| {
+            when.host(target_server.host()).port(target_server.port());
+        })
+        .add_response_header("x-proxied", "yes")
+        .replace_body_regex("fake", "proxied");
+    });
+
+    let client = Client::builder()
+        .proxy(reqwest::Proxy::all(proxy_server.base_url()).unwrap())
+        .build()
+        .unwrap();
+
+    // Act
+    let response = client.get(target_server.url("/get")).send().unwrap();
+
+    // Assert
+    assert_eq!(response.headers()["x-proxied"], "yes");
+    assert_eq!(response.text().unwrap(), "Hi from proxied GitHub!");
+}
+
 // When httpmock operates as an HTTPS MITM proxy, the client→proxy leg speaks origin-form ("/", with a Host header).
 // Internally we normalize to absolute-form for matching/recording, but before sending upstream we convert back to
 // origin-form. Many HTTPS origin servers (especially those negotiating HTTP/2, like google.com) reject absolute-form
@@ -71,3 +102,148 @@ fn absolute_origin_form_test() {
     let response = client.get("https://google.com/").send().unwrap();
     assert_eq!(response.status(), 301);
 }
+
+#[test]
+fn proxy_passthrough_tunnel_test() {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        time::Duration,
+    };
+
+    // Arrange
+    let target_server = MockServer::start();
+    let target_mock = target_server.mock(|when, then| {
+        when.path("/hello");
+        then.status(200).body("hello through the tunnel");
+    });
+
+    let passthrough_proxy = MockServer::start();
+    passthrough_proxy.proxy(|rule| {
+        rule.passthrough_tunnel().tunnel_deny_host("*.example.com");
+    });
+
+    let intercepting_proxy = MockServer::start();
+    intercepting_proxy.proxy(|rule| {
+        rule.passthrough_tunnel().tunnel_allow_host("*.example.com");
+    });
+
+    // Opens a tunnel through the proxy and sends a plain HTTP request through it.
+    let get_through_tunnel = |proxy: &MockServer| {
+        let mut stream = TcpStream::connect(proxy.address()).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        let target = target_server.address();
+        write!(stream, "CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n\r\n").unwrap();
+
+        let mut connect_response = [0u8; 128];
+        let n = stream.read(&mut connect_response).unwrap();
+        assert!(String::from_utf8_lossy(&connect_response[..n]).starts_with("HTTP/1.1 200"));
+
+        // An intercepted tunnel may already have been closed by the proxy.
+        let _ = write!(
+            stream,
+            "GET /hello HTTP/1.1\r\nHost: {target}\r\nConnection: close\r\n\r\n"
+        );
+
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        String::from_utf8_lossy(&response).to_string()
+    };
+
+    // Act
+    let passed_through = get_through_tunnel(&passthrough_proxy);
+    let intercepted = get_through_tunnel(&intercepting_proxy);
+
+    // Assert
+    assert!(passed_through.starts_with("HTTP/1.1 200"));
+    assert!(passed_through.ends_with("hello through the tunnel"));
+    assert!(!intercepted.contains("hello through the tunnel"));
+    target_mock.assert_calls(1);
+}
+
+#[test]
+fn proxy_passthrough_tunnel_through_upstream_proxy_test() {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{Shutdown, TcpListener, TcpStream},
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    // Arrange
+    let target_server = MockServer::start();
+    let target_mock = target_server.mock(|when, then| {
+        when.path("/hello");
+        then.status(200).body("hello through the upstream proxy");
+    });
+
+    // A minimal HTTP proxy that accepts a single CONNECT request and reports its head.
+    let upstream_proxy = TcpListener::bind("127.0.0.1:0").unwrap();
+    let upstream_proxy_address = upstream_proxy.local_addr().unwrap();
+    let (head_sender, head_receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let (client, _) = upstream_proxy.accept().unwrap();
+        let mut reader = BufReader::new(client.try_clone().unwrap());
+
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            reader.read_line(&mut head).unwrap();
+        }
+
+        let target_address = head.split_whitespace().nth(1).unwrap().to_string();
+        head_sender.send(head).unwrap();
+
+        let target = TcpStream::connect(target_address).unwrap();
+        (&client)
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .unwrap();
+
+        let mut target_reader = target.try_clone().unwrap();
+        let mut client_writer = client.try_clone().unwrap();
+        thread::spawn(move || {
+            let _ = std::io::copy(&mut target_reader, &mut client_writer);
+            let _ = client_writer.shutdown(Shutdown::Write);
+        });
+        let _ = std::io::copy(&mut reader, &mut &target);
+    });
+
+    let proxy = MockServer::start();
+    proxy.proxy(|rule| {
+        rule.passthrough_tunnel()
+            .upstream_proxy(format!("http://user:secret@{}", upstream_proxy_address));
+    });
+
+    // Act: Open a tunnel through the proxy and send a plain HTTP request through it.
+    let target = target_server.address();
+    let mut stream = TcpStream::connect(proxy.address()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    write!(stream, "CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n\r\n").unwrap();
+
+    let mut connect_response = [0u8; 128];
+    let n = stream.read(&mut connect_response).unwrap();
+    let connect_response = String::from_utf8_lossy(&connect_response[..n]).to_string();
+
+    write!(
+        stream,
+        "GET /hello HTTP/1.1\r\nHost: {target}\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+
+    // Assert
+    let upstream_head = head_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(upstream_head.starts_with(&format!("CONNECT {target} HTTP/1.1\r\n")));
+    assert!(
+        upstream_head
+            .to_lowercase()
+            .contains("proxy-authorization: basic dxnlcjpzzwnyzxq=")
+    );
+
+    assert!(connect_response.starts_with("HTTP/1.1 200"));
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.ends_with("hello through the upstream proxy"));
+    target_mock.assert();
+}
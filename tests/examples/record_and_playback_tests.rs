@@ -324,3 +324,675 @@ fn record_with_forwarding_all_request_parts_test() {
             .contains("Simple yet powerful HTTP mocking library for Rust")
     );
 }
+
+#[cfg(feature = "record")]
+#[test]
+fn record_and_playback_har_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.method(POST).path("/orders");
+        then.status(201)
+            .header("content-type", "application/json")
+            .body(r#"{"id":7}"#);
+    });
+    target_server.mock(|when, then| {
+        when.path("/image");
+        then.status(200).body(vec![0u8, 159, 146, 150]);
+    });
+
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let recording = recording_server.record(|rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let client = Client::new();
+    client
+        .post(recording_server.url("/orders?source=web"))
+        .body(r#"{"item":"apple"}"#)
+        .send()
+        .unwrap();
+    client.get(recording_server.url("/image")).send().unwrap();
+
+    // Act
+    let har = recording.export_har().unwrap().unwrap();
+    let path = recording.save_har("har_scenario").unwrap();
+
+    // Assert
+    let document: serde_json::Value = serde_json::from_slice(&har).unwrap();
+    assert_eq!(document["log"]["version"], "1.2");
+
+    let entries = document["log"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["request"]["method"], "POST");
+    assert_eq!(entries[0]["request"]["url"], recording_server.url("/orders?source=web"));
+    assert_eq!(entries[0]["request"]["postData"]["text"], r#"{"item":"apple"}"#);
+    assert_eq!(entries[0]["response"]["status"], 201);
+    assert_eq!(entries[0]["response"]["statusText"], "Created");
+    assert_eq!(entries[0]["response"]["content"]["text"], r#"{"id":7}"#);
+    assert_eq!(entries[1]["response"]["content"]["encoding"], "base64");
+
+    assert_eq!(path.extension().unwrap(), "har");
+
+    let playback_server = MockServer::start();
+    playback_server.playback_har(path);
+
+    let response = client
+        .post(playback_server.url("/orders?source=web"))
+        .body(r#"{"item":"apple"}"#)
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.text().unwrap(), r#"{"id":7}"#);
+
+    let response = client.get(playback_server.url("/image")).send().unwrap();
+    assert_eq!(response.bytes().unwrap().to_vec(), vec![0u8, 159, 146, 150]);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_and_playback_har_json_body_test() {
+    use httpmock::BodyMatchMode;
+
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.method(POST).path("/orders");
+        then.status(201);
+    });
+
+    for mode in [BodyMatchMode::Json, BodyMatchMode::JsonIncludes] {
+        let recording_server = MockServer::start();
+        recording_server.forward_to(target_server.base_url(), |rule| {
+            rule.filter(|when| {
+                when.any_request();
+            });
+        });
+
+        let recording = recording_server.record(|rule| {
+            rule.filter(|when| {
+                when.any_request();
+            })
+            .body_match_mode(mode);
+        });
+
+        let client = Client::new();
+        client
+            .post(recording_server.url("/orders"))
+            .body(r#"{"item":"apple","quantity":2}"#)
+            .send()
+            .unwrap();
+
+        // Act
+        let har = recording.export_har().unwrap().unwrap();
+        let path = recording.save_har(format!("har_json_body_{:?}", mode)).unwrap();
+
+        // Assert
+        let document: serde_json::Value = serde_json::from_slice(&har).unwrap();
+        let post_data = &document["log"]["entries"][0]["request"]["postData"];
+        assert_eq!(post_data["mimeType"], "application/json");
+        assert_eq!(post_data["text"], r#"{"item":"apple","quantity":2}"#);
+
+        let playback_server = MockServer::start();
+        playback_server.playback_har(path);
+
+        let response = client
+            .post(playback_server.url("/orders"))
+            .body(r#"{"item":"apple","quantity":2}"#)
+            .send()
+            .unwrap();
+        assert_eq!(response.status(), 201);
+    }
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_with_redaction_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.method(POST).path("/login");
+        then.status(200)
+            .header("set-cookie", "session=abc123")
+            .body(r#"{"user":"alice","token":"tok-123","card":"4111-1111-1111-1111"}"#);
+    });
+
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let recording = recording_server.record(|rule| {
+        rule.record_request_header("Authorization")
+            .redact_header("authorization")
+            .redact_header("Set-Cookie")
+            .redact_query_param("api_key")
+            .redact_json_field("$.password")
+            .redact_json_field("$.token")
+            .redact_body_regex(r"\d{4}-\d{4}-\d{4}-(\d{4})", "****-$1")
+            .filter(|when| {
+                when.any_request();
+            });
+    });
+
+    let client = Client::new();
+    client
+        .post(recording_server.url("/login?api_key=s3cr3t&lang=en"))
+        .header("Authorization", "Bearer s3cr3t")
+        .body(r#"{"user":"alice","password":"hunter2"}"#)
+        .send()
+        .unwrap();
+
+    // Act
+    let yaml = String::from_utf8(recording.export().unwrap().unwrap().to_vec()).unwrap();
+
+    // Assert
+    for secret in ["s3cr3t", "hunter2", "abc123", "tok-123", "4111-1111-1111-1111"] {
+        assert!(!yaml.contains(secret), "recording contains '{}':\n{}", secret, yaml);
+    }
+    assert!(yaml.contains("[REDACTED]"));
+    assert!(yaml.contains("****-1111"));
+
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(yaml);
+
+    let response = client
+        .post(playback_server.url("/login?api_key=other&lang=en"))
+        .header("Authorization", "Bearer other")
+        .body(r#"{"user":"alice","password":"other"}"#)
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["set-cookie"], "[REDACTED]");
+    assert_eq!(
+        response.text().unwrap(),
+        r#"{"card":"****-1111","token":"[REDACTED]","user":"alice"}"#
+    );
+
+    let response = client
+        .post(playback_server.url("/login?api_key=other&lang=en"))
+        .header("Authorization", "Bearer other")
+        .body(r#"{"user":"bob","password":"other"}"#)
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_with_normalized_response_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.path("/status");
+        then.status(200)
+            .header("content-type", "application/json")
+            .header("etag", "\"33a64df5\"")
+            .header("x-request-id", "5d1f8e2a")
+            .header("x-powered-by", "Express")
+            .header("server", "nginx")
+            .body(r#"{"ok":true,"requestId":"abc","checkedAt":"2024-05-01T12:30:00.123Z","items":[{"createdAt":"2024-04-30 08:00:00"}]}"#);
+    });
+
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let recording = recording_server.record(|rule| {
+        rule.record_response_headers(vec!["Content-Type", "ETag", "X-Request-Id", "X-Powered-By", "Date"])
+            .drop_response_headers(vec!["x-powered-by"])
+            .normalize_volatile_response_headers()
+            .normalize_json_field("$.requestId", "<request-id>")
+            .normalize_json_timestamps("<timestamp>")
+            .filter(|when| {
+                when.any_request();
+            });
+    });
+
+    // Act
+    Client::new().get(recording_server.url("/status")).send().unwrap();
+    let yaml = String::from_utf8(recording.export().unwrap().unwrap().to_vec()).unwrap();
+
+    // Assert
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(&yaml);
+
+    let response = Client::new().get(playback_server.url("/status")).send().unwrap();
+    let headers = response.headers();
+    assert_eq!(headers["content-type"], "application/json");
+    assert_eq!(headers["etag"], "\"normalized\"");
+    assert_eq!(headers["x-request-id"], "00000000-0000-0000-0000-000000000000");
+    assert_eq!(headers["date"], "Thu, 01 Jan 1970 00:00:00 GMT");
+    assert!(!headers.contains_key("x-powered-by"));
+    assert!(!headers.contains_key("server"));
+    assert!(!yaml.contains("Express") && !yaml.contains("nginx"));
+
+    let body: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "ok": true,
+            "requestId": "<request-id>",
+            "checkedAt": "<timestamp>",
+            "items": [{ "createdAt": "<timestamp>" }]
+        })
+    );
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_with_relaxed_request_matching_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.any_request();
+        then.status(200).body("recorded");
+    });
+
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let json_recording = recording_server.record(|rule| {
+        rule.match_body_as_json()
+            .ignore_query_params(vec!["ts"])
+            .filter(|when| {
+                when.path("/json");
+            });
+    });
+
+    let strict_recording = recording_server.record(|rule| {
+        rule.ignore_body().match_query_subset(false).filter(|when| {
+            when.path("/strict");
+        });
+    });
+
+    let client = Client::new();
+    client
+        .post(recording_server.url("/json?ts=1&page=2"))
+        .body(r#"{"a":1,"b":[1,2]}"#)
+        .send()
+        .unwrap();
+    client
+        .post(recording_server.url("/strict?page=2"))
+        .body("original")
+        .send()
+        .unwrap();
+
+    // Act
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(String::from_utf8(json_recording.export().unwrap().unwrap().to_vec()).unwrap());
+    playback_server
+        .playback_from_yaml(String::from_utf8(strict_recording.export().unwrap().unwrap().to_vec()).unwrap());
+
+    let send = |path: &str, body: &str| {
+        client
+            .post(playback_server.url(path))
+            .body(body.to_string())
+            .send()
+            .unwrap()
+            .status()
+    };
+
+    // Assert
+    // Reordered JSON keys, different whitespace and a changed timestamp still match.
+    assert_eq!(send("/json?page=2&ts=999", r#"{ "b": [1, 2], "a": 1 }"#), 200);
+    assert_eq!(send("/json?page=2", r#"{"a":2,"b":[1,2]}"#), 404);
+
+    // The body is ignored, but no other query parameters are allowed.
+    assert_eq!(send("/strict?page=2", "something else"), 200);
+    assert_eq!(send("/strict?page=2&extra=1", "original"), 404);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_repeated_requests_test() {
+    use httpmock::RepeatedRequests;
+
+    // Arrange
+    let target_server = MockServer::start();
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let sequence_recording = recording_server.record(|rule| {
+        rule.repeated_requests(RepeatedRequests::Sequence).filter(|when| {
+            when.path("/job");
+        });
+    });
+    let collapsed_recording = recording_server.record(|rule| {
+        rule.repeated_requests(RepeatedRequests::Collapse).filter(|when| {
+            when.path("/job");
+        });
+    });
+
+    // A polling flow: the job is pending on the first two requests and done afterwards.
+    let client = Client::new();
+    for status in ["pending", "pending", "done"] {
+        let mut mock = target_server.mock(|when, then| {
+            when.path("/job");
+            then.status(200).body(status);
+        });
+        client.get(recording_server.url("/job")).send().unwrap();
+        mock.delete();
+    }
+
+    // Act
+    let sequence_yaml = String::from_utf8(sequence_recording.export().unwrap().unwrap().to_vec()).unwrap();
+    let collapsed_yaml = String::from_utf8(collapsed_recording.export().unwrap().unwrap().to_vec()).unwrap();
+
+    // Assert
+    assert_eq!(sequence_yaml.matches("when:").count(), 1);
+    assert_eq!(collapsed_yaml.matches("when:").count(), 1);
+
+    let get = |server: &MockServer| client.get(server.url("/job")).send().unwrap().text().unwrap();
+
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(sequence_yaml);
+    assert_eq!(get(&playback_server), "pending");
+    assert_eq!(get(&playback_server), "pending");
+    assert_eq!(get(&playback_server), "done");
+    // The last response is repeated.
+    assert_eq!(get(&playback_server), "done");
+
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(collapsed_yaml);
+    assert_eq!(get(&playback_server), "pending");
+    assert_eq!(get(&playback_server), "pending");
+    assert_eq!(get(&playback_server), "pending");
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn playback_sequence_with_delay_test() {
+    use std::time::{Duration, Instant};
+
+    // Arrange: The delay of the response applies to every response of the sequence that does not
+    // set its own delay.
+    let yaml = r#"
+when:
+  path: /job
+then:
+  delay: 300
+  sequence:
+    - status: 202
+      body: pending
+    - status: 200
+      body: done
+      delay: 10
+"#;
+
+    let server = MockServer::start();
+    server.playback_from_yaml(yaml);
+
+    let client = Client::new();
+    let get = || {
+        let start = Instant::now();
+        let status = client.get(server.url("/job")).send().unwrap().status();
+        (status, start.elapsed())
+    };
+
+    // Act
+    let (pending_status, pending_elapsed) = get();
+    let (done_status, done_elapsed) = get();
+
+    // Assert
+    assert_eq!(pending_status, 202);
+    assert!(pending_elapsed >= Duration::from_millis(300));
+    assert_eq!(done_status, 200);
+    assert!(done_elapsed < Duration::from_millis(300));
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_streaming_to_file_test() {
+    // Arrange
+    let target_server = MockServer::start();
+    target_server.mock(|when, then| {
+        when.path_prefix("/items/");
+        then.status(200).body("item");
+    });
+
+    let recording_server = MockServer::start();
+    recording_server.forward_to(target_server.base_url(), |rule| {
+        rule.filter(|when| {
+            when.any_request();
+        });
+    });
+
+    let path = std::env::temp_dir().join(format!("httpmock_streaming_recording_test_{}.yaml", std::process::id()));
+    let recording = recording_server.record(|rule| {
+        rule.stream_to(&path).stream_max_interactions(2).filter(|when| {
+            when.any_request();
+        });
+    });
+
+    // Act
+    let client = Client::new();
+    for id in 1..=3 {
+        client
+            .get(recording_server.url(format!("/items/{}", id)))
+            .send()
+            .unwrap();
+    }
+
+    // Assert
+    // Interactions are written to the file as they happen, and the cap is respected.
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.matches("when:").count(), 2);
+    assert!(content.contains("/items/1") && content.contains("/items/2"));
+    assert!(!content.contains("/items/3"));
+
+    // Exporting the recording returns the content of the file.
+    let exported = recording.export().unwrap().unwrap();
+    assert_eq!(exported, content.as_bytes());
+    std::fs::remove_file(&path).unwrap();
+
+    let playback_server = MockServer::start();
+    playback_server.playback_from_yaml(content);
+    assert_eq!(
+        client.get(playback_server.url("/items/2")).send().unwrap().status(),
+        200
+    );
+    assert_eq!(
+        client.get(playback_server.url("/items/3")).send().unwrap().status(),
+        404
+    );
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_streaming_over_rest_api_is_confined_test() {
+    use httpmock::server::HttpMockServerBuilder;
+
+    // Arrange
+    let dir = std::env::temp_dir().join(format!("httpmock_recording_stream_dir_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let outside = std::env::temp_dir().join(format!("httpmock_recording_stream_outside_{}.yaml", std::process::id()));
+
+    let start_server = |builder: HttpMockServerBuilder| {
+        let server = builder.build().unwrap();
+        let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(server.start_with_signals(Some(addr_sender), std::future::pending()))
+                .unwrap();
+        });
+        addr_receiver.blocking_recv().unwrap()
+    };
+    let confined_addr = start_server(HttpMockServerBuilder::new().recording_stream_dir(dir.clone()));
+    let unconfined_addr = start_server(HttpMockServerBuilder::new());
+
+    let create_recording = |addr: std::net::SocketAddr, path: &std::path::Path| {
+        Client::new()
+            .post(format!("http://{}/__httpmock__/recordings", addr))
+            .body(serde_json::json!({ "stream_to": path }).to_string())
+            .send()
+            .unwrap()
+            .status()
+            .as_u16()
+    };
+
+    // Act
+    let relative = create_recording(confined_addr, std::path::Path::new("inside.yaml"));
+    let absolute_inside = create_recording(confined_addr, &dir.join("absolute.yaml"));
+    let escaping = create_recording(confined_addr, std::path::Path::new("../escaping.yaml"));
+    let absolute_outside = create_recording(confined_addr, &outside);
+    let without_dir = create_recording(unconfined_addr, &outside);
+
+    // Assert
+    assert_eq!(relative, 201);
+    assert!(dir.join("inside.yaml").exists());
+    assert_eq!(absolute_inside, 201);
+    assert!(dir.join("absolute.yaml").exists());
+
+    assert_ne!(escaping, 201);
+    assert!(!dir.parent().unwrap().join("escaping.yaml").exists());
+    assert_ne!(absolute_outside, 201);
+    assert_ne!(without_dir, 201);
+    assert!(!outside.exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn cassette_test() {
+    use httpmock::CassetteMode;
+
+    // Arrange
+    let upstream = MockServer::start();
+    let hello = upstream.mock(|when, then| {
+        when.path("/hello");
+        then.status(200).body("hello");
+    });
+    let world = upstream.mock(|when, then| {
+        when.path("/world");
+        then.status(200).body("world");
+    });
+
+    let path = std::env::temp_dir().join("httpmock_cassette_test.yaml");
+    let _ = std::fs::remove_file(&path);
+
+    let client = Client::new();
+    let get = |server: &MockServer, path: &str| client.get(server.url(path)).send().unwrap();
+
+    // Act & Assert: Record writes all forwarded interactions when the server is dropped.
+    {
+        let server = MockServer::start();
+        server.cassette(&path, upstream.base_url(), CassetteMode::Record);
+        assert_eq!(get(&server, "/hello").text().unwrap(), "hello");
+    }
+    assert!(std::fs::read_to_string(&path).unwrap().contains("/hello"));
+
+    // RecordMissing replays known interactions and records unknown ones.
+    {
+        let server = MockServer::start();
+        server.cassette(&path, upstream.base_url(), CassetteMode::RecordMissing);
+        assert_eq!(get(&server, "/hello").text().unwrap(), "hello");
+        assert_eq!(get(&server, "/world").text().unwrap(), "world");
+    }
+    hello.assert_calls(1);
+    world.assert_calls(1);
+
+    // Replay never contacts the upstream server.
+    {
+        let server = MockServer::start();
+        server.cassette(&path, upstream.base_url(), CassetteMode::Replay);
+        assert_eq!(get(&server, "/hello").text().unwrap(), "hello");
+        assert_eq!(get(&server, "/world").text().unwrap(), "world");
+        assert_eq!(get(&server, "/unknown").status(), 404);
+    }
+    hello.assert_calls(1);
+    world.assert_calls(1);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.matches("/hello").count(), 1);
+    assert_eq!(content.matches("/world").count(), 1);
+
+    // Passthrough forwards all requests and leaves the cassette untouched.
+    {
+        let server = MockServer::start();
+        server.cassette(&path, upstream.base_url(), CassetteMode::Passthrough);
+        assert_eq!(get(&server, "/hello").text().unwrap(), "hello");
+    }
+    hello.assert_calls(2);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn cassette_save_error_test() {
+    use httpmock::CassetteMode;
+
+    // Arrange: The cassette path is a directory, so the cassette cannot be written.
+    let upstream = MockServer::start();
+    let path = std::env::temp_dir();
+
+    let server = MockServer::start();
+    server.cassette(&path, upstream.base_url(), CassetteMode::Record);
+
+    // Act
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(server)));
+
+    // Assert
+    let err = result.unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with("cannot save cassette to"), "{}", message);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn playback_browser_har_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Act
+    let mocks = server.playback_har("tests/resources/simple_archive.har");
+
+    // Assert
+    // The cancelled request in the archive (status 0) is skipped.
+    assert_eq!(mocks.ids.len(), 2);
+
+    let client = Client::new();
+    let users = client.get(server.url("/api/users?page=1")).send().unwrap();
+    assert_eq!(users.status(), 200);
+    assert_eq!(users.headers()["content-type"], "application/json");
+    // Encoding headers describe the original transfer and are not played back.
+    assert!(users.headers().get("content-encoding").is_none());
+    assert_eq!(users.text().unwrap(), r#"[{"id":1,"name":"Alice"}]"#);
+
+    let created = client
+        .post(server.url("/api/users"))
+        .body(r#"{"name":"Bob"}"#)
+        .send()
+        .unwrap();
+    assert_eq!(created.status(), 201);
+    assert_eq!(created.headers()["location"], "/api/users/2");
+    assert_eq!(created.bytes().unwrap().to_vec(), vec![0u8, 1, 2, 3]);
+
+    let other_page = client.get(server.url("/api/users?page=2")).send().unwrap();
+    assert_eq!(other_page.status(), 404);
+}
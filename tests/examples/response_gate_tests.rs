@@ -0,0 +1,64 @@
+use std::thread;
+
+use httpmock::{ResponseGate, prelude::*};
+
+#[test]
+fn response_gate_release_test() {
+    // Arrange
+    let server = MockServer::start();
+    let gate = ResponseGate::new();
+
+    let mock = server.mock(|when, then| {
+        when.path("/gated");
+        then.status(200).body("released").wait_for(gate.clone());
+    });
+
+    // Act: Send two requests that will be parked at the gate
+    let url = server.url("/gated");
+    let requests: Vec<_> = (0..2)
+        .map(|_| {
+            let url = url.clone();
+            thread::spawn(move || reqwest::blocking::get(url).unwrap())
+        })
+        .collect();
+
+    gate.wait_for_pending(2);
+    assert_eq!(gate.pending(), 2);
+
+    gate.release();
+    gate.wait_for_pending(1);
+    assert_eq!(gate.release_all(), 1);
+
+    // Assert
+    for request in requests {
+        let response = request.join().unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().unwrap(), "released");
+    }
+
+    assert_eq!(gate.pending(), 0);
+    mock.assert_calls(2);
+}
+
+#[test]
+fn response_gate_release_with_test() {
+    // Arrange
+    let server = MockServer::start();
+    let gate = ResponseGate::new();
+
+    let mock = server.mock(|when, then| {
+        when.path("/gated");
+        then.status(200).wait_for(gate.clone());
+    });
+
+    // A release that happens before a request arrives lets the next request pass immediately
+    gate.release_with(HttpMockResponse::builder().status(503).body("unavailable").build());
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/gated")).unwrap();
+
+    // Assert
+    mock.assert();
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.text().unwrap(), "unavailable");
+}
@@ -0,0 +1,222 @@
+use httpmock::prelude::*;
+use reqwest::blocking::Client;
+
+#[test]
+fn script_predicate_and_response_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/orders")
+            .script_predicate(r#"request.json.quantity > 10"#);
+        then.script(
+            r#"
+            let order = request.json;
+            #{
+                status: 201,
+                headers: #{ "x-order-item": order.item },
+                body: #{ item: order.item, quantity: order.quantity, bulk: true }
+            }
+            "#,
+        );
+    });
+
+    // Act
+    let client = Client::new();
+    let bulk_response = client
+        .post(server.url("/orders"))
+        .body(r#"{"item": "apple", "quantity": 12}"#)
+        .send()
+        .unwrap();
+    let small_response = client
+        .post(server.url("/orders"))
+        .body(r#"{"item": "apple", "quantity": 2}"#)
+        .send()
+        .unwrap();
+
+    // Assert
+    mock.assert_calls(1);
+    assert_eq!(bulk_response.status(), 201);
+    assert_eq!(bulk_response.headers().get("x-order-item").unwrap(), "apple");
+    assert_eq!(
+        bulk_response.text().unwrap(),
+        r#"{"bulk":true,"item":"apple","quantity":12}"#
+    );
+    assert_eq!(small_response.status(), 404);
+}
+
+#[test]
+fn script_runtime_error_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.path("/broken");
+        then.script(r#"response().status(request.does_not_exist)"#);
+    });
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/broken")).unwrap();
+
+    // Assert
+    mock.assert();
+    assert_eq!(response.status(), 500);
+    assert!(response.text().unwrap().starts_with("script error:"));
+}
+
+#[test]
+fn script_invalid_status_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    let builder_mock = server.mock(|when, then| {
+        when.path("/builder");
+        then.script(r#"response().status(70000)"#);
+    });
+    let map_mock = server.mock(|when, then| {
+        when.path("/map");
+        then.script(r#"#{ status: -1 }"#);
+    });
+
+    // Act
+    let builder_response = reqwest::blocking::get(server.url("/builder")).unwrap();
+    let map_response = reqwest::blocking::get(server.url("/map")).unwrap();
+
+    // Assert
+    builder_mock.assert();
+    map_mock.assert();
+    assert_eq!(builder_response.status(), 500);
+    assert!(
+        builder_response
+            .text()
+            .unwrap()
+            .contains("status must be between 100 and 999, got 70000")
+    );
+    assert_eq!(map_response.status(), 500);
+    assert!(
+        map_response
+            .text()
+            .unwrap()
+            .contains("status must be between 100 and 999, got -1")
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid script")]
+fn script_compile_error_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Creating this mock will panic because the script cannot be compiled.
+    server.mock(|when, _then| {
+        when.script_predicate("request.path ==");
+    });
+}
+
+#[test]
+fn script_sandbox_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // A module that scripts would be able to import if they had access to the filesystem.
+    let module = std::env::temp_dir().join(format!("httpmock-secret-{}", std::process::id()));
+    std::fs::write(module.with_extension("rhai"), "export const value = 42;").unwrap();
+
+    server.mock(|when, then| {
+        when.path("/import");
+        then.script(format!(
+            r#"import "{}" as secret; secret::value"#,
+            module.display().to_string().replace('\\', "/")
+        ));
+    });
+    server.mock(|when, then| {
+        when.path("/runaway");
+        then.script(r#"let s = "x"; loop { s += s; }"#);
+    });
+
+    // Act
+    let import_response = reqwest::blocking::get(server.url("/import")).unwrap();
+    let runaway_response = reqwest::blocking::get(server.url("/runaway")).unwrap();
+
+    std::fs::remove_file(module.with_extension("rhai")).unwrap();
+
+    // Assert
+    assert_eq!(import_response.status(), 500);
+    assert!(import_response.text().unwrap().contains("Module not found"));
+    assert_eq!(runaway_response.status(), 500);
+    assert!(runaway_response.text().unwrap().contains("Length of string"));
+}
+
+#[test]
+fn script_predicate_operation_budget_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Predicates are evaluated while the server is blocked, so their operation budget is small.
+    let mock = server.mock(|when, then| {
+        when.path("/busy")
+            .script_predicate(r#"let i = 0; while i < 50000 { i += 1; } true"#);
+        then.status(200);
+    });
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/busy")).unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 404);
+    mock.assert_calls(0);
+}
+
+#[test]
+#[should_panic(expected = "both a custom responder function and a script")]
+fn script_and_respond_with_conflict_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Creating this mock will panic because the script would silently replace the closure.
+    server.mock(|when, then| {
+        when.path("/conflict");
+        then.respond_with(|_| HttpMockResponse::builder().status(200).build())
+            .script(r#""script""#);
+    });
+}
+
+#[test]
+#[should_panic(expected = "both a script and a named responder")]
+fn script_and_responder_conflict_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Creating this mock will panic because it is ambiguous which responder to use.
+    server.mock(|when, then| {
+        when.path("/conflict");
+        then.script(r#""script""#).responder("echo_path");
+    });
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn script_from_yaml_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    server.playback_from_yaml(
+        r#"
+when:
+  path: /greet
+  script_predicate:
+    - request.query_param("name") != ()
+then:
+  script: |
+    "Hello, " + request.query_param("name") + "!"
+"#,
+    );
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/greet?name=Fred")).unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "Hello, Fred!");
+}
@@ -142,3 +142,122 @@ fn binary_body_standalone_test() {
 
     assert_eq!(buf, binary_content.to_vec());
 }
+
+#[cfg(feature = "remote")]
+#[test]
+#[should_panic(expected = "Anonymous function request matchers are not supported")]
+fn unsupported_is_false_closure_test() {
+    use httpmock::MockServer;
+
+    use crate::with_standalone_server;
+
+    // Arrange
+    with_standalone_server();
+
+    let server = MockServer::connect_from_env();
+
+    // Creating this mock will panic because closures cannot be sent to a remote mock server.
+    let _ = server.mock(|when, _then| {
+        when.is_false(|_| false);
+    });
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn named_predicate_standalone_test() {
+    use httpmock::MockServer;
+    use reqwest::blocking::Client;
+
+    use crate::{simulated_standalone_server_disabled, with_standalone_server};
+
+    // The standalone binary has no named functions registered.
+    if simulated_standalone_server_disabled() {
+        return;
+    }
+
+    // Arrange
+
+    // This starts up a standalone server in the background running on port 5050. The server
+    // has a predicate named "is_admin" registered (see with_standalone_server).
+    with_standalone_server();
+
+    let server = MockServer::connect_from_env();
+
+    let admin_mock = server.mock(|when, then| {
+        when.path("/named-predicate").predicate("is_admin");
+        then.status(200);
+    });
+
+    let guest_mock = server.mock(|when, then| {
+        when.path("/named-predicate").predicate_not("is_admin");
+        then.status(403);
+    });
+
+    // Act
+    let client = Client::new();
+    let admin_response = client
+        .get(server.url("/named-predicate"))
+        .header("x-role", "admin")
+        .send()
+        .unwrap();
+    let guest_response = client.get(server.url("/named-predicate")).send().unwrap();
+
+    // Assert
+    admin_mock.assert();
+    guest_mock.assert();
+    assert_eq!(admin_response.status(), 200);
+    assert_eq!(guest_response.status(), 403);
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn named_responder_standalone_test() {
+    use httpmock::MockServer;
+
+    use crate::{simulated_standalone_server_disabled, with_standalone_server};
+
+    // The standalone binary has no named functions registered.
+    if simulated_standalone_server_disabled() {
+        return;
+    }
+
+    // Arrange
+
+    // This starts up a standalone server in the background running on port 5050. The server
+    // has a responder named "echo_path" registered (see with_standalone_server).
+    with_standalone_server();
+
+    let server = MockServer::connect_from_env();
+
+    let m = server.mock(|when, then| {
+        when.path_prefix("/named-responder");
+        then.responder("echo_path");
+    });
+
+    // Act
+    let response = reqwest::blocking::get(server.url("/named-responder/hello")).unwrap();
+
+    // Assert
+    m.assert();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "/named-responder/hello");
+}
+
+#[cfg(feature = "remote")]
+#[test]
+#[should_panic(expected = "no predicate named 'does_not_exist' has been registered")]
+fn unregistered_named_predicate_test() {
+    use httpmock::MockServer;
+
+    use crate::with_standalone_server;
+
+    // Arrange
+    with_standalone_server();
+
+    let server = MockServer::connect_from_env();
+
+    // Creating this mock will panic because the server does not know the predicate.
+    let _ = server.mock(|when, _then| {
+        when.predicate("does_not_exist");
+    });
+}
@@ -0,0 +1,186 @@
+use std::time::{Duration, Instant};
+
+use httpmock::{prelude::*, server::HttpMockServerBuilder};
+use reqwest::blocking::Client;
+
+#[test]
+fn wiremock_mappings_test() {
+    // Arrange
+    let server = MockServer::start();
+
+    // Act
+    let mocks = server.mocks_from_wiremock("tests/resources/wiremock");
+
+    // Assert
+    assert_eq!(mocks.ids.len(), 4);
+
+    let client = Client::new();
+
+    // Matched with a path pattern and a header; body read from "__files".
+    let user = client
+        .get(server.url("/api/users/42"))
+        .header("Accept", "application/json")
+        .send()
+        .unwrap();
+    assert_eq!(user.status(), 200);
+    assert_eq!(user.headers()["content-type"], "application/json");
+    assert_eq!(user.text().unwrap(), r#"{"id":42,"name":"Alice"}"#);
+
+    // Falls through to the stub with the lower priority.
+    let unknown = client.get(server.url("/api/users/abc")).send().unwrap();
+    assert_eq!(unknown.status(), 404);
+    assert_eq!(unknown.text().unwrap(), r#"{"error":"not found"}"#);
+
+    // Query parameter matchers.
+    let search = client.get(server.url("/api/users?name=ALICE&page=2")).send().unwrap();
+    assert_eq!(search.status(), 200);
+    assert_eq!(search.headers()["x-total-count"], "1");
+    assert_eq!(search.headers().get_all("set-cookie").iter().count(), 2);
+    assert_eq!(search.text().unwrap(), r#"[{"id":42}]"#);
+
+    let search_with_debug = client
+        .get(server.url("/api/users?name=alice&page=2&debug=true"))
+        .send()
+        .unwrap();
+    assert_eq!(search_with_debug.status(), 404);
+}
+
+#[test]
+fn wiremock_body_patterns_test() {
+    // Arrange
+    let server = MockServer::start();
+    server.mocks_from_wiremock("tests/resources/wiremock/mappings/create-order.json");
+
+    let client = Client::new();
+    let send = |body: &str| {
+        client
+            .post(server.url("/api/orders?source=web"))
+            .basic_auth("shop", Some("secret"))
+            .body(body.to_string())
+            .send()
+            .unwrap()
+    };
+
+    // Act
+    let start = Instant::now();
+    let created = send(r#"{"item":"apple","quantity":3,"note":"extra"}"#);
+    let elapsed = start.elapsed();
+    let missing_quantity = send(r#"{"item":"apple"}"#);
+    let other_item = send(r#"{"item":"pear","quantity":3}"#);
+
+    // `url` matches the query string exactly, so additional query parameters are rejected.
+    let extra_query_param = client
+        .post(server.url("/api/orders?source=web&debug=true"))
+        .basic_auth("shop", Some("secret"))
+        .body(r#"{"item":"apple","quantity":3}"#)
+        .send()
+        .unwrap();
+
+    // Assert
+    assert_eq!(created.status(), 201);
+    assert!(elapsed >= Duration::from_millis(10));
+    assert_eq!(created.bytes().unwrap().to_vec(), vec![0u8, 1, 2, 3]);
+    assert_eq!(missing_quantity.status(), 404);
+    assert_eq!(other_item.status(), 404);
+    assert_eq!(extra_query_param.status(), 404);
+}
+
+#[test]
+#[should_panic(expected = "is not inside the '__files' directory")]
+fn wiremock_body_file_outside_files_dir_test() {
+    let dir = std::env::temp_dir().join(format!("httpmock_wiremock_body_file_test_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("mappings")).unwrap();
+    std::fs::create_dir_all(dir.join("__files")).unwrap();
+    std::fs::write(dir.join("secret.txt"), "secret").unwrap();
+    std::fs::write(
+        dir.join("mappings").join("secret.json"),
+        r#"{ "request": { "url": "/secret" }, "response": { "bodyFileName": "../secret.txt" } }"#,
+    )
+    .unwrap();
+
+    let server = MockServer::start();
+    server.mocks_from_wiremock(&dir);
+}
+
+#[test]
+#[should_panic(expected = "scenarios are not supported")]
+fn wiremock_unsupported_mapping_test() {
+    let dir = std::env::temp_dir().join("httpmock_wiremock_unsupported_mapping_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("scenario.json"),
+        r#"{ "scenarioName": "checkout", "request": { "method": "GET" }, "response": { "status": 200 } }"#,
+    )
+    .unwrap();
+
+    let server = MockServer::start();
+    server.mocks_from_wiremock(dir);
+}
+
+#[test]
+fn wiremock_static_mock_dir_test() {
+    // Arrange
+    let server = HttpMockServerBuilder::new()
+        .static_mock_dir("tests/resources/wiremock".into())
+        .build()
+        .unwrap();
+
+    let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(server.start_with_signals(Some(addr_sender), std::future::pending()))
+            .unwrap();
+    });
+    let addr = addr_receiver.blocking_recv().unwrap();
+
+    // Act
+    let response = Client::new()
+        .get(format!("http://{}/api/users/42", addr))
+        .header("Accept", "application/json")
+        .send()
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), r#"{"id":42,"name":"Alice"}"#);
+}
+
+#[test]
+fn wiremock_static_mock_dir_ignores_top_level_json_test() {
+    // Arrange: JSON files next to the YAML mocks (e.g. fixtures) are not stub mappings.
+    let dir = std::env::temp_dir().join(format!("httpmock_wiremock_top_level_json_test_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("mappings")).unwrap();
+    std::fs::write(dir.join("fixture.json"), r#"{ "scenarioName": "not a stub mapping" }"#).unwrap();
+    std::fs::write(
+        dir.join("mappings").join("ping.json"),
+        r#"{ "request": { "method": "GET", "url": "/ping" }, "response": { "status": 200, "body": "pong" } }"#,
+    )
+    .unwrap();
+
+    let server = HttpMockServerBuilder::new().static_mock_dir(dir.clone()).build();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let server = server.unwrap();
+
+    let (addr_sender, addr_receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(server.start_with_signals(Some(addr_sender), std::future::pending()))
+            .unwrap();
+    });
+    let addr = addr_receiver.blocking_recv().unwrap();
+
+    // Act
+    let response = Client::new().get(format!("http://{}/ping", addr)).send().unwrap();
+
+    // Assert
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "pong");
+}
@@ -1,6 +1,9 @@
 use std::{sync::Mutex, thread};
 
-use httpmock::server::{HttpMockServer, HttpMockServerBuilder};
+use httpmock::{
+    HttpMockRequest, HttpMockResponse,
+    server::{HttpMockServer, HttpMockServerBuilder},
+};
 use tokio::task::LocalSet;
 mod examples;
 mod matchers;
@@ -10,10 +13,7 @@ mod utils;
 /// The rest of this file is only required to simulate that a standalone mock server is
 /// running somewhere else.
 pub fn with_standalone_server() {
-    let disable_server =
-        std::env::var("HTTPMOCK_TESTS_DISABLE_SIMULATED_STANDALONE_SERVER").unwrap_or_else(|_| "0".to_string());
-
-    if disable_server == "1" {
+    if simulated_standalone_server_disabled() {
         tracing::info!("Skipping creating a simulated mock server.");
         return;
     }
@@ -23,6 +23,15 @@ pub fn with_standalone_server() {
         thread::spawn(move || {
             let srv: HttpMockServer = HttpMockServerBuilder::new()
                 .port(5050)
+                .predicate("is_admin", |req: &HttpMockRequest| {
+                    req.headers().get("x-role").is_some_and(|role| role == "admin")
+                })
+                .responder("echo_path", |req: &HttpMockRequest| {
+                    HttpMockResponse::builder()
+                        .status(200)
+                        .body(req.uri().path().to_string())
+                        .build()
+                })
                 .build()
                 .expect("cannot create mock server");
 
@@ -36,4 +45,10 @@ pub fn with_standalone_server() {
     *started = true
 }
 
+/// Returns true if the tests run against a real standalone mock server instead of the simulated
+/// one. The real server has none of the named predicates and responders registered above.
+pub fn simulated_standalone_server_disabled() -> bool {
+    std::env::var("HTTPMOCK_TESTS_DISABLE_SIMULATED_STANDALONE_SERVER").is_ok_and(|value| value == "1")
+}
+
 static SERVER_STARTED: Mutex<bool> = Mutex::new(false);